@@ -0,0 +1,262 @@
+use std::fs;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::days::{self, Timings};
+
+/// Steps have to slow down by at least this much to regress - faster steps are too noisy to time.
+const MIN_REGRESSION: Duration = Duration::from_millis(1);
+
+/// Timings for one day, in microseconds so they serialize as plain numbers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DayTimings {
+    day: u32,
+    parse_us: u64,
+    part1_us: u64,
+    part2_us: u64,
+}
+
+impl DayTimings {
+    /// Returns timings for the given day.
+    fn new(day: u32, timings: &Timings) -> DayTimings {
+        DayTimings {
+            day,
+            parse_us: timings.parse.as_micros() as u64,
+            part1_us: timings.part1.as_micros() as u64,
+            part2_us: timings.part2.as_micros() as u64,
+        }
+    }
+
+    /// Returns each step's name along with how long it took.
+    fn steps(&self) -> [(&'static str, Duration); 3] {
+        [
+            ("parse", Duration::from_micros(self.parse_us)),
+            ("part 1", Duration::from_micros(self.part1_us)),
+            ("part 2", Duration::from_micros(self.part2_us)),
+        ]
+    }
+}
+
+/// One benchmark run, which times some or all of the days.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    /// When the run happened, in seconds since the Unix epoch.
+    timestamp: u64,
+    days: Vec<DayTimings>,
+}
+
+/// History of benchmark runs, oldest first.  Stored as a JSON list of runs.
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct History {
+    runs: Vec<Run>,
+}
+
+impl History {
+    /// Loads history from the given JSON file.  A missing file is an empty history.
+    pub fn load(filename: &str) -> Result<History, String> {
+        match fs::read_to_string(filename) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| format!("Unable to load '{}': {}", filename, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(format!("Unable to read '{}': {}", filename, e)),
+        }
+    }
+
+    /// Saves this history to the given JSON file.
+    pub fn save(&self, filename: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+
+        fs::write(filename, json + "\n").map_err(|e| format!("Unable to write '{}': {}", filename, e))
+    }
+
+    /// Returns the most recent timings for the given day, if any run timed it.
+    fn previous(&self, day: u32) -> Option<&DayTimings> {
+        self.runs.iter().rev()
+            .flat_map(|run| run.days.iter())
+            .find(|timings| timings.day == day)
+    }
+
+    /// Adds a run to the end of this history.
+    pub fn push(&mut self, run: Run) {
+        self.runs.push(run);
+    }
+}
+
+/// A step of a day's solution that got slower since the previous run.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Regression {
+    day: u32,
+    step: &'static str,
+    previous: Duration,
+    current: Duration,
+}
+
+/// Times each of the given days against its input.txt, keeping the fastest of several runs
+/// of each step.  Prints progress to stderr, since some days take a while.
+pub fn run(days: &[u32], runs: u32) -> Result<Run, String> {
+    let mut timed = Vec::new();
+
+    for &day in days {
+        eprintln!("Timing day {}...", day);
+
+        let input = format!("day{}/input.txt", day);
+        let mut fastest = days::time_day(day, &input)?;
+
+        for _ in 1..runs {
+            let timings = days::time_day(day, &input)?;
+            fastest = Timings {
+                parse: fastest.parse.min(timings.parse),
+                part1: fastest.part1.min(timings.part1),
+                part2: fastest.part2.min(timings.part2),
+            };
+        }
+
+        timed.push(DayTimings::new(day, &fastest));
+    }
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+
+    Ok(Run { timestamp, days: timed })
+}
+
+/// Compares a run to the history before it, and returns every step that's more than
+/// threshold percent slower than the last time its day was timed.
+pub fn regressions(history: &History, run: &Run, threshold: f64) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+
+    for current in &run.days {
+        let last = match history.previous(current.day) {
+            Some(last) => last,
+            None => continue,
+        };
+
+        for (&(step, previous), &(_, duration)) in last.steps().iter().zip(current.steps().iter()) {
+            let limit = previous.mul_f64(1.0 + threshold / 100.0);
+
+            if duration > limit && duration - previous >= MIN_REGRESSION {
+                regressions.push(Regression { day: current.day, step, previous, current: duration });
+            }
+        }
+    }
+
+    regressions
+}
+
+/// Formats a run as a table of timings, followed by any regressions.
+pub fn table(run: &Run, regressions: &[Regression]) -> String {
+    let mut lines = vec![format!("{:<4} {:>10} {:>10} {:>10}", "Day", "Parse", "Part 1", "Part 2")];
+
+    for timings in &run.days {
+        let steps = timings.steps();
+        lines.push(format!("{:<4} {:>10} {:>10} {:>10}",
+                           timings.day, format_duration(steps[0].1), format_duration(steps[1].1), format_duration(steps[2].1)));
+    }
+
+    if !regressions.is_empty() {
+        lines.push(String::new());
+    }
+
+    for regression in regressions {
+        let change = regression.current.as_secs_f64() / regression.previous.as_secs_f64() - 1.0;
+
+        lines.push(format!("REGRESSION day {} {}: {} -> {} (+{:.0}%)",
+                           regression.day, regression.step,
+                           format_duration(regression.previous), format_duration(regression.current),
+                           change * 100.0));
+    }
+
+    lines.join("\n")
+}
+
+/// Formats a duration with a unit that suits its size, like '850 µs', '12.3 ms', or '4.56 s'.
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+
+    if micros < 1_000 {
+        format!("{} µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.1} ms", micros as f64 / 1_000.0)
+    } else {
+        format!("{:.2} s", micros as f64 / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(day: u32, parse_us: u64, part1_us: u64, part2_us: u64) -> DayTimings {
+        DayTimings { day, parse_us, part1_us, part2_us }
+    }
+
+    #[test]
+    fn history_json() {
+        let history = History {
+            runs: vec![Run { timestamp: 1608854400, days: vec![timings(15, 5, 200, 1_500_000)] }],
+        };
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert_eq!(r#"[{"timestamp":1608854400,"days":[{"day":15,"parse_us":5,"part1_us":200,"part2_us":1500000}]}]"#, json);
+        assert_eq!(history, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn load_missing_history() {
+        assert_eq!(History::default(), History::load("missing.json").unwrap());
+    }
+
+    #[test]
+    fn previous_uses_latest_run_with_day() {
+        let history = History {
+            runs: vec![
+                Run { timestamp: 1, days: vec![timings(15, 1, 1, 1), timings(23, 2, 2, 2)] },
+                Run { timestamp: 2, days: vec![timings(15, 3, 3, 3)] },
+            ],
+        };
+
+        assert_eq!(Some(&timings(15, 3, 3, 3)), history.previous(15));
+        assert_eq!(Some(&timings(23, 2, 2, 2)), history.previous(23));
+        assert_eq!(None, history.previous(22));
+    }
+
+    #[test]
+    fn find_regressions() {
+        let history = History {
+            runs: vec![Run { timestamp: 1, days: vec![timings(15, 10, 100_000, 2_000_000), timings(23, 10, 10_000, 10_000)] }],
+        };
+        let run = Run {
+            timestamp: 2,
+            // Day 15 part 1 is 5% slower and part 2 is 50% slower.  Day 23 parsing is 5x slower,
+            // but too fast to count.
+            days: vec![timings(15, 10, 105_000, 3_000_000), timings(23, 50, 9_000, 10_000), timings(24, 1, 1, 1)],
+        };
+
+        assert_eq!(vec![Regression {
+            day: 15,
+            step: "part 2",
+            previous: Duration::from_secs(2),
+            current: Duration::from_secs(3),
+        }], regressions(&history, &run, 10.0));
+    }
+
+    #[test]
+    fn format_table() {
+        let run = Run { timestamp: 2, days: vec![timings(15, 850, 12_340, 4_560_000)] };
+        let regressions = vec![Regression {
+            day: 15,
+            step: "part 2",
+            previous: Duration::from_secs(2),
+            current: Duration::from_millis(4560),
+        }];
+
+        let expected = "Day       Parse     Part 1     Part 2\n\
+                        15       850 µs    12.3 ms     4.56 s\n\
+                        \n\
+                        REGRESSION day 15 part 2: 2.00 s -> 4.56 s (+128%)";
+
+        assert_eq!(expected, table(&run, &regressions));
+    }
+}
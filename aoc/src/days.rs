@@ -0,0 +1,176 @@
+use std::fs;
+use std::hint;
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+
+use common::{Answer, LoadError, Solution};
+
+/// Solver runs one day's Solution without the caller needing to know its type.
+struct Solver {
+    /// Parses the input, then returns the answer to the given part.
+    solve: fn(&str, u32) -> Result<String, SolveError>,
+
+    /// Parses the input and answers both parts, timing each step.
+    time: fn(&str) -> Result<Timings, SolveError>,
+}
+
+/// SolveError is why a day's solution couldn't answer a part: either the input didn't load, or
+/// it loaded but the part has no answer for it.
+enum SolveError {
+    Load(LoadError),
+    NoAnswer { part: u32, message: String },
+}
+
+impl From<LoadError> for SolveError {
+    fn from(e: LoadError) -> Self {
+        SolveError::Load(e)
+    }
+}
+
+impl Solver {
+    /// Returns a Solver for the given Solution.
+    fn of<S: Solution>() -> Solver {
+        Solver { solve: solve::<S>, time: time::<S> }
+    }
+
+    /// Returns the Solver for the given day, or an error if there's no solution for that day.
+    fn for_day(day: u32) -> Result<Solver, String> {
+        Ok(match day {
+            1 => Solver::of::<day1::Day1>(),
+            2 => Solver::of::<day2::Day2>(),
+            3 => Solver::of::<day3::Day3>(),
+            4 => Solver::of::<day4::Day4>(),
+            5 => Solver::of::<day5::Day5>(),
+            6 => Solver::of::<day6::Day6>(),
+            7 => Solver::of::<day7::Day7>(),
+            8 => Solver::of::<day8::Day8>(),
+            9 => Solver::of::<day9::Day9>(),
+            10 => Solver::of::<day10::Day10>(),
+            11 => Solver::of::<day11::Day11>(),
+            12 => Solver::of::<day12::Day12>(),
+            13 => Solver::of::<day13::Day13>(),
+            14 => Solver::of::<day14::Day14>(),
+            15 => Solver::of::<day15::Day15>(),
+            16 => Solver::of::<day16::Day16>(),
+            17 => Solver::of::<day17::Day17>(),
+            18 => Solver::of::<day18::Day18>(),
+            19 => Solver::of::<day19::Day19>(),
+            20 => Solver::of::<day20::Day20>(),
+            21 => Solver::of::<day21::Day21>(),
+            22 => Solver::of::<day22::Day22>(),
+            23 => Solver::of::<day23::Day23>(),
+            24 => Solver::of::<day24::Day24>(),
+            25 => Solver::of::<day25::Day25>(),
+            _ => return Err(format!("There's no solution for day {}.", day)),
+        })
+    }
+}
+
+/// How long each step of a day's solution took.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+/// Runs one part of the given day's solution against the input file, and returns the answer.
+pub fn run(day: u32, part: u32, input: &str) -> Result<String, String> {
+    if part != 1 && part != 2 {
+        return match day {
+            1..=25 => Err(format!("Day {} doesn't have a part {}.", day, part)),
+            _ => Err(format!("There's no solution for day {}.", day)),
+        };
+    }
+
+    let solver = Solver::for_day(day)?;
+
+    (solver.solve)(&read(input)?, part).map_err(|e| solve_error(input, e))
+}
+
+/// Runs both parts of the given day's solution against the input file, and returns how long
+/// parsing the input and answering each part took.
+pub fn time_day(day: u32, input: &str) -> Result<Timings, String> {
+    let solver = Solver::for_day(day)?;
+
+    (solver.time)(&read(input)?).map_err(|e| solve_error(input, e))
+}
+
+/// Parses the puzzle input for a solution, then returns the answer to the given part.
+fn solve<S: Solution>(contents: &str, part: u32) -> Result<String, SolveError> {
+    let solution = S::parse(contents)?;
+
+    let answer = if part == 1 {
+        solution.part1().answer()
+    } else {
+        solution.part2().answer()
+    };
+
+    answer.map_err(|message| SolveError::NoAnswer { part, message })
+}
+
+/// Parses the puzzle input for a solution and answers both parts, timing each step.
+fn time<S: Solution>(contents: &str) -> Result<Timings, SolveError> {
+    let start = Instant::now();
+    let solution = S::parse(contents)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let answer = hint::black_box(solution.part1());
+    let part1 = start.elapsed();
+    answer.answer().map_err(|message| SolveError::NoAnswer { part: 1, message })?;
+
+    let start = Instant::now();
+    let answer = hint::black_box(solution.part2());
+    let part2 = start.elapsed();
+    answer.answer().map_err(|message| SolveError::NoAnswer { part: 2, message })?;
+
+    Ok(Timings { parse, part1, part2 })
+}
+
+/// Describes an error solving a part for the given input file.
+fn solve_error(input: &str, e: SolveError) -> String {
+    let name = if input == "-" { "standard input" } else { input };
+
+    match e {
+        SolveError::Load(e) => format!("Unable to load {}:\n{}", name, e),
+        SolveError::NoAnswer { part, message } => format!("Part {} has no answer for {}:\n{}", part, name, message),
+    }
+}
+
+/// Reads the contents of the given file, or standard input if the file is '-'.
+fn read(input: &str) -> Result<String, String> {
+    if input == "-" {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents)
+            .map_err(|e| format!("Unable to read standard input: {}", e))?;
+
+        return Ok(contents);
+    }
+
+    fs::read_to_string(input).map_err(|e| format!("Unable to read '{}': {}", input, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_part() {
+        assert_eq!(Ok("8".to_owned()), solve::<day8::Day8>("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n", 2).map_err(|e| solve_error("-", e)));
+    }
+
+    #[test]
+    fn solve_without_answer() {
+        let e = solve::<day8::Day8>("jmp +0\njmp +0\n", 2).map_err(|e| solve_error("program.txt", e));
+
+        assert_eq!(Err("Part 2 has no answer for program.txt:\nNo toggled instructions allow the computer to terminate.".to_owned()), e);
+    }
+
+    #[test]
+    fn solve_invalid_input() {
+        let e = solve::<day8::Day8>("jmp\n", 1).map_err(|e| solve_error("-", e));
+
+        assert!(e.unwrap_err().starts_with("Unable to load standard input:\n"));
+    }
+}
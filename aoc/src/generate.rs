@@ -0,0 +1,37 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Generates a random puzzle input for the given day from a seed.  Size sets the day's main
+/// dimension, like the number of instructions or tiles per side, and defaults to about the
+/// size of a real input.
+pub fn generate(day: u32, seed: u64, size: Option<usize>) -> Result<String, String> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+
+    Ok(match day {
+        8 => {
+            let defaults = day8::generate::Params::default();
+            day8::generate::generate(rng, &day8::generate::Params { instructions: size.unwrap_or(defaults.instructions), ..defaults })
+        }
+        14 => {
+            let defaults = day14::generate::Params::default();
+            day14::generate::generate(rng, &day14::generate::Params { masks: size.unwrap_or(defaults.masks), ..defaults })
+        }
+        16 => {
+            let defaults = day16::generate::Params::default();
+            day16::generate::generate(rng, &day16::generate::Params { nearby_tickets: size.unwrap_or(defaults.nearby_tickets), ..defaults })
+        }
+        19 => {
+            let defaults = day19::generate::Params::default();
+            day19::generate::generate(rng, &day19::generate::Params { messages: size.unwrap_or(defaults.messages), ..defaults })
+        }
+        20 => {
+            let defaults = day20::generate::Params::default();
+            day20::generate::generate(rng, &day20::generate::Params { dimension: size.unwrap_or(defaults.dimension), ..defaults })
+        }
+        22 => {
+            let defaults = day22::generate::Params::default();
+            day22::generate::generate(rng, &day22::generate::Params { cards: size.unwrap_or(defaults.cards) })
+        }
+        _ => return Err(format!("There's no input generator for day {}.", day)),
+    })
+}
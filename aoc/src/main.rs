@@ -0,0 +1,527 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use clap::{Parser, Subcommand};
+
+mod bench;
+mod days;
+mod generate;
+mod verify;
+
+/// Changes to a day 19 puzzle's rules.  Override files are applied in order, then patches, then
+/// part 2's recursive rules.
+#[derive(clap::Args)]
+struct RuleOverrides {
+    /// File of rules, one per line like the puzzle's, that replace or add to the puzzle's rules.
+    #[arg(long = "overrides")]
+    override_files: Vec<String>,
+
+    /// A rule that replaces or adds to the puzzle's rules, like '8: 42 | 42 8'.
+    #[arg(long = "patch")]
+    patches: Vec<String>,
+
+    /// Uses part 2's recursive rules 8 and 11.
+    #[arg(long)]
+    recursive: bool,
+}
+
+/// Runs solutions to the 2020 Advent of Code.
+#[derive(Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs one part of a day's solution and prints the answer.
+    Run {
+        /// Day to run, from 1 to 25.
+        #[arg(long)]
+        day: u32,
+
+        /// Part of the day to run, 1 or 2.
+        #[arg(long)]
+        part: u32,
+
+        /// Puzzle input file, or '-' for standard input.  Defaults to the day's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+    },
+
+    /// Runs every solution in the answer registry and checks that it gives the expected answer.
+    Verify {
+        /// Registry of expected answers.
+        #[arg(long, default_value = "answers.toml")]
+        answers: String,
+
+        /// Only verify this day's answers.
+        #[arg(long)]
+        day: Option<u32>,
+    },
+
+    /// Times parsing and each part of every day's solution, and flags regressions since the
+    /// previous run.
+    Bench {
+        /// Only time this day.
+        #[arg(long)]
+        day: Option<u32>,
+
+        /// Number of times to run each day.  The fastest time for each step is kept.
+        #[arg(long, default_value_t = 1)]
+        runs: u32,
+
+        /// JSON file of previous runs.  This run is added to it.
+        #[arg(long, default_value = "bench_history.json")]
+        history: String,
+
+        /// Percent slower than the previous run that counts as a regression.
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+
+    /// Steps through a day 8 boot program interactively, with breakpoints and watches.
+    /// Type 'help' for commands.
+    Debug {
+        /// Boot program to debug.  Defaults to day 8's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+    },
+
+    /// Runs a day 8 boot program until it finishes or repeats an instruction, and prints every
+    /// instruction it ran along with the loop it found.
+    Trace {
+        /// Boot program to trace.  Defaults to day 8's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Output format, 'json' or 'csv'.
+        #[arg(long, default_value = "json")]
+        format: String,
+    },
+
+    /// Assembles a day 8 boot program written with labels and comments, and prints it in the
+    /// form the puzzle reads.
+    Asm {
+        /// Assembly source file.
+        #[arg(long)]
+        input: String,
+    },
+
+    /// Prints a day 8 boot program in canonical form, optionally with labels and jump targets.
+    /// The output can be assembled back into the same program.
+    Disasm {
+        /// Boot program to disassemble.  Defaults to day 8's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Labels jump targets and jumps to the labels instead of by offsets.
+        #[arg(long)]
+        labels: bool,
+
+        /// Follows each jump with a comment giving the pc it jumps to.
+        #[arg(long)]
+        targets: bool,
+    },
+
+    /// Runs an interactive calculator built on day 18's expressions, with variables and a
+    /// switchable precedence mode.  Type ':help' for commands.
+    Calc {
+        /// Precedence mode to start in, 'left-to-right' or 'add-before-times'.
+        #[arg(long, default_value = "add-before-times")]
+        mode: String,
+    },
+
+    /// Explains why a message matches a day 19 rule, with a derivation tree, or why it doesn't,
+    /// with the longest prefix that could match and the rules expected next.
+    Explain {
+        /// Message to explain.
+        message: String,
+
+        /// Puzzle whose rules to match with.  Defaults to day 19's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Rule to match the message against.
+        #[arg(long, default_value_t = 0)]
+        rule: i32,
+
+        #[command(flatten)]
+        rules: RuleOverrides,
+
+        /// Output format for a match, 'tree' or 'dot'.
+        #[arg(long, default_value = "tree")]
+        format: String,
+    },
+
+    /// Counts the messages in a day 19 puzzle that match a rule after overriding rules, or
+    /// prints the puzzle with the overrides applied.
+    Rules {
+        /// Puzzle to match.  Defaults to day 19's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Rule to match messages against.
+        #[arg(long, default_value_t = 0)]
+        rule: i32,
+
+        #[command(flatten)]
+        rules: RuleOverrides,
+
+        /// Prints the puzzle in the format it's read in instead of counting matches.
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Counts the strings of each length that a day 19 rule matches, or lists them shortest
+    /// first.  Works for recursive rules too, up to the maximum length.
+    Language {
+        /// Puzzle whose rules to use.  Defaults to day 19's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Rule to count.
+        #[arg(long, default_value_t = 0)]
+        rule: i32,
+
+        #[command(flatten)]
+        rules: RuleOverrides,
+
+        /// Longest strings to count.
+        #[arg(long, default_value_t = 24)]
+        max_len: usize,
+
+        /// Lists up to this many matching strings instead of counting them.
+        #[arg(long)]
+        strings: Option<usize>,
+    },
+
+    /// Assembles day 20 tiles by backtracking, and prints the tile ids in place along with the
+    /// picture's roughness.  Handles edges that match several tiles and tiles that don't belong.
+    Assemble {
+        /// Tiles to assemble.  Defaults to day 20's input.txt.
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Rows of tiles in the image.  Defaults to the largest square the tiles can fill.
+        #[arg(long)]
+        rows: Option<usize>,
+
+        /// Columns of tiles in the image.  Defaults to the number of rows.
+        #[arg(long)]
+        cols: Option<usize>,
+
+        /// Prints every assembly instead of the first one found.
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Prints a random, valid puzzle input for a day.  Days 8, 14, 16, 19, 20, and 22 have
+    /// generators.
+    Generate {
+        /// Day to generate input for.
+        #[arg(long)]
+        day: u32,
+
+        /// Seed for the random number generator.  The same seed always gives the same input.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Size of the input, like the number of instructions, tickets, or tiles per side.
+        /// Defaults to about the size of a real input.
+        #[arg(long)]
+        size: Option<usize>,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run { day, part, input } => {
+            let input = input.unwrap_or_else(|| format!("day{}/input.txt", day));
+
+            match days::run(day, part, &input) {
+                Ok(answer) => println!("{}", answer),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Verify { answers, day } => {
+            let registry = match verify::Registry::load(&answers) {
+                Ok(registry) => registry,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            };
+
+            // Inputs in the registry are relative to the registry itself.
+            let dir = Path::new(&answers).parent().unwrap_or_else(|| Path::new(""));
+            let checks = registry.verify(dir, day);
+
+            println!("{}", verify::table(&checks));
+
+            let failed = checks.iter().filter(|check| !check.passed()).count();
+            println!("\n{} passed, {} failed", checks.len() - failed, failed);
+
+            if failed > 0 {
+                process::exit(1);
+            }
+        }
+        Command::Bench { day, runs, history, threshold } => {
+            if let Err(e) = run_bench(day, runs, &history, threshold) {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        Command::Debug { input } => {
+            let input = input.unwrap_or_else(|| "day8/input.txt".to_owned());
+
+            if let Err(e) = debug(&input) {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        Command::Trace { input, format } => {
+            let input = input.unwrap_or_else(|| "day8/input.txt".to_owned());
+
+            match trace(&input, &format) {
+                Ok(trace) => print!("{}", trace),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Asm { input } => {
+            match assemble(&input) {
+                Ok(program) => print!("{}", program),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Disasm { input, labels, targets } => {
+            let input = input.unwrap_or_else(|| "day8/input.txt".to_owned());
+
+            match day8::Computer::load(&input) {
+                Ok(computer) => print!("{}", day8::asm::disassemble(computer.instructions(), &day8::asm::Listing { labels, targets })),
+                Err(e) => {
+                    eprintln!("Unable to load {}:\n{}", input, e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Calc { mode } => {
+            if let Err(e) = calc(&mode) {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        Command::Explain { message, input, rule, rules, format } => {
+            let input = input.unwrap_or_else(|| "day19/input.txt".to_owned());
+
+            match explain(&input, &rules, &message, rule, &format) {
+                Ok(explanation) => print!("{}", explanation),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Rules { input, rule, rules, print } => {
+            let input = input.unwrap_or_else(|| "day19/input.txt".to_owned());
+
+            match load_rules(&input, &rules) {
+                Ok(puzzle) if print => print!("{}", puzzle),
+                Ok(puzzle) => {
+                    let matcher = puzzle.grammar().matcher(rule);
+                    if let Some(e @ day19::CompileError::Recursive { .. }) = matcher.compile_error() {
+                        eprintln!("{}  Matching with the general matcher instead of a regular expression.", e);
+                    }
+
+                    println!("{}", puzzle.count_matches(&matcher));
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Language { input, rule, rules, max_len, strings } => {
+            let input = input.unwrap_or_else(|| "day19/input.txt".to_owned());
+
+            match language(&input, &rules, rule, max_len, strings) {
+                Ok(lines) => print!("{}", lines),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Assemble { input, rows, cols, all } => {
+            let input = input.unwrap_or_else(|| "day20/input.txt".to_owned());
+
+            match assemble_tiles(&input, rows, cols, all) {
+                Ok(assemblies) => print!("{}", assemblies),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Generate { day, seed, size } => {
+            match generate::generate(day, seed, size) {
+                Ok(input) => print!("{}", input),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Runs an interactive debugging session on the given day 8 boot program.
+fn debug(input: &str) -> Result<(), String> {
+    let computer = day8::Computer::load(input).map_err(|e| format!("Unable to load {}:\n{}", input, e))?;
+    let mut debugger = day8::debugger::Debugger::new(computer);
+
+    day8::debugger::repl(&mut debugger, io::stdin().lock(), &mut io::stdout())
+        .map_err(|e| format!("Debugger failed: {}", e))
+}
+
+/// Traces the given day 8 boot program, returning the trace in the given format.
+fn trace(input: &str, format: &str) -> Result<String, String> {
+    let mut computer = day8::Computer::load(input).map_err(|e| format!("Unable to load {}:\n{}", input, e))?;
+    let trace = computer.trace_until_loop();
+
+    match format {
+        "json" => Ok(trace.to_json() + "\n"),
+        "csv" => Ok(trace.to_csv()),
+        _ => Err(format!("Unknown format '{}' - expected 'json' or 'csv'.", format)),
+    }
+}
+
+/// Assembles the given day 8 assembly source, returning the program in canonical form.
+fn assemble(input: &str) -> Result<String, String> {
+    let source = fs::read_to_string(input).map_err(|e| format!("Unable to read {}: {}", input, e))?;
+    let computer = day8::Computer::assemble(&source, &day8::OpcodeTable::standard())
+        .map_err(|e| format!("Unable to assemble {}:\n{}", input, e))?;
+
+    Ok(day8::asm::disassemble(computer.instructions(), &day8::asm::Listing::default()))
+}
+
+/// Runs an interactive day 18 calculator, starting in the given precedence mode.
+fn calc(mode: &str) -> Result<(), String> {
+    let mode = mode.parse().map_err(|e| format!("Invalid mode:\n{}", e))?;
+    let mut calc = day18::calc::Calculator::new(mode);
+
+    day18::calc::repl(&mut calc, io::stdin().lock(), &mut io::stdout())
+        .map_err(|e| format!("Calculator failed: {}", e))
+}
+
+/// Loads a day 19 puzzle and applies the rule overrides to it.
+fn load_rules(input: &str, overrides: &RuleOverrides) -> Result<day19::Puzzle, String> {
+    let mut puzzle = day19::Puzzle::load(input).map_err(|e| format!("Unable to load {}:\n{}", input, e))?;
+
+    for file in &overrides.override_files {
+        puzzle.patch_file(file).map_err(|e| format!("Unable to apply overrides from {}:\n{}", file, e))?;
+    }
+
+    for patch in &overrides.patches {
+        puzzle.patch(patch).map_err(|e| format!("Unable to apply patch '{}':\n{}", patch, e))?;
+    }
+
+    if overrides.recursive {
+        puzzle.patch(day19::RECURSIVE_PATCH).map_err(|e| format!("Unable to use recursive rules:\n{}", e))?;
+    }
+
+    Ok(puzzle)
+}
+
+/// Explains how the message matches the given rule of a day 19 puzzle, or why it doesn't.
+fn explain(input: &str, overrides: &RuleOverrides, message: &str, rule: i32, format: &str) -> Result<String, String> {
+    if format != "tree" && format != "dot" {
+        return Err(format!("Unknown format '{}' - expected 'tree' or 'dot'.", format));
+    }
+
+    let puzzle = load_rules(input, overrides)?;
+    let grammar = puzzle.grammar();
+
+    if grammar.rule(rule).is_none() {
+        return Err(format!("{} doesn't have a rule {}.", input, rule));
+    }
+
+    match grammar.explain(rule, message) {
+        day19::Explanation::Match(tree) if format == "dot" => Ok(tree.to_dot()),
+        explanation => Ok(explanation.to_string()),
+    }
+}
+
+/// Returns the number of strings of each length up to max_len that the rule matches, or the
+/// first of the strings if a limit is given.
+fn language(input: &str, overrides: &RuleOverrides, rule: i32, max_len: usize, strings: Option<usize>) -> Result<String, String> {
+    let puzzle = load_rules(input, overrides)?;
+
+    if puzzle.grammar().rule(rule).is_none() {
+        return Err(format!("{} doesn't have a rule {}.", input, rule));
+    }
+
+    let counts = puzzle.grammar().length_counts(max_len);
+
+    let lines: Vec<String> = match strings {
+        Some(limit) => counts.strings(rule).take(limit).collect(),
+        None => counts.counts(rule).iter().enumerate()
+            .filter(|(_, count)| count.bits() != 0)
+            .map(|(len, count)| format!("{:>4}  {}", len, count))
+            .collect(),
+    };
+
+    Ok(lines.iter().map(|line| format!("{}\n", line)).collect())
+}
+
+/// Assembles day 20 tiles into an image with the given shape, returning each assembly's tile
+/// ids and roughness.
+fn assemble_tiles(input: &str, rows: Option<usize>, cols: Option<usize>, all: bool) -> Result<String, String> {
+    let tiles = day20::Tiles::load(input).map_err(|e| format!("Unable to load {}:\n{}", input, e))?;
+
+    let square = day20::Shape::square(tiles.len());
+    let rows = rows.or(cols).unwrap_or(square.rows);
+    let shape = day20::Shape { rows, cols: cols.unwrap_or(rows) };
+
+    let assemblies = if all {
+        tiles.assemble_all(shape)
+    } else {
+        tiles.assemble(shape).map(|assembly| vec![assembly])
+    };
+
+    let assemblies = assemblies.map_err(|e| format!("Unable to assemble {}:\n{}", input, e))?;
+
+    Ok(assemblies.iter()
+        .map(|assembly| format!("{}Roughness: {}\n", assembly, assembly.picture().roughness()))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Times the given day (or every day), prints the timings along with any regressions, and
+/// adds the run to the history file.
+fn run_bench(day: Option<u32>, runs: u32, history_file: &str, threshold: f64) -> Result<(), String> {
+    let mut history = bench::History::load(history_file)?;
+
+    let days = day.map_or_else(|| (1..=25).collect(), |day| vec![day]);
+    let run = bench::run(&days, runs.max(1))?;
+    let regressions = bench::regressions(&history, &run, threshold);
+
+    println!("{}", bench::table(&run, &regressions));
+
+    history.push(run);
+    history.save(history_file)
+}
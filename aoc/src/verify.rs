@@ -0,0 +1,195 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::days;
+
+/// Registry of expected answers, read from a TOML file like:
+///
+/// ```toml
+/// [[answer]]
+/// day = 14
+/// input = "day14/sample.txt"
+/// part1 = "165"
+/// ```
+///
+/// Inputs are relative to the registry file, and default to the day's input.txt.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Registry {
+    #[serde(default, rename = "answer")]
+    answers: Vec<Answer>,
+}
+
+/// Expected answers to a day's puzzle for one input.  Either part can be left out.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+struct Answer {
+    day: u32,
+    input: Option<String>,
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+/// Outcome of running one part of a day's solution and comparing it to the expected answer.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Check {
+    day: u32,
+    part: u32,
+    input: String,
+    expected: String,
+    actual: Result<String, String>,
+}
+
+impl Check {
+    /// Returns whether the solution produced the expected answer.
+    pub fn passed(&self) -> bool {
+        self.actual.as_ref() == Ok(&self.expected)
+    }
+}
+
+impl Registry {
+    /// Loads a registry from the given TOML file.
+    pub fn load(filename: &str) -> Result<Registry, String> {
+        let contents = fs::read_to_string(filename)
+            .map_err(|e| format!("Unable to read '{}': {}", filename, e))?;
+
+        Registry::parse(&contents).map_err(|e| format!("Unable to load '{}': {}", filename, e))
+    }
+
+    /// Parses a registry from TOML.
+    fn parse(contents: &str) -> Result<Registry, String> {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    }
+
+    /// Runs every registered answer (or only the given day's answers), resolving inputs
+    /// relative to the given directory.  Returns the outcome of each check in registry order.
+    pub fn verify(&self, dir: &Path, day: Option<u32>) -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        for answer in self.answers.iter().filter(|answer| day.is_none_or(|day| answer.day == day)) {
+            let input = answer.input.clone().unwrap_or_else(|| format!("day{}/input.txt", answer.day));
+            let path = dir.join(&input);
+
+            for (part, expected) in [(1, &answer.part1), (2, &answer.part2)].iter() {
+                if let Some(expected) = expected {
+                    checks.push(Check {
+                        day: answer.day,
+                        part: *part,
+                        input: input.clone(),
+                        expected: expected.clone(),
+                        actual: days::run(answer.day, *part, &path.to_string_lossy()),
+                    });
+                }
+            }
+        }
+
+        checks
+    }
+}
+
+/// Formats checks as a table with one row per check, followed by any errors that kept
+/// a solution from producing an answer.
+pub fn table(checks: &[Check]) -> String {
+    let headers = ["Day", "Part", "Input", "Expected", "Actual", "Result"];
+
+    let rows: Vec<[String; 6]> = checks.iter()
+        .map(|check| [
+            check.day.to_string(),
+            check.part.to_string(),
+            check.input.clone(),
+            check.expected.clone(),
+            check.actual.clone().unwrap_or_else(|_| "error".to_owned()),
+            if check.passed() { "pass" } else { "FAIL" }.to_owned(),
+        ])
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (i, value) in row.iter().enumerate() {
+            widths[i] = widths[i].max(value.len());
+        }
+    }
+
+    let format_row = |values: Vec<&str>| values.iter().zip(widths.iter())
+        .map(|(value, &width)| format!("{:<width$}", value, width = width))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_owned();
+
+    let mut lines = vec![format_row(headers.to_vec())];
+    lines.extend(rows.iter().map(|row| format_row(row.iter().map(String::as_str).collect())));
+
+    for check in checks {
+        if let Err(e) = &check.actual {
+            lines.push(String::new());
+            lines.push(format!("Day {} part {}: {}", check.day, check.part, e));
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_registry() {
+        let registry = Registry::parse("[[answer]]\nday = 1\npart1 = \"910539\"\n\n\
+                                        [[answer]]\nday = 14\ninput = \"day14/sample.txt\"\npart2 = \"208\"\n").unwrap();
+
+        assert_eq!(Registry {
+            answers: vec![
+                Answer { day: 1, input: None, part1: Some("910539".to_owned()), part2: None },
+                Answer { day: 14, input: Some("day14/sample.txt".to_owned()), part1: None, part2: Some("208".to_owned()) },
+            ]
+        }, registry);
+    }
+
+    #[test]
+    fn parse_invalid_registry() {
+        assert!(Registry::parse("[[answer]]\npart1 = \"910539\"\n").is_err());
+    }
+
+    #[test]
+    fn verify_samples() {
+        let registry = Registry::parse("[[answer]]\nday = 1\ninput = \"day1/sample.txt\"\npart1 = \"514579\"\npart2 = \"1\"\n\n\
+                                        [[answer]]\nday = 2\ninput = \"day2/missing.txt\"\npart1 = \"2\"\n").unwrap();
+
+        let checks = registry.verify(Path::new(".."), None);
+
+        assert_eq!(3, checks.len());
+        assert!(checks[0].passed());
+        assert_eq!(Ok("241861950".to_owned()), checks[1].actual);
+        assert!(!checks[1].passed());
+        assert!(checks[2].actual.is_err());
+    }
+
+    #[test]
+    fn verify_one_day() {
+        let registry = Registry::parse("[[answer]]\nday = 1\ninput = \"day1/sample.txt\"\npart1 = \"514579\"\n\n\
+                                        [[answer]]\nday = 2\ninput = \"day2/sample.txt\"\npart1 = \"2\"\n").unwrap();
+
+        let checks = registry.verify(Path::new(".."), Some(2));
+
+        assert_eq!(1, checks.len());
+        assert_eq!(2, checks[0].day);
+    }
+
+    #[test]
+    fn format_table() {
+        let checks = vec![
+            Check { day: 1, part: 1, input: "day1/input.txt".to_owned(), expected: "7".to_owned(), actual: Ok("7".to_owned()) },
+            Check { day: 12, part: 2, input: "day12/input.txt".to_owned(), expected: "25235".to_owned(), actual: Err("Oops".to_owned()) },
+        ];
+
+        let expected = "Day  Part  Input            Expected  Actual  Result\n\
+                        1    1     day1/input.txt   7         7       pass\n\
+                        12   2     day12/input.txt  25235     error   FAIL\n\
+                        \n\
+                        Day 12 part 2: Oops";
+
+        assert_eq!(expected, table(&checks));
+    }
+}
@@ -0,0 +1,303 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub use sections::{sections, Section, Sections};
+
+mod sections;
+
+/// Solution is a day's puzzle.  It parses the puzzle input once, then answers both parts
+/// of the puzzle from the parsed input.
+pub trait Solution: Sized {
+    /// Type of the answer to part 1.
+    type Part1: Answer;
+
+    /// Type of the answer to part 2.
+    type Part2: Answer;
+
+    /// Parses the puzzle input, returning an error if it's invalid.
+    fn parse(input: &str) -> Result<Self, LoadError>;
+
+    /// Returns the answer to part 1 of the puzzle.
+    fn part1(&self) -> Self::Part1;
+
+    /// Returns the answer to part 2 of the puzzle.
+    fn part2(&self) -> Self::Part2;
+}
+
+/// Answer is what a part of a Solution returns: a value to print, or a Result for parts that
+/// can fail on input that parses but has no answer.
+pub trait Answer {
+    /// Returns the answer as it's printed, or a description of why there isn't one.
+    fn answer(self) -> Result<String, String>;
+}
+
+macro_rules! display_answers {
+    ($($answer:ty),*) => {
+        $(
+            impl Answer for $answer {
+                fn answer(self) -> Result<String, String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_answers!(i32, i64, u32, u64, usize, String, &str);
+
+impl<T: Answer, E: Display> Answer for Result<T, E> {
+    fn answer(self) -> Result<String, String> {
+        self.map_err(|e| e.to_string())?.answer()
+    }
+}
+
+/// LoadError is a problem loading puzzle input - either the input couldn't be read, or it
+/// isn't in the form the puzzle expects.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The input couldn't be read.
+    Io(io::Error),
+
+    /// A line of the input couldn't be parsed.
+    Parse(ParseError),
+
+    /// The input is malformed in a way that isn't tied to a single line, like a missing section.
+    Invalid(String),
+}
+
+impl LoadError {
+    /// Returns an error for input that's malformed, described by the given message.
+    pub fn invalid(message: &str) -> LoadError {
+        LoadError::Invalid(message.to_owned())
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "Unable to read input: {}", e),
+            LoadError::Parse(e) => write!(f, "{}", e),
+            LoadError::Invalid(message) => write!(f, "Invalid input: {}", message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Parse(e) => Some(e),
+            LoadError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl From<ParseError> for LoadError {
+    fn from(e: ParseError) -> Self {
+        LoadError::Parse(e)
+    }
+}
+
+/// ParseError describes text that couldn't be parsed - where the problem is, the text
+/// that contains it, and what was expected instead.  It renders like a compiler diagnostic,
+/// with a caret under the column where parsing failed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    /// Line of the input containing the text, numbered from 1.  Line is 0 when the text was
+    /// parsed on its own, outside of any input.
+    pub line: usize,
+
+    /// Column of the text where parsing failed, numbered from 1.
+    pub column: usize,
+
+    /// Text that couldn't be parsed.
+    pub text: String,
+
+    /// Form that was expected at the column, like "a number" or "'nop', 'acc', or 'jmp'".
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Returns an error for the given text, which has something other than the expected form
+    /// at a column (numbered from 1).
+    pub fn new(text: &str, column: usize, expected: &str) -> ParseError {
+        ParseError {
+            line: 0,
+            column,
+            text: text.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// Returns this error, located on the given line of input (numbered from 1).
+    pub fn at_line(self, line: usize) -> ParseError {
+        ParseError { line, ..self }
+    }
+
+    /// Returns this error, moved right by the given number of columns.  Used when the text
+    /// that failed to parse is part of a larger line.
+    pub fn offset(self, columns: usize, text: &str) -> ParseError {
+        ParseError { column: self.column + columns, text: text.to_owned(), ..self }
+    }
+}
+
+impl Display for ParseError {
+    /// Renders the error like a compiler diagnostic:
+    /// ```text
+    /// error: expected 'nop', 'acc', or 'jmp'
+    ///  --> line 3, column 1
+    ///   |
+    /// 3 | foo +2
+    ///   | ^
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let line_label = if self.line > 0 { self.line.to_string() } else { String::new() };
+        let gutter = " ".repeat(line_label.len().max(1));
+
+        writeln!(f, "error: expected {}", self.expected)?;
+        if self.line > 0 {
+            writeln!(f, "{}--> line {}, column {}", gutter, self.line, self.column)?;
+        } else {
+            writeln!(f, "{}--> column {}", gutter, self.column)?;
+        }
+
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{:>width$} | {}", line_label, self.text, width = gutter.len())?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl Error for ParseError {}
+
+/// Describe turns the error from parsing some text into a ParseError.
+pub trait Describe {
+    /// Returns a ParseError describing why the given text couldn't be parsed.
+    fn describe(self, text: &str) -> ParseError;
+}
+
+impl Describe for ParseError {
+    fn describe(self, _text: &str) -> ParseError {
+        self
+    }
+}
+
+impl Describe for ParseIntError {
+    fn describe(self, text: &str) -> ParseError {
+        ParseError::new(text, 1, "an integer")
+    }
+}
+
+/// Opens the given file for buffered reading.
+pub fn open(filename: &str) -> Result<BufReader<File>, LoadError> {
+    Ok(BufReader::new(File::open(filename)?))
+}
+
+/// Reads every line out of the given reader.
+pub fn read_lines<R: BufRead>(reader: R) -> Result<Vec<String>, LoadError> {
+    reader.lines()
+        .map(|line| line.map_err(LoadError::from))
+        .collect()
+}
+
+/// Parses one value per line out of the given reader.
+pub fn parse_lines<T, R>(reader: R) -> Result<Vec<T>, LoadError>
+    where T: FromStr, T::Err: Describe, R: BufRead {
+    reader.lines().enumerate()
+        .map(|(i, line)| {
+            let line = line?;
+            line.parse::<T>().map_err(|e| LoadError::Parse(e.describe(&line).at_line(i + 1)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        assert_eq!(Ok("-3".to_owned()), (-3).answer());
+        assert_eq!(Ok("abc".to_owned()), "abc".answer());
+        assert_eq!(Ok("12".to_owned()), Ok::<usize, LoadError>(12).answer());
+        assert_eq!(Err("Invalid input: No answer.".to_owned()), Err::<usize, LoadError>(LoadError::invalid("No answer.")).answer());
+    }
+
+    #[test]
+    fn parse_lines_valid() {
+        let nums: Vec<i32> = parse_lines("1\n22\n-3\n".as_bytes()).unwrap();
+        assert_eq!(vec![1, 22, -3], nums);
+    }
+
+    #[test]
+    fn parse_lines_invalid() {
+        let result: Result<Vec<i32>, LoadError> = parse_lines("1\n2x\n3\n".as_bytes());
+
+        match result {
+            Err(LoadError::Parse(e)) => {
+                assert_eq!(2, e.line);
+                assert_eq!("2x", e.text);
+            }
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn render_parse_error() {
+        let e = ParseError::new("foo +2", 1, "'nop', 'acc', or 'jmp'").at_line(3);
+        let expected = "error: expected 'nop', 'acc', or 'jmp'\n \
+                        --> line 3, column 1\n  \
+                        |\n\
+                        3 | foo +2\n  \
+                        | ^";
+
+        assert_eq!(expected, e.to_string());
+    }
+
+    #[test]
+    fn render_parse_error_wide_line() {
+        let e = ParseError::new("mem[8] = x1", 10, "a number").at_line(120);
+        let expected = "error: expected a number\n   \
+                        --> line 120, column 10\n    \
+                        |\n\
+                        120 | mem[8] = x1\n    \
+                        |          ^";
+
+        assert_eq!(expected, e.to_string());
+    }
+
+    #[test]
+    fn render_parse_error_without_line() {
+        let e = ParseError::new("#.x", 3, "'#' or '.'");
+        let expected = "error: expected '#' or '.'\n \
+                        --> column 3\n  \
+                        |\n  \
+                        | #.x\n  \
+                        |   ^";
+
+        assert_eq!(expected, e.to_string());
+    }
+
+    #[test]
+    fn offset_parse_error() {
+        let e = ParseError::new("x1", 1, "a number").offset(9, "mem[8] = x1");
+
+        assert_eq!(ParseError::new("mem[8] = x1", 10, "a number"), e);
+    }
+
+    #[test]
+    fn open_missing_file() {
+        let result = open("missing.txt");
+
+        assert!(matches!(result, Err(LoadError::Io(_))));
+    }
+}
@@ -0,0 +1,169 @@
+use std::io::{BufRead, Lines};
+use std::iter::Enumerate;
+use std::str::FromStr;
+
+use crate::{Describe, LoadError, ParseError};
+
+/// Section is a group of consecutive non-blank lines in the input, like a tile or a player's
+/// deck.  Lines keep their indentation, but trailing whitespace is trimmed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Section {
+    /// Line of the input that the section starts on, numbered from 1.
+    pub line: usize,
+
+    /// Lines in the section, in input order.
+    pub lines: Vec<String>,
+}
+
+impl Section {
+    /// Returns the lines in this section along with their line numbers.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.lines.iter().enumerate().map(move |(i, line)| (self.line + i, line.as_str()))
+    }
+
+    /// Returns the lines after this section's header along with their line numbers.
+    pub fn body(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.lines().skip(1)
+    }
+
+    /// Parses this section's header, which is a value between a prefix and a suffix like
+    /// 'Tile 2311:' or 'Player 1:'.
+    pub fn header<T>(&self, prefix: &str, suffix: &str) -> Result<T, ParseError>
+        where T: FromStr, T::Err: Describe {
+        let header = &self.lines[0];
+        let parse_err = |column: usize, expected: &str| ParseError::new(header, column, expected).at_line(self.line);
+
+        let rest = header.strip_prefix(prefix).ok_or_else(|| parse_err(1, &format!("'{}'", prefix)))?;
+        let value = rest.strip_suffix(suffix).ok_or_else(|| parse_err(header.len() + 1, &format!("'{}'", suffix)))?;
+
+        value.parse().map_err(|e: T::Err| e.describe(value).offset(prefix.len(), header).at_line(self.line))
+    }
+
+    /// Checks that this section's header is the given title, like 'your ticket:'.
+    pub fn title(&self, title: &str) -> Result<(), ParseError> {
+        if self.lines[0].trim() == title {
+            Ok(())
+        } else {
+            Err(ParseError::new(&self.lines[0], 1, &format!("'{}'", title)).at_line(self.line))
+        }
+    }
+
+    /// Parses one value from each line in this section.
+    pub fn parse<T>(&self) -> Result<Vec<T>, LoadError>
+        where T: FromStr, T::Err: Describe {
+        parse_numbered(self.lines())
+    }
+
+    /// Parses one value from each line after this section's header.
+    pub fn parse_body<T>(&self) -> Result<Vec<T>, LoadError>
+        where T: FromStr, T::Err: Describe {
+        parse_numbered(self.body())
+    }
+}
+
+/// Parses one value from each of the given numbered lines, ignoring indentation.
+fn parse_numbered<'a, T, I>(lines: I) -> Result<Vec<T>, LoadError>
+    where T: FromStr, T::Err: Describe, I: Iterator<Item = (usize, &'a str)> {
+    lines
+        .map(|(number, line)| {
+            let value = line.trim_start();
+            let indent = line.len() - value.len();
+
+            value.parse::<T>().map_err(|e| LoadError::Parse(e.describe(value).offset(indent, line).at_line(number)))
+        })
+        .collect()
+}
+
+/// Sections reads blank-line-separated sections out of a reader, one at a time.  Any number
+/// of blank lines can separate sections, and blank lines at the start or end are ignored.
+pub struct Sections<R> {
+    lines: Enumerate<Lines<R>>,
+}
+
+impl<R: BufRead> Iterator for Sections<R> {
+    type Item = Result<Section, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut section: Option<Section> = None;
+
+        for (i, line) in &mut self.lines {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(LoadError::from(e))),
+            };
+
+            if line.trim().is_empty() {
+                if section.is_some() {
+                    break;
+                }
+
+                continue;
+            }
+
+            section.get_or_insert_with(|| Section { line: i + 1, lines: Vec::new() })
+                .lines.push(line.trim_end().to_owned());
+        }
+
+        section.map(Ok)
+    }
+}
+
+/// Returns an iterator over the blank-line-separated sections in the given reader.
+pub fn sections<R: BufRead>(reader: R) -> Sections<R> {
+    Sections { lines: reader.lines().enumerate() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_sections(input: &str) -> Vec<Section> {
+        sections(input.as_bytes()).collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn read_sections_skips_blank_lines() {
+        let expected = vec![
+            Section { line: 2, lines: vec!["abc".to_owned(), "  de".to_owned()] },
+            Section { line: 6, lines: vec!["f".to_owned()] },
+        ];
+
+        assert_eq!(expected, read_sections("\nabc\n  de  \n\n \nf\n\n"));
+    }
+
+    #[test]
+    fn read_sections_without_trailing_newline() {
+        assert_eq!(vec![Section { line: 1, lines: vec!["a".to_owned(), "b".to_owned()] }], read_sections("a\nb"));
+        assert_eq!(Vec::<Section>::new(), read_sections(""));
+    }
+
+    #[test]
+    fn parse_header() {
+        let section = &read_sections("\nTile 2311:\n..#\n")[0];
+
+        assert_eq!(Ok(2311), section.header::<i32>("Tile ", ":"));
+        assert_eq!(vec![(3, "..#")], section.body().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parse_invalid_header() {
+        let section = &read_sections("Tile 23x1:")[0];
+
+        assert_eq!(Err(ParseError::new("Tile 23x1:", 1, "'Player '").at_line(1)), section.header::<i32>("Player ", ":"));
+        assert_eq!(Err(ParseError::new("Tile 23x1:", 11, "'.'").at_line(1)), section.header::<i32>("Tile ", "."));
+        assert_eq!(Err(ParseError::new("Tile 23x1:", 6, "an integer").at_line(1)), section.header::<i32>("Tile ", ":"));
+    }
+
+    #[test]
+    fn parse_body() {
+        let section = &read_sections("your ticket:\n 7\n1\n")[0];
+
+        assert_eq!(Ok(()), section.title("your ticket:"));
+        assert_eq!(vec![7, 1], section.parse_body::<i32>().unwrap());
+
+        match section.parse::<i32>() {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("your ticket:", 1, "an integer").at_line(1), e),
+            _ => panic!("Expected a parse error."),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::BufRead;
+use itertools::Itertools;
+use common::{LoadError, Solution};
+
+/// Loads numbers out of the given file, returning an error if the file doesn't exist or is invalid.
+pub fn load_input(filename: &str) -> Result<Vec<i32>, LoadError> {
+    read_input(common::open(filename)?)
+}
+
+/// Reads numbers from the given reader, one per line.
+pub fn read_input<R: BufRead>(reader: R) -> Result<Vec<i32>, LoadError> {
+    common::parse_lines(reader)
+}
+
+/// Finds two numbers that sum to 2020 and returns their product.
+pub fn find_two_2020_product(numbers: &[i32]) -> i32 {
+    for number in numbers {
+        let complement = 2020 - number;
+        if numbers.contains(&complement) {
+            return number * complement;
+        }
+    }
+
+    0
+}
+
+/// Finds three numbers that sum to 2020 and returns their product.
+pub fn find_three_2020_product(numbers: &[i32]) -> i32 {
+    for combo in numbers.iter().combinations(3) {
+        if combo.iter().copied().sum::<i32>() == 2020 {
+            return combo.iter().copied().product();
+        }
+    }
+
+    0
+}
+
+pub struct Day1 {
+    numbers: Vec<i32>,
+}
+
+impl Solution for Day1 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day1 { numbers: read_input(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> i32 {
+        find_two_2020_product(&self.numbers)
+    }
+
+    fn part2(&self) -> i32 {
+        find_three_2020_product(&self.numbers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_load_input() {
+        let numbers = load_input("input.txt").unwrap();
+        assert!(!numbers.is_empty());
+    }
+
+    #[test]
+    fn test_read_input_invalid() {
+        assert!(read_input("1721\n979\nabc\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_find_two_2020() {
+        let numbers = load_input("sample.txt").unwrap();
+        assert_eq!(514579, find_two_2020_product(&numbers));
+    }
+
+    #[test]
+    fn test_find_three_2020() {
+        let numbers = load_input("sample.txt").unwrap();
+        assert_eq!(241861950, find_three_2020_product(&numbers));
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day1::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(514579, day.part1());
+        assert_eq!(241861950, day.part2());
+    }
+}
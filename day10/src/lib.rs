@@ -0,0 +1,192 @@
+use std::io::BufRead;
+use common::{LoadError, Solution};
+
+/// Loads jolts from the given file.
+pub fn load(filename: &str) -> Result<Vec<i32>, LoadError> {
+    read(common::open(filename)?)
+}
+
+/// Reads jolts from the given reader, one per line.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<i32>, LoadError> {
+    common::parse_lines(reader)
+}
+
+/// Returns the product of the 1-jolt differences multiplied by the number of 3-jolt differences.
+pub fn differences(jolts: &[i32]) -> i32 {
+    let mut sorted_jolts = jolts.to_vec();
+
+    sorted_jolts.sort();
+
+    sorted_jolts.insert(0, 0);
+    sorted_jolts.push(sorted_jolts[sorted_jolts.len() - 1] + 3);
+
+    let differences: Vec<i32> = sorted_jolts.windows(2)
+        .map(|t| t[1] - t[0])
+        .collect();
+
+    let ones = differences.iter().filter(|&d| *d == 1).count();
+    let threes = differences.iter().filter(|&d| *d == 3).count();
+
+    (ones * threes) as i32
+}
+
+/// Returns the number of valid ways that the jolt adapters can be combined.  Adapters can
+/// transform up to 3 jolts.
+pub fn combinations(jolts: &[i32]) -> i64 {
+    let mut sorted_jolts = jolts.to_vec();
+
+    sorted_jolts.sort();
+
+    // Seat is 0 jolts and device is biggest adapter + 3
+    sorted_jolts.insert(0, 0);
+    sorted_jolts.push(sorted_jolts[sorted_jolts.len() - 1] + 3);
+
+    let differences: Vec<i32> = sorted_jolts.windows(2)
+        .map(|t| t[1] - t[0])
+        .collect();
+
+    let mut consecutive_ones = Vec::new();
+    let mut ones = 0;
+
+    for difference in &differences {
+        if *difference == 1 {
+            ones += 1;
+        } else if ones > 0 {
+            consecutive_ones.push(ones);
+            ones = 0;
+        }
+    }
+
+    // A run of n 1-jolt differences can skip any adapters inside it as long as no gap is bigger
+    // than 3, which is the tribonacci sequence: 1, 1, 2, 4, 7, 13, ...
+    consecutive_ones.iter()
+        .map(|&consecutive| run_combinations(consecutive))
+        .product()
+}
+
+/// Returns the number of ways to combine a run of adapters separated by the given number of
+/// 1-jolt differences.
+fn run_combinations(ones: usize) -> i64 {
+    let (mut a, mut b, mut c) = (0, 0, 1);
+
+    for _ in 0..ones {
+        let next = a + b + c;
+        a = b;
+        b = c;
+        c = next;
+    }
+
+    c
+}
+
+pub struct Day10 {
+    jolts: Vec<i32>,
+}
+
+impl Solution for Day10 {
+    type Part1 = i32;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day10 { jolts: read(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> i32 {
+        differences(&self.jolts)
+    }
+
+    fn part2(&self) -> i64 {
+        combinations(&self.jolts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differences_sample() {
+        let jolts = load("sample.txt").unwrap();
+
+        assert_eq!(35, differences(&jolts));
+    }
+
+    #[test]
+    fn differences_sample2() {
+        let jolts = load("sample2.txt").unwrap();
+
+        assert_eq!(220, differences(&jolts));
+    }
+
+    #[test]
+    fn combinations_sample() {
+        let jolts = load("sample.txt").unwrap();
+
+        assert_eq!(8, combinations(&jolts));
+    }
+
+    #[test]
+    fn combinations_sample2() {
+        let jolts = load("sample2.txt").unwrap();
+
+        assert_eq!(19208, combinations(&jolts));
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day10::parse(&std::fs::read_to_string("sample2.txt").unwrap()).unwrap();
+
+        assert_eq!(220, day.part1());
+        assert_eq!(19208, day.part2());
+    }
+}
+
+#[cfg(test)]
+mod combinations_properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Counts combinations by trying every subset of the adapters.
+    fn brute_force_combinations(jolts: &[i32]) -> i64 {
+        let mut sorted_jolts = jolts.to_vec();
+        sorted_jolts.sort();
+
+        let mut count = 0;
+        for subset in 0..1_u32 << sorted_jolts.len() {
+            let mut chain = vec![0];
+            chain.extend((0..sorted_jolts.len()).filter(|i| subset & (1 << i) != 0).map(|i| sorted_jolts[i]));
+            chain.push(sorted_jolts[sorted_jolts.len() - 1] + 3);
+
+            if chain.windows(2).all(|pair| pair[1] - pair[0] <= 3) {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Adapters in a random order, where sorted adapters differ by 1 or 3 jolts like the puzzle input.
+    fn adapters() -> impl Strategy<Value = Vec<i32>> {
+        prop::collection::vec(prop_oneof![Just(1), Just(3)], 1..=16)
+            .prop_map(|differences| differences.iter()
+                .scan(0, |jolts, difference| {
+                    *jolts += difference;
+                    Some(*jolts)
+                })
+                .collect::<Vec<i32>>())
+            .prop_shuffle()
+    }
+
+    proptest! {
+        #[test]
+        fn combinations_matches_brute_force(jolts in adapters()) {
+            prop_assert_eq!(brute_force_combinations(&jolts), combinations(&jolts));
+        }
+    }
+
+    #[test]
+    fn combinations_long_run() {
+        assert_eq!(13, combinations(&[1, 2, 3, 4, 5]));
+    }
+}
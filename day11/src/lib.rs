@@ -1,13 +1,12 @@
 use core::fmt;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::str::FromStr;
 
-use crate::Seat::{Empty, Floor, Occupied};
+use common::{LoadError, ParseError, Solution};
+use grid::Neighborhood;
 
-#[derive(Debug, Eq, PartialEq)]
-struct ParseErr {}
+use crate::Seat::{Empty, Floor, Occupied};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Seat {
@@ -17,14 +16,14 @@ enum Seat {
 }
 
 impl FromStr for Seat {
-    type Err = ParseErr;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "L" => Ok(Empty),
             "#" => Ok(Occupied),
             "." => Ok(Floor),
-            _ => Err(ParseErr{}),
+            _ => Err(ParseError::new(s, 1, "'L', '#', or '.'")),
         }
     }
 }
@@ -50,40 +49,32 @@ mod seat_tests {
         assert_eq!(Ok(Empty), "L".parse());
         assert_eq!(Ok(Occupied), "#".parse());
         assert_eq!(Ok(Floor), ".".parse());
-        assert_eq!(Err(ParseErr {}), "invalid".parse::<Seat>());
+        assert_eq!(Err(ParseError::new("invalid", 1, "'L', '#', or '.'")), "invalid".parse::<Seat>());
     }
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Eq, PartialEq, Clone)]
 pub struct Grid {
-    seats: Vec<Vec<Seat>>
+    seats: grid::Grid<Seat>,
 }
 
 impl Display for Grid {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        for line in self.seats.clone() {
-            for seat in line {
-                write!(f, "{}", seat)?;
-            }
-
-            writeln!(f, "")?;
-        }
-
-        Ok(())
+        write!(f, "{}", self.seats)
     }
 }
 
 impl Grid {
     /// Loads a grid from the given file.
-    pub fn load(filename: &str) -> Grid {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
+    pub fn load(filename: &str) -> Result<Grid, LoadError> {
+        Grid::read(common::open(filename)?)
+    }
 
-        let seats: Vec<Vec<Seat>> = f.lines()
-            .map(|line| line.unwrap().chars().map(|c| c.to_string().parse().unwrap()).collect())
-            .collect();
+    /// Reads a grid from the given reader.
+    pub fn read<R: BufRead>(reader: R) -> Result<Grid, LoadError> {
+        let seats = grid::Grid::read(reader, "'L', '#', or '.'", |c| c.to_string().parse::<Seat>().ok())?;
 
-        Grid { seats }
+        Ok(Grid { seats })
     }
 
     /// Applies seating rules to the grid, advancing it one round.  Returns the number
@@ -92,25 +83,23 @@ impl Grid {
         let mut new_seats = self.seats.clone();
         let mut num_changed = 0;
 
-        for row in 0..self.seats.len() {
-            for col in 0..self.seats[row].len() {
-                let occupied = self.adjacent(row, col);
-                let current_seat = self.seats[row][col];
-
-                // Rules:
-                // * Seat becomes occupied if it's empty and there are no adjacent occupied seats
-                // * Seat becomes empty if it's occupied and there are 4+ occupied seats.
-                // * Seat state does not change otherwise.
-                let new_seat = match current_seat {
-                    Empty if occupied == 0 => Occupied,
-                    Occupied if occupied >= 4 => Empty,
-                    _ => current_seat,
-                };
-
-                if new_seat != current_seat {
-                    num_changed += 1;
-                    new_seats[row][col] = new_seat;
-                }
+        for (row, col) in self.seats.positions() {
+            let occupied = self.adjacent(row, col);
+            let current_seat = self.seats[(row, col)];
+
+            // Rules:
+            // * Seat becomes occupied if it's empty and there are no adjacent occupied seats
+            // * Seat becomes empty if it's occupied and there are 4+ occupied seats.
+            // * Seat state does not change otherwise.
+            let new_seat = match current_seat {
+                Empty if occupied == 0 => Occupied,
+                Occupied if occupied >= 4 => Empty,
+                _ => current_seat,
+            };
+
+            if new_seat != current_seat {
+                num_changed += 1;
+                new_seats[(row, col)] = new_seat;
             }
         }
 
@@ -119,25 +108,11 @@ impl Grid {
         num_changed
     }
 
+    /// Returns the number of occupied seats next to the given seat, including diagonals.
     fn adjacent(&self, row: usize, col: usize) -> usize {
-        let lower_row = row.checked_sub(1).unwrap_or(row);
-        let upper_row = usize::min(row + 1, self.seats.len() - 1);
-
-        let lower_col = col.checked_sub(1).unwrap_or(col);
-        let upper_col = usize::min(col + 1, self.seats[row].len() - 1);
-
-        let mut occupied = 0;
-        for r in lower_row ..= upper_row {
-            for c in lower_col ..= upper_col {
-                if r != row || c != col {
-                    if self.seats[r][c] == Occupied {
-                        occupied += 1;
-                    }
-                }
-            }
-        }
-
-        occupied
+        self.seats.neighbors(row, col, Neighborhood::Eight).into_iter()
+            .filter(|&position| self.seats[position] == Occupied)
+            .count()
     }
 
     /// Applies adjacent seating rules to the grid repeatedly until no more seats change state.
@@ -151,25 +126,23 @@ impl Grid {
         let mut new_seats = self.seats.clone();
         let mut num_changed = 0;
 
-        for row in 0..self.seats.len() {
-            for col in 0..self.seats[row].len() {
-                let occupied = self.visible(row, col);
-                let current_seat = self.seats[row][col];
-
-                // Rules:
-                // * Seat becomes occupied if it's empty and there are no visible occupied seats
-                // * Seat becomes empty if it's occupied and there are 5+ occupied seats.
-                // * Seat state does not change otherwise.
-                let new_seat = match current_seat {
-                    Empty if occupied == 0 => Occupied,
-                    Occupied if occupied >= 5 => Empty,
-                    _ => current_seat,
-                };
-
-                if new_seat != current_seat {
-                    num_changed += 1;
-                    new_seats[row][col] = new_seat;
-                }
+        for (row, col) in self.seats.positions() {
+            let occupied = self.visible(row, col);
+            let current_seat = self.seats[(row, col)];
+
+            // Rules:
+            // * Seat becomes occupied if it's empty and there are no visible occupied seats
+            // * Seat becomes empty if it's occupied and there are 5+ occupied seats.
+            // * Seat state does not change otherwise.
+            let new_seat = match current_seat {
+                Empty if occupied == 0 => Occupied,
+                Occupied if occupied >= 5 => Empty,
+                _ => current_seat,
+            };
+
+            if new_seat != current_seat {
+                num_changed += 1;
+                new_seats[(row, col)] = new_seat;
             }
         }
 
@@ -180,33 +153,10 @@ impl Grid {
 
     /// Returns the number of visible occupied seats in all directions.
     fn visible(&self, row: usize, col: usize) -> usize {
-        let mut occupied = 0;
-
-        for row_dir in -1..=1 {
-            for col_dir in -1..=1 {
-                if row_dir == 0 && col_dir == 0 {
-                    continue;
-                }
-
-                let mut dist = 1;
-                let mut square = self.get(
-                    row as i32 + row_dir * dist,
-                    col as i32 + col_dir * dist);
-
-                while square == Some(Floor) {
-                    dist += 1;
-                    square = self.get(
-                        row as i32 + row_dir * dist,
-                        col as i32 + col_dir * dist);
-                }
-
-                if square == Some(Occupied) {
-                    occupied += 1;
-                }
-            }
-        }
-
-        occupied
+        // Floor doesn't block the view of the seats beyond it.
+        Neighborhood::Eight.directions().iter()
+            .filter(|&&direction| self.seats.cast(row, col, direction, |&seat| seat == Floor) == Some(&Occupied))
+            .count()
     }
 
     /// Applies visible seating rules to the grid repeatedly until no more seats change state.
@@ -217,25 +167,35 @@ impl Grid {
     /// Returns the number of occupied seats in this grid.
     pub fn num_occupied(&self) -> usize {
         self.seats.iter()
-            .flat_map(|row| row.iter())
             .filter(|&seat| *seat == Occupied)
             .count()
     }
+}
 
-    /// Returns the seat at the given row and column, or None if they're out of bounds.
-    fn get(&self, row: i32, col: i32) -> Option<Seat> {
-        if row < 0 || col < 0 {
-            return None;
-        }
+pub struct Day11 {
+    grid: Grid,
+}
 
-        let row = row as usize;
-        let col = col as usize;
+impl Solution for Day11 {
+    type Part1 = usize;
+    type Part2 = usize;
 
-        if row >= self.seats.len() || col >= self.seats[row].len() {
-            return None;
-        }
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day11 { grid: Grid::read(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        let mut grid = self.grid.clone();
+        grid.adjacent_tick_until_stable();
 
-        Some(self.seats[row][col])
+        grid.num_occupied()
+    }
+
+    fn part2(&self) -> usize {
+        let mut grid = self.grid.clone();
+        grid.visible_tick_until_stable();
+
+        grid.num_occupied()
     }
 }
 
@@ -245,15 +205,23 @@ mod grid_tests {
 
     #[test]
     fn load_sample() {
-        let grid = Grid::load("sample.txt");
+        let grid = Grid::load("sample.txt").unwrap();
+
+        assert_eq!(10, grid.seats.height());
+        assert_eq!(vec![Empty, Floor, Empty, Empty, Floor, Empty, Empty, Floor, Empty, Empty], grid.seats.rows().next().unwrap());
+    }
 
-        assert_eq!(10, grid.seats.len());
-        assert_eq!(vec![Empty, Floor, Empty, Empty, Floor, Empty, Empty, Floor, Empty, Empty], grid.seats[0]);
+    #[test]
+    fn read_invalid_seat() {
+        match Grid::read("L.L\nLL#\nL?L\n".as_bytes()) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("L?L", 2, "'L', '#', or '.'").at_line(3), e),
+            _ => panic!("Expected a parse error."),
+        }
     }
 
     #[test]
     fn tick_adjacent_sample() {
-        let mut grid = Grid::load("sample.txt");
+        let mut grid = Grid::load("sample.txt").unwrap();
 
         // Tick 1: all seats become occupied.
         assert_eq!(71, grid.tick_adjacent());
@@ -266,7 +234,7 @@ mod grid_tests {
 
     #[test]
     fn tick_visible_sample() {
-        let mut grid = Grid::load("sample.txt");
+        let mut grid = Grid::load("sample.txt").unwrap();
 
         // Tick 1: all seats become occupied.
         assert_eq!(71, grid.tick_visible());
@@ -276,4 +244,12 @@ mod grid_tests {
         assert_eq!(64, grid.tick_visible());
         assert_eq!(7, grid.num_occupied());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solution_sample() {
+        let day = Day11::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(37, day.part1());
+        assert_eq!(26, day.part2());
+    }
+}
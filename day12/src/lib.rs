@@ -0,0 +1,354 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use common::{LoadError, ParseError, Solution};
+
+use crate::Heading::{East, North, South, West};
+use crate::Instruction::{E, F, L, N, R, S, W};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Instruction {
+    N(i32),
+    S(i32),
+    E(i32),
+    W(i32),
+    R(i32),
+    L(i32),
+    F(i32),
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    /// Parses an instruction, which is an action followed by a value like 'F10'.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let instruction: fn(i32) -> Instruction = match s.chars().next() {
+            Some('N') => N,
+            Some('S') => S,
+            Some('E') => E,
+            Some('W') => W,
+            Some('R') => R,
+            Some('L') => L,
+            Some('F') => F,
+
+            _ => return Err(ParseError::new(s, 1, "an action (N, S, E, W, L, R, or F)")),
+        };
+
+        let value = s[1..].parse().map_err(|_| ParseError::new(s, 2, "a number"))?;
+
+        Ok(instruction(value))
+    }
+}
+
+/// Loads instructions from the given file, returning an error if it doesn't exist or can't be loaded.
+pub fn load_instructions(filename: &str) -> Result<Vec<Instruction>, LoadError> {
+    read_instructions(common::open(filename)?)
+}
+
+/// Reads instructions from the given reader, one per line.
+pub fn read_instructions<R: BufRead>(reader: R) -> Result<Vec<Instruction>, LoadError> {
+    common::parse_lines(reader)
+}
+
+#[cfg(test)]
+mod instruction_tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(N(3)), "N3".parse());
+        assert_eq!(Ok(S(10)), "S10".parse());
+        assert_eq!(Ok(E(4)), "E4".parse());
+        assert_eq!(Ok(W(2)), "W2".parse());
+        assert_eq!(Ok(L(90)), "L90".parse());
+        assert_eq!(Ok(R(180)), "R180".parse());
+        assert_eq!(Ok(F(7)), "F7".parse());
+
+        assert_eq!(Err(ParseError::new("", 1, "an action (N, S, E, W, L, R, or F)")), "".parse::<Instruction>());
+        assert_eq!(Err(ParseError::new("F", 2, "a number")), "F".parse::<Instruction>());
+        assert_eq!(Err(ParseError::new("X3", 1, "an action (N, S, E, W, L, R, or F)")), "X3".parse::<Instruction>());
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Heading {
+    North, South, East, West,
+}
+
+impl Heading {
+    /// Returns this heading as degrees.  North starts at 0, and degrees go clockwise (East is 90).
+    fn to_degrees(&self) -> i32 {
+        match self {
+            North => 0,
+            East => 90,
+            South => 180,
+            West => 270,
+        }
+    }
+
+    /// Converts the given degrees into a heading.
+    fn from_degrees(degrees: i32) -> Heading {
+        match (360 + degrees) % 360 {
+            0 => North,
+            90 => East,
+            180 => South,
+            270 => West,
+
+            _ => panic!("Invalid degrees: {}", degrees),
+        }
+    }
+}
+
+#[cfg(test)]
+mod heading_tests {
+    use crate::Heading::{North, South, West};
+
+    use super::*;
+
+    #[test]
+    fn to_degrees() {
+        assert_eq!(0, North.to_degrees());
+        assert_eq!(90, East.to_degrees());
+        assert_eq!(180, South.to_degrees());
+        assert_eq!(270, West.to_degrees());
+    }
+
+    #[test]
+    fn from_degrees() {
+        assert_eq!(North, Heading::from_degrees(-360));
+        assert_eq!(East, Heading::from_degrees(-270));
+        assert_eq!(South, Heading::from_degrees(-180));
+        assert_eq!(West, Heading::from_degrees(-90));
+
+        assert_eq!(North, Heading::from_degrees(0));
+        assert_eq!(East, Heading::from_degrees(90));
+        assert_eq!(South, Heading::from_degrees(180));
+        assert_eq!(West, Heading::from_degrees(270));
+
+        assert_eq!(North, Heading::from_degrees(360));
+        assert_eq!(East, Heading::from_degrees(450));
+        assert_eq!(South, Heading::from_degrees(540));
+        assert_eq!(West, Heading::from_degrees(630));
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Ship {
+    heading: Heading,
+    x: i32,
+    y: i32,
+}
+
+impl Ship {
+    /// Returns a new ship which starts at 0,0 facing East.
+    fn new() -> Ship {
+        Ship { heading: East, x: 0, y: 0 }
+    }
+
+    /// Runs this instruction, changing this ship in place
+    fn run(&mut self, instruction: &Instruction) {
+        match instruction {
+            N(distance) => self.y += distance,
+            S(distance) => self.y -= distance,
+            E(distance) => self.x += distance,
+            W(distance) => self.x -= distance,
+
+            R(degrees) =>
+                self.heading = Heading::from_degrees(self.heading.to_degrees() + degrees),
+
+            L(degrees) =>
+                self.heading = Heading::from_degrees(self.heading.to_degrees() - degrees),
+
+            F(distance) => {
+                match self.heading {
+                    North => self.run(&N(*distance)),
+                    South => self.run(&S(*distance)),
+                    East => self.run(&E(*distance)),
+                    West => self.run(&W(*distance)),
+                }
+            }
+        }
+    }
+
+    /// Returns the Manhattan distance between the ships starting position 0,0 and its current position.
+    fn distance(&self) -> i32 {
+        self.x.abs() + self.y.abs()
+    }
+}
+
+#[cfg(test)]
+mod ship_tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(0, Ship { heading: East, x: 0, y: 0 }.distance());
+        assert_eq!(14, Ship { heading: East, x: 10, y: -4 }.distance());
+    }
+
+    #[test]
+    fn run_example() {
+        let instructions = load_instructions("sample.txt").unwrap();
+        let mut ship = Ship::new();
+
+        for instruction in instructions {
+            ship.run(&instruction);
+        }
+
+        assert_eq!(25, ship.distance());
+    }
+
+    #[test]
+    fn run() {
+        let mut ship = Ship::new();
+
+        ship.run(&N(3));
+        assert_eq!(Ship { heading: East, x: 0, y: 3 }, ship);
+
+        ship.run(&S(10));
+        assert_eq!(Ship { heading: East, x: 0, y: -7 }, ship);
+
+        ship.run(&E(4));
+        assert_eq!(Ship { heading: East, x: 4, y: -7 }, ship);
+
+        ship.run(&W(2));
+        assert_eq!(Ship { heading: East, x: 2, y: -7 }, ship);
+
+        ship.run(&L(90));
+        assert_eq!(Ship { heading: North, x: 2, y: -7 }, ship);
+
+        ship.run(&R(180));
+        assert_eq!(Ship { heading: South, x: 2, y: -7 }, ship);
+
+        ship.run(&F(7));
+        assert_eq!(Ship { heading: South, x: 2, y: -14 }, ship);
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Waypoint {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ShipWaypoint {
+    ship: Ship,
+    waypoint: Waypoint,
+}
+
+impl ShipWaypoint {
+    /// Returns a new ship and waypoint.
+    fn new() -> ShipWaypoint {
+        ShipWaypoint { ship: Ship::new(), waypoint: Waypoint { x: 10, y: 1 }}
+    }
+
+    /// Runs the given instruction, modifying this ship and waypoint.
+    fn run(&mut self, instruction: &Instruction) {
+        match instruction {
+            N(distance) => self.waypoint.y += distance,
+            S(distance) => self.waypoint.y -= distance,
+            E(distance) => self.waypoint.x += distance,
+            W(distance) => self.waypoint.x -= distance,
+
+            L(90) | R(270) => {
+                let new_y = self.waypoint.x;
+                self.waypoint.x = -self.waypoint.y;
+                self.waypoint.y = new_y;
+            }
+
+            R(90) | L(270) => {
+                let new_y = -self.waypoint.x;
+                self.waypoint.x = self.waypoint.y;
+                self.waypoint.y = new_y;
+            }
+
+            R(180) | L(180) => {
+                self.waypoint.x *= -1;
+                self.waypoint.y *= -1;
+            }
+
+            F(times) => {
+                self.ship.x += times * self.waypoint.x;
+                self.ship.y += times * self.waypoint.y;
+            }
+
+            _ => panic!("Unknown instruction {:?}", instruction),
+        }
+    }
+}
+
+/// Navigates a ship using the given instructions, and returns the Manhattan distance between
+/// where the ship started and where it ended up.
+pub fn ship_distance(instructions: &[Instruction]) -> i32 {
+    let mut ship = Ship::new();
+
+    for instruction in instructions {
+        ship.run(instruction);
+    }
+
+    ship.distance()
+}
+
+/// Navigates a ship by moving its waypoint with the given instructions, and returns the Manhattan
+/// distance between where the ship started and where it ended up.
+pub fn ship_waypoint_distance(instructions: &[Instruction]) -> i32 {
+    let mut ship_waypoint = ShipWaypoint::new();
+
+    for instruction in instructions {
+        ship_waypoint.run(instruction);
+    }
+
+    ship_waypoint.ship.distance()
+}
+
+#[cfg(test)]
+mod ship_waypoint_tests {
+    use super::*;
+
+    #[test]
+    fn run_sample() {
+        let mut ship_waypoint = ShipWaypoint::new();
+
+        let instructions = load_instructions("sample.txt").unwrap();
+        for instruction in instructions {
+            ship_waypoint.run(&instruction);
+        }
+
+        assert_eq!(286, ship_waypoint.ship.distance());
+    }
+}
+
+pub struct Day12 {
+    instructions: Vec<Instruction>,
+}
+
+impl Solution for Day12 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day12 { instructions: read_instructions(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> i32 {
+        ship_distance(&self.instructions)
+    }
+
+    fn part2(&self) -> i32 {
+        ship_waypoint_distance(&self.instructions)
+    }
+}
+
+#[cfg(test)]
+mod solution_tests {
+    use super::*;
+
+    #[test]
+    fn solution_sample() {
+        let day = Day12::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(25, day.part1());
+        assert_eq!(286, day.part2());
+    }
+}
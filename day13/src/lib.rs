@@ -0,0 +1,251 @@
+use std::io::BufRead;
+use common::{LoadError, ParseError, Solution};
+
+pub struct Notes {
+    now: i32,
+    bus_ids: Vec<i32>,
+}
+
+impl Notes {
+    /// Loads notes from the given file.  Notes have the current time on one line,
+    /// followed by a comma-separated list of bus ids on the next.  Bus ids are either 'x'
+    /// or how often that bus arrives.
+    pub fn load(filename: &str) -> Result<Notes, LoadError> {
+        Notes::read(common::open(filename)?)
+    }
+
+    /// Reads notes from the given reader.
+    pub fn read<R: BufRead>(reader: R) -> Result<Notes, LoadError> {
+        let mut lines = reader.lines();
+
+        let now_line = lines.next()
+            .ok_or_else(|| LoadError::invalid("notes are missing the current time"))??;
+        let earliest = now_line.parse()
+            .map_err(|_| ParseError::new(&now_line, 1, "a timestamp").at_line(1))?;
+
+        let bus_line = lines.next()
+            .ok_or_else(|| LoadError::invalid("notes are missing bus ids"))??;
+        let bus_ids = Bus::from_line(&bus_line).map_err(|e| e.at_line(2))?.iter()
+            .map(|bus| bus.id as i32)
+            .collect();
+
+        Ok(Notes { now: earliest, bus_ids })
+    }
+
+    /// Returns the id of the earliest bus you can take multiplied by the time you need to
+    /// wait to take that bus.
+    pub fn wait(&self) -> i32 {
+        let (earliest_bus_id, earliest_wait) = self.bus_ids.iter()
+            .map(|&bus| (bus, (self.now as f32 / bus as f32).ceil() as i32 * bus - self.now))
+            .min_by(|&x, &y| x.1.cmp(&y.1)).unwrap();
+
+        earliest_bus_id * earliest_wait
+    }
+}
+
+#[cfg(test)]
+mod notes_tests {
+    use super::*;
+
+    #[test]
+    fn load_notes_sample() {
+        let notes = Notes::load("sample.txt").unwrap();
+
+        assert_eq!(939, notes.now);
+        assert_eq!(5, notes.bus_ids.len());
+    }
+
+    #[test]
+    fn wait_sample() {
+        let notes = Notes::load("sample.txt").unwrap();
+
+        assert_eq!(295, notes.wait());
+    }
+}
+
+#[derive(Debug)]
+pub struct Bus {
+    id: i64,
+    offset: i64,
+}
+
+impl Bus {
+    /// Loads busses from the given file.  The second line in the file is a comma-separated list
+    /// of bus ids.
+    pub fn load(filename: &str) -> Result<Vec<Bus>, LoadError> {
+        Bus::read(common::open(filename)?)
+    }
+
+    /// Reads busses from the given reader.  The second line is a comma-separated list of bus ids.
+    pub fn read<R: BufRead>(reader: R) -> Result<Vec<Bus>, LoadError> {
+        let mut lines = reader.lines().skip(1);
+
+        let line = lines.next()
+            .ok_or_else(|| LoadError::invalid("notes are missing bus ids"))??;
+
+        Ok(Bus::from_line(line.as_str()).map_err(|e| e.at_line(2))?)
+    }
+
+    /// Parses a list of busses from the given comma-separated line of bus ids.
+    fn from_line(line: &str) -> Result<Vec<Bus>, ParseError> {
+        let mut busses = Vec::new();
+        let mut column = 1;
+
+        for (offset, bus) in line.split(",").enumerate() {
+            if bus != "x" {
+                let id = bus.parse().map_err(|_| ParseError::new(line, column, "a bus id or 'x'"))?;
+                busses.push(Bus { id, offset: offset as i64 });
+            }
+
+            column += bus.len() + 1;
+        }
+
+        Ok(busses)
+    }
+}
+
+/// Returns the earliest timestamp where all of the listed busses depart at offsets matching
+/// their position in the list.
+pub fn earliest_depart(busses: &[Bus]) -> i64 {
+    // Chinese Remainder Theorem - https://en.wikipedia.org/wiki/Chinese_remainder_theorem#Search_by_sieving
+    // Using sieving (the brute force approach to solving for t with the CRT), we find:
+    // express equivalences as `t ≡ a_i % n_i`
+    // test a_1, a_1+n, a_1 + 2*n_1, ... mod n_2 to find t_2
+    // test t_2, t_2 + n_1 * n_2, x_2 + 2 * n_1 * n_2, ... mod n_3 to find t_3
+    // repeat to find the solution.
+    // Busses depart at t + offset, so the departures for 17, x, 13, 19 can be expressed as:
+    // t ≡ 0 % 17
+    // t ≡ (13-2) % 13
+    // t ≡ (19-3) % 19
+    // Which sieves to (sorting moduli descending to go quicker):
+    // 16 + i * 19 % 17 = 0, i = 9
+    // 187 + i * 17 * 19 % 13 = 11, i = 10
+    // t = 3417
+
+    // t ≡ a % n
+    #[derive(Debug)]
+    struct Mod {
+        a: i64,
+        n: i64,
+    }
+
+    let mut mods: Vec<Mod> = busses.iter().map(|bus| Mod {
+        a: (bus.id * (bus.offset as f64 / bus.id as f64).ceil() as i64 - bus.offset) % bus.id,
+        n: bus.id
+    }).collect();
+
+    mods.sort_by_key(|m| -m.n);
+
+    let mut x = mods[0].a;
+    let mut n = mods[0].n;
+
+    for m in &mods[1..] {
+        let i = (0 .. m.n).find(|i| (x + i * n) % m.n == m.a).unwrap();
+
+        x += i * n;
+        n *= m.n;
+    }
+
+    x
+}
+
+#[cfg(test)]
+mod bus_tests {
+    use super::*;
+
+    #[test]
+    fn load() {
+        let busses = Bus::load("sample.txt").unwrap();
+
+        assert_eq!(5, busses.len());
+    }
+
+    #[test]
+    fn earliest_depart_samples() {
+        assert_eq!(1068781, earliest_depart(&Bus::from_line("7,13,x,x,59,x,31,19").unwrap()));
+        assert_eq!(3417, earliest_depart(&Bus::from_line("17,x,13,19").unwrap()));
+        assert_eq!(754018, earliest_depart(&Bus::from_line("67,7,59,61").unwrap()));
+        assert_eq!(779210, earliest_depart(&Bus::from_line("67,x,7,59,61").unwrap()));
+        assert_eq!(1261476, earliest_depart(&Bus::from_line("67,7,x,59,61").unwrap()));
+        assert_eq!(1202161486, earliest_depart(&Bus::from_line("1789,37,47,1889").unwrap()));
+    }
+}
+
+#[cfg(test)]
+mod earliest_depart_properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Finds the earliest timestamp by checking every timestamp.
+    fn brute_force_earliest_depart(busses: &[Bus]) -> i64 {
+        (0..)
+            .find(|t| busses.iter().all(|bus| (t + bus.offset) % bus.id == 0))
+            .unwrap()
+    }
+
+    /// A line of bus ids that are distinct primes like the puzzle input, with some 'x's.
+    fn bus_line() -> impl Strategy<Value = String> {
+        let primes = vec!["2", "3", "5", "7", "11", "13", "17", "19", "23", "29"];
+
+        prop::sample::subsequence(primes, 1..=4)
+            .prop_shuffle()
+            .prop_flat_map(|ids| {
+                let len = ids.len();
+                (Just(ids), prop::collection::vec(0..3_usize, len))
+            })
+            .prop_map(|(ids, gaps)| {
+                ids.iter().zip(gaps)
+                    .map(|(id, gap)| format!("{}{}", "x,".repeat(gap), id))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn earliest_depart_matches_brute_force(line in bus_line()) {
+            let busses = Bus::from_line(&line).unwrap();
+
+            prop_assert_eq!(brute_force_earliest_depart(&busses), earliest_depart(&busses));
+        }
+    }
+}
+
+pub struct Day13 {
+    notes: Notes,
+    busses: Vec<Bus>,
+}
+
+impl Solution for Day13 {
+    type Part1 = i32;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day13 {
+            notes: Notes::read(input.as_bytes())?,
+            busses: Bus::read(input.as_bytes())?,
+        })
+    }
+
+    fn part1(&self) -> i32 {
+        self.notes.wait()
+    }
+
+    fn part2(&self) -> i64 {
+        earliest_depart(&self.busses)
+    }
+}
+
+#[cfg(test)]
+mod solution_tests {
+    use super::*;
+
+    #[test]
+    fn solution_sample() {
+        let day = Day13::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(295, day.part1());
+        assert_eq!(1068781, day.part2());
+    }
+}
@@ -0,0 +1,79 @@
+use rand::Rng;
+use rand::seq::index;
+
+/// Params size a generated initialization program.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// Number of masks in the program.
+    pub masks: usize,
+
+    /// Most memory sets after each mask.
+    pub max_sets: usize,
+
+    /// Most floating 'X' bits in each mask.  Version 2 of the decoder writes to 2^X addresses
+    /// for each set, so this bounds how long part 2 takes.
+    pub max_floating: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { masks: 100, max_sets: 6, max_floating: 9 }
+    }
+}
+
+/// Generates an initialization program of masks, each followed by one or more memory sets.
+pub fn generate<R: Rng>(rng: &mut R, params: &Params) -> String {
+    let mut program = String::new();
+
+    for _ in 0..params.masks {
+        let mut mask = [0; 36];
+        for bit in mask.iter_mut() {
+            *bit = if rng.gen() { b'1' } else { b'0' };
+        }
+
+        let floating = rng.gen_range(0..=params.max_floating.min(mask.len()));
+        for i in index::sample(rng, mask.len(), floating) {
+            mask[i] = b'X';
+        }
+
+        program.push_str(&format!("mask = {}\n", String::from_utf8_lossy(&mask)));
+
+        for _ in 0..rng.gen_range(1..=params.max_sets.max(1)) {
+            let location = rng.gen_range(0..1_i64 << 16);
+            let value = rng.gen_range(0..1_i64 << 36);
+
+            program.push_str(&format!("mem[{}] = {}\n", location, value));
+        }
+    }
+
+    program
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::MaskValue::Unchanged;
+    use crate::read_instructions;
+
+    use super::*;
+
+    #[test]
+    fn generate_valid() {
+        let params = Params { masks: 20, max_sets: 3, max_floating: 4 };
+        let input = generate(&mut StdRng::seed_from_u64(3), &params);
+        let instructions = read_instructions(input.as_bytes()).unwrap();
+
+        assert_eq!(20, instructions.len());
+        assert!(instructions.iter().all(|instruction| (1..=3).contains(&instruction.sets.len())));
+        assert!(instructions.iter().all(|instruction| instruction.mask.values.iter().filter(|&&v| v == Unchanged).count() <= 4));
+    }
+
+    #[test]
+    fn generate_seeded() {
+        let params = Params::default();
+
+        assert_eq!(generate(&mut StdRng::seed_from_u64(7), &params), generate(&mut StdRng::seed_from_u64(7), &params));
+    }
+}
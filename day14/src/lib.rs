@@ -5,19 +5,18 @@ extern crate regex;
 use core::fmt;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::str::FromStr;
 
 use itertools::Itertools;
 use regex::Regex;
+use common::{LoadError, ParseError, Solution};
 
 use crate::MaskValue::{One, Unchanged, Zero};
 
-#[derive(Debug)]
-pub struct ParseErr {}
+pub mod generate;
 
-#[derive(Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum MaskValue {
     Zero, One, Unchanged,
 }
@@ -27,24 +26,28 @@ pub struct Mask {
 }
 
 impl FromStr for Mask {
-    type Err = ParseErr;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
-        if !s.starts_with("mask = ") || s.len() != 43 {
-            return Err(ParseErr {});
-        }
+        let bits = s.strip_prefix("mask = ")
+            .ok_or_else(|| ParseError::new(s, 1, "'mask = '"))?;
 
         let mut values = [Zero; 36];
-
-        s[7..43].chars().enumerate().for_each(|(i, c)| {
-            values[i] = match c {
-                '0' => Zero,
-                '1' => One,
-                'X' => Unchanged,
-                other => panic!("Invalid mask char '{}'", other),
+        let mut chars = bits.chars();
+
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = match chars.next() {
+                Some('0') => Zero,
+                Some('1') => One,
+                Some('X') => Unchanged,
+                _ => return Err(ParseError::new(s, 8 + i, "'0', '1', or 'X'")),
             }
-        });
+        }
+
+        if chars.next().is_some() {
+            return Err(ParseError::new(s, 8 + values.len(), "end of mask"));
+        }
 
         Ok(Mask { values })
     }
@@ -83,9 +86,9 @@ impl Mask {
     /// Returns a list of locations by applying this mask to the given location.
     fn locations(&self, location: i64) -> Vec<i64> {
         // In the mask, 0 means unchanged, 1 means overwrite with 1, and X means floating.
-        let mut base_mask = self.values.clone();
-        for i in 0..base_mask.len() {
-            base_mask[i] = match self.values[i] {
+        let mut base_mask = self.values;
+        for (bit, value) in base_mask.iter_mut().zip(self.values.iter()) {
+            *bit = match value {
                 Unchanged => Zero,
                 Zero => Unchanged,
                 One => One,
@@ -105,7 +108,7 @@ impl Mask {
 
         for num_ones in 1 ..= unchanged_indexes.len() {
             for combo in unchanged_indexes.iter().combinations(num_ones) {
-                let mut combo_mask = base_mask.clone();
+                let mut combo_mask = base_mask;
 
                 for i in combo {
                     combo_mask[*i] = One;
@@ -137,12 +140,7 @@ mod mask_tests {
 
         assert_eq!(expected_mask.len(), parsed_mask.values.len());
 
-        let mut values_match = true;
-        for i in 0..parsed_mask.values.len() {
-            values_match = values_match && expected_mask[i] == parsed_mask.values[i];
-        }
-
-        assert!(values_match);
+        assert_eq!(expected_mask, parsed_mask.values);
     }
 
     #[test]
@@ -162,6 +160,82 @@ mod mask_tests {
         let mask: Mask = "mask = 00000000000000000000000000000000X0XX".parse().unwrap();
         assert_eq!(vec![16, 24, 18, 17, 26, 25, 19, 27], mask.locations(26));
     }
+
+    #[test]
+    fn parse_invalid() {
+        let s = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX2X";
+        assert_eq!(Err(ParseError::new(s, 42, "'0', '1', or 'X'")), s.parse::<Mask>().map(|_| ()));
+
+        let s = "mask = XXXX";
+        assert_eq!(Err(ParseError::new(s, 12, "'0', '1', or 'X'")), s.parse::<Mask>().map(|_| ()));
+
+        let s = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X0";
+        assert_eq!(Err(ParseError::new(s, 44, "end of mask")), s.parse::<Mask>().map(|_| ()));
+    }
+}
+
+#[cfg(test)]
+mod mask_properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Returns the locations written by a mask by counting through every value of the floating bits.
+    fn brute_force_locations(mask: &Mask, location: i64) -> Vec<i64> {
+        let floating: Vec<usize> = (0..mask.values.len()).filter(|&i| mask.values[i] == Unchanged).collect();
+
+        (0..1_i64 << floating.len())
+            .map(|n| {
+                let mut result = location;
+
+                for (i, value) in mask.values.iter().enumerate() {
+                    let bit = 1 << (35 - i);
+
+                    match value {
+                        Zero => {},
+                        One => result |= bit,
+                        Unchanged => result &= !bit,
+                    }
+                }
+
+                for (j, &i) in floating.iter().enumerate() {
+                    if n & (1 << j) != 0 {
+                        result |= 1 << (35 - i);
+                    }
+                }
+
+                result
+            })
+            .collect()
+    }
+
+    /// A mask with at most 10 floating bits, so there are at most 1024 locations.
+    fn mask() -> impl Strategy<Value = String> {
+        prop::collection::vec(prop_oneof![Just('0'), Just('1')], 36)
+            .prop_flat_map(|bits| (Just(bits), prop::collection::vec(0..36_usize, 0..=10)))
+            .prop_map(|(mut bits, floating)| {
+                for i in floating {
+                    bits[i] = 'X';
+                }
+
+                format!("mask = {}", bits.into_iter().collect::<String>())
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn locations_matches_brute_force(mask in mask(), location in 0..1_i64 << 36) {
+            let mask: Mask = mask.parse().unwrap();
+
+            let mut locations = mask.locations(location);
+            locations.sort_unstable();
+
+            let mut expected = brute_force_locations(&mask, location);
+            expected.sort_unstable();
+
+            prop_assert_eq!(expected, locations);
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -171,7 +245,7 @@ pub struct MemSet {
 }
 
 impl FromStr for MemSet {
-    type Err = ParseErr;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // mem[8] = 11
@@ -179,12 +253,16 @@ impl FromStr for MemSet {
             static ref MEM_SET_RE: Regex = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
         }
 
-        MEM_SET_RE.captures(s)
-            .map(|captures| Ok(MemSet {
-                location: captures[1].parse().unwrap(),
-                value: captures[2].parse().unwrap(),
-            }))
-            .unwrap_or(Err(ParseErr {}))
+        let captures = MEM_SET_RE.captures(s)
+            .ok_or_else(|| ParseError::new(s, 1, "a memory set like 'mem[8] = 11'"))?;
+
+        // Both numbers match digits, but can still be too large to fit in an i64.
+        let number = |i: usize| {
+            let m = captures.get(i).unwrap();
+            m.as_str().parse().map_err(|_| ParseError::new(s, m.start() + 1, "a 64-bit number"))
+        };
+
+        Ok(MemSet { location: number(1)?, value: number(2)? })
     }
 }
 
@@ -200,7 +278,7 @@ mod mem_set_tests {
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Default)]
 pub struct Memory {
     values: HashMap<i64, i64>
 }
@@ -238,11 +316,9 @@ mod memory_tests {
         mem.set(8, 73);
         assert_eq!(73, mem.get(8));
 
-        let mem_set = MemSet { location: 7, value: 101 };
         mem.set(7, 101);
         assert_eq!(101, mem.get(7));
 
-        let mem_set = MemSet { location: 8, value: 0 };
         mem.set(8, 64);
         assert_eq!(64, mem.get(8));
 
@@ -257,40 +333,42 @@ pub struct Instruction {
 }
 
 /// Loads instructions from the given file.
-pub fn load_instructions(filename: &str) -> Vec<Instruction> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+pub fn load_instructions(filename: &str) -> Result<Vec<Instruction>, LoadError> {
+    read_instructions(common::open(filename)?)
+}
 
+/// Reads instructions from the given reader.
+pub fn read_instructions<R: BufRead>(reader: R) -> Result<Vec<Instruction>, LoadError> {
     let mut instructions = Vec::new();
 
     let mut mask: Option<Mask> = None;
     let mut sets = Vec::new();
 
-    for line_result in f.lines() {
-        let line = line_result.unwrap();
+    for (i, line_result) in reader.lines().enumerate() {
+        let line = line_result?;
 
         if line.starts_with("mask") {
-            if mask.is_some() && !sets.is_empty() {
-                instructions.push(Instruction { mask: mask.unwrap(), sets });
+            if let Some(mask) = mask.filter(|_| !sets.is_empty()) {
+                instructions.push(Instruction { mask, sets });
             }
 
-            mask = Some(line.parse().unwrap());
+            mask = Some(line.parse::<Mask>().map_err(|e| e.at_line(i + 1))?);
             sets = Vec::new();
         } else if line.starts_with("mem") {
-            sets.push(line.parse().unwrap());
+            sets.push(line.parse::<MemSet>().map_err(|e| e.at_line(i + 1))?);
         }
     }
 
-    if mask.is_some() && !sets.is_empty() {
-        instructions.push(Instruction { mask: mask.unwrap(), sets });
+    if let Some(mask) = mask.filter(|_| !sets.is_empty()) {
+        instructions.push(Instruction { mask, sets });
     }
 
-    instructions
+    Ok(instructions)
 }
 
 /// Runs the given instructions on uninitialized memory, returning the resulting memory.
 /// Instructions apply a mask to a value to set a single memory address.
-pub fn run_instructions(instructions: &Vec<Instruction>) -> Memory {
+pub fn run_instructions(instructions: &[Instruction]) -> Memory {
     let mut mem = Memory::new();
 
     for instruction in instructions {
@@ -304,7 +382,7 @@ pub fn run_instructions(instructions: &Vec<Instruction>) -> Memory {
 
 /// Runs the given instructions on uninitialized memory, returning the resulting memory.
 /// Instructions apply a mask to a memory address and set a value.
-pub fn run_instructions_v2(instructions: &Vec<Instruction>) -> Memory {
+pub fn run_instructions_v2(instructions: &[Instruction]) -> Memory {
     let mut mem = Memory::new();
 
     for instruction in instructions {
@@ -318,21 +396,53 @@ pub fn run_instructions_v2(instructions: &Vec<Instruction>) -> Memory {
     mem
 }
 
+pub struct Day14 {
+    instructions: Vec<Instruction>,
+}
+
+impl Solution for Day14 {
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day14 { instructions: read_instructions(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> i64 {
+        run_instructions(&self.instructions).sum()
+    }
+
+    fn part2(&self) -> i64 {
+        run_instructions_v2(&self.instructions).sum()
+    }
+}
+
 #[cfg(test)]
 mod instruction_tests {
     use super::*;
 
     #[test]
     fn sample_load_instructions() {
-        let instructions = load_instructions("sample.txt");
+        let instructions = load_instructions("sample.txt").unwrap();
 
         assert_eq!(1, instructions.len());
         assert_eq!(3, instructions[0].sets.len());
     }
 
+    #[test]
+    fn read_invalid_instructions() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11\nmem[7] = abc\n";
+
+        match read_instructions(input.as_bytes()) {
+            Err(LoadError::Parse(e)) =>
+                assert_eq!(ParseError::new("mem[7] = abc", 1, "a memory set like 'mem[8] = 11'").at_line(3), e),
+            _ => panic!("Expected a parse error on line 3."),
+        }
+    }
+
     #[test]
     fn sample_run() {
-        let instructions = load_instructions("sample.txt");
+        let instructions = load_instructions("sample.txt").unwrap();
         let mem = run_instructions(&instructions);
 
         assert_eq!(165, mem.sum());
@@ -340,9 +450,15 @@ mod instruction_tests {
 
     #[test]
     fn sample_run_v2() {
-        let instructions = load_instructions("sample_v2.txt");
+        let instructions = load_instructions("sample_v2.txt").unwrap();
         let mem = run_instructions_v2(&instructions);
 
         assert_eq!(208, mem.sum());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solution_sample() {
+        assert_eq!(165, Day14::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap().part1());
+        assert_eq!(208, Day14::parse(&std::fs::read_to_string("sample_v2.txt").unwrap()).unwrap().part2());
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use common::{LoadError, ParseError, Solution};
+
+pub struct Numbers {
+    start: Vec<i32>,
+    round: usize,
+    last_num: i32,
+    num_round: HashMap<i32, usize>,
+}
+
+impl Numbers {
+    /// Creates a new Numbers that starts with the given numbers.
+    pub fn new(start: Vec<i32>) -> Numbers {
+        Numbers {
+            start,
+            round: 0,
+            last_num: 0,
+            num_round: HashMap::new(),
+        }
+    }
+}
+
+impl Iterator for Numbers {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Players take turns saying numbers.  They first read the starting numbers, then each
+        // turn considers the most recently spoken number:
+        // * If it's the first time the number has been spoken, the player says 0.
+        // * Otherwise, the player says how many turns ago it was previously spoken.
+
+        if self.round < self.start.len() {
+            let num = self.start[self.round];
+
+            if self.round > 0 {
+                self.num_round.insert(self.start[self.round - 1], self.round - 1);
+            }
+
+            self.last_num = num;
+            self.round += 1;
+
+            return Some(num);
+        }
+
+        let prev_round = self.num_round.get(&self.last_num);
+
+        let num = match prev_round {
+            Some(&round) if round < self.round - 1 => (self.round - round - 1) as i32,
+            _ => 0,
+        };
+
+        self.num_round.insert(self.last_num, self.round - 1);
+        self.last_num = num;
+        self.round += 1;
+
+        Some(num)
+    }
+}
+
+pub struct Day15 {
+    start: Vec<i32>,
+}
+
+impl Solution for Day15 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    /// Parses a comma-separated list of starting numbers.
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        let line = input.trim();
+        let mut start = Vec::new();
+        let mut column = 1;
+
+        for num in line.split(',') {
+            start.push(num.parse().map_err(|_| ParseError::new(line, column, "a number").at_line(1))?);
+            column += num.len() + 1;
+        }
+
+        Ok(Day15 { start })
+    }
+
+    fn part1(&self) -> i32 {
+        Numbers::new(self.start.clone()).nth(2019).unwrap()
+    }
+
+    fn part2(&self) -> i32 {
+        Numbers::new(self.start.clone()).nth(29999999).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_samples() {
+        let nums = Numbers::new(vec![0, 3, 6]);
+        assert_eq!(vec![0, 3, 6, 0, 3, 3, 1, 0, 4, 0], nums.take(10).collect::<Vec<i32>>());
+
+        assert_eq!(1, Numbers::new(vec![1, 3, 2]).nth(2019).unwrap());
+        assert_eq!(10, Numbers::new(vec![2, 1, 3]).nth(2019).unwrap());
+        assert_eq!(27, Numbers::new(vec![1, 2, 3]).nth(2019).unwrap());
+        assert_eq!(78, Numbers::new(vec![2, 3, 1]).nth(2019).unwrap());
+        assert_eq!(438, Numbers::new(vec![3, 2, 1]).nth(2019).unwrap());
+        assert_eq!(1836, Numbers::new(vec![3, 1, 2]).nth(2019).unwrap());
+    }
+
+    #[test]
+    fn numbers_samples_thirty_millionth() {
+        assert_eq!(175594, Numbers::new(vec![0, 3, 6]).nth(29999999).unwrap());
+        assert_eq!(2578, Numbers::new(vec![1, 3, 2]).nth(29999999).unwrap());
+        assert_eq!(3544142, Numbers::new(vec![2, 1, 3]).nth(29999999).unwrap());
+        assert_eq!(261214, Numbers::new(vec![1, 2, 3]).nth(29999999).unwrap());
+        assert_eq!(6895259, Numbers::new(vec![2, 3, 1]).nth(29999999).unwrap());
+        assert_eq!(18, Numbers::new(vec![3, 2, 1]).nth(29999999).unwrap());
+        assert_eq!(362, Numbers::new(vec![3, 1, 2]).nth(29999999).unwrap());
+    }
+
+    #[test]
+    fn solution_sample() {
+        assert_eq!(436, Day15::parse("0,3,6\n").unwrap().part1());
+    }
+}
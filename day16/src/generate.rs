@@ -0,0 +1,135 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+const PREFIXES: [&str; 6] = ["departure", "arrival", "return", "transfer", "connection", "layover"];
+const NOUNS: [&str; 6] = ["location", "station", "platform", "track", "date", "time"];
+
+/// Params size generated ticket notes.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// Number of fields on each ticket, which is also the number of rules.  There are names
+    /// for at most 36 rules, and the first 6 rules are departure fields.
+    pub fields: usize,
+
+    /// Number of nearby tickets.
+    pub nearby_tickets: usize,
+
+    /// Percent of nearby tickets that have a value that doesn't match any rule.
+    pub invalid_percent: u32,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { fields: 20, nearby_tickets: 240, invalid_percent: 20 }
+    }
+}
+
+/// Generates ticket notes: rules, your ticket, and nearby tickets.  Every rule has two ranges
+/// around a gap of invalid values, and each rule's ranges contain the ranges of the rules
+/// before it.  Each field has a valid nearby ticket with a value that's only matched by its
+/// rule and the rules after it, so fields can be assigned to rules by elimination.
+pub fn generate<R: Rng>(rng: &mut R, params: &Params) -> String {
+    let fields = params.fields.clamp(1, PREFIXES.len() * NOUNS.len());
+
+    // Rule k matches low - widths[k] .. low and high .. high + widths[k].
+    let low: i32 = rng.gen_range(250..350);
+    let high = low + rng.gen_range(20..60);
+
+    let mut widths: Vec<i32> = vec![rng.gen_range(20..40)];
+    while widths.len() < fields {
+        widths.push(widths.last().unwrap() + rng.gen_range(1..=10));
+    }
+
+    let matching = |rng: &mut R, rule: usize| {
+        let offset = rng.gen_range(0..widths[rule]);
+        if rng.gen() { low - 1 - offset } else { high + offset }
+    };
+
+    // Only matched by the given rule and the rules after it.
+    let first_matching = |rng: &mut R, rule: usize| {
+        let previous = if rule == 0 { 0 } else { widths[rule - 1] };
+        let offset = rng.gen_range(previous..widths[rule]);
+        if rng.gen() { low - 1 - offset } else { high + offset }
+    };
+
+    let invalid = |rng: &mut R| {
+        if rng.gen() { rng.gen_range(low..high) } else { rng.gen_range(high + widths[fields - 1]..1000) }
+    };
+
+    // Rule for each field of a ticket.
+    let mut field_rules: Vec<usize> = (0..fields).collect();
+    field_rules.shuffle(rng);
+
+    let ticket = |rng: &mut R| -> Vec<i32> {
+        field_rules.iter().map(|&rule| matching(rng, rule)).collect()
+    };
+
+    let your_ticket = ticket(rng);
+
+    // The first nearby ticket is always valid, so there's somewhere to put each field's
+    // distinguishing value.
+    let count = params.nearby_tickets.max(1);
+    let mut nearby_tickets: Vec<Vec<i32>> = (0..count).map(|_| ticket(rng)).collect();
+    let (invalid_tickets, valid_tickets): (Vec<usize>, Vec<usize>) = (0..count)
+        .partition(|&i| i > 0 && rng.gen_range(0..100) < params.invalid_percent);
+
+    for (field, &rule) in field_rules.iter().enumerate() {
+        let &i = valid_tickets.choose(rng).unwrap();
+        nearby_tickets[i][field] = first_matching(rng, rule);
+    }
+
+    for i in invalid_tickets {
+        let field = rng.gen_range(0..fields);
+        nearby_tickets[i][field] = invalid(rng);
+    }
+
+    // Rules are listed in a random order.
+    let mut rules: Vec<usize> = (0..fields).collect();
+    rules.shuffle(rng);
+
+    let mut notes = String::new();
+    for rule in rules {
+        let name = format!("{} {}", PREFIXES[rule / NOUNS.len()], NOUNS[rule % NOUNS.len()]);
+
+        notes.push_str(&format!("{}: {}-{} or {}-{}\n", name, low - widths[rule], low - 1, high, high + widths[rule] - 1));
+    }
+
+    let line = |ticket: &Vec<i32>| ticket.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(",");
+
+    notes.push_str(&format!("\nyour ticket:\n{}\n\nnearby tickets:\n", line(&your_ticket)));
+    for ticket in &nearby_tickets {
+        notes.push_str(&line(ticket));
+        notes.push('\n');
+    }
+
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::Input;
+
+    use super::*;
+
+    #[test]
+    fn generate_solvable() {
+        for seed in 0..10 {
+            let params = Params { fields: 8, nearby_tickets: 20, invalid_percent: 25 };
+            let input = Input::read(generate(&mut StdRng::seed_from_u64(seed), &params).as_bytes()).unwrap();
+
+            assert_eq!(8, input.rules.len());
+            assert_eq!(20, input.nearby_tickets.len());
+            assert_eq!(8, input.rule_fields().len());
+        }
+    }
+
+    #[test]
+    fn generate_seeded() {
+        let params = Params::default();
+
+        assert_eq!(generate(&mut StdRng::seed_from_u64(7), &params), generate(&mut StdRng::seed_from_u64(7), &params));
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
+use common::{LoadError, Solution};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Grid {
     dimensions: usize,
     active: HashSet<Vec<i32>>,
@@ -10,27 +10,30 @@ pub struct Grid {
 
 impl Grid {
     /// Loads a Grid with the given number of dimensions from a file.
-    pub fn load(filename: &str, dimensions: usize) -> Grid {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-
-        let mut active = HashSet::new();
-
-        for (y, line) in f.lines().enumerate() {
-            for (x, c) in line.unwrap().chars().enumerate() {
-                if c == '#' {
-                    let mut point = vec![x as i32, y as i32];
-
-                    for _ in 2..dimensions {
-                        point.push(0);
-                    }
-
-                    active.insert(point);
-                }
-            }
-        }
+    pub fn load(filename: &str, dimensions: usize) -> Result<Grid, LoadError> {
+        Grid::read(common::open(filename)?, dimensions)
+    }
 
-        Grid { dimensions, active }
+    /// Reads a Grid with the given number of dimensions from the given reader.  The input is
+    /// a 2D slice of the grid, where '#' is an active cube.
+    pub fn read<R: BufRead>(reader: R, dimensions: usize) -> Result<Grid, LoadError> {
+        let slice = grid::Grid::read(reader, "'#' or '.'", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+
+        let active = slice.positions()
+            .filter(|&position| slice[position])
+            .map(|(y, x)| {
+                let mut point = vec![x as i32, y as i32];
+                point.resize(dimensions, 0);
+
+                point
+            })
+            .collect();
+
+        Ok(Grid { dimensions, active })
     }
 
     /// Runs this grid a given number of cycles, modifying it in the process.
@@ -44,6 +47,10 @@ impl Grid {
         // * If a cube is active and exactly 2 or 3 neighbors are active, the cube remains active.
         //   Otherwise it becomes inactive.
         // * If a cube is inactive but exactly 3 of its neighbors are active, the cube becomes active.
+        if self.active.is_empty() {
+            return;
+        }
+
         let mut to_check: Vec<Vec<i32>> = Vec::new();
         for i in 0..self.dimensions {
             let min = self.active.iter().map(|a| a[i]).min().unwrap() - 1;
@@ -72,9 +79,7 @@ impl Grid {
             let active = self.active.contains(&pos);
             let neighbors = self.neighbors(&pos);
 
-            if active && (neighbors == 2 || neighbors == 3) {
-                new_active.insert(pos);
-            } else if !active && neighbors == 3 {
+            if neighbors == 3 || (active && neighbors == 2) {
                 new_active.insert(pos);
             }
         }
@@ -83,18 +88,18 @@ impl Grid {
     }
 
     /// Returns the number of active neighbors around the given position.
-    fn neighbors(&self, pos: &Vec<i32>) -> usize {
+    fn neighbors(&self, pos: &[i32]) -> usize {
         let mut neighbors: Vec<Vec<i32>> = Vec::new();
-        for i in 0..self.dimensions {
+        for &coordinate in pos.iter().take(self.dimensions) {
             let mut new_neighbors = Vec::new();
 
             for j in -1 ..= 1 {
                 if neighbors.is_empty() {
-                    new_neighbors.push(vec![pos[i] + j])
+                    new_neighbors.push(vec![coordinate + j])
                 } else {
                     for partial_neighbor in &neighbors {
                         let mut neighbor = partial_neighbor.clone();
-                        neighbor.push(pos[i] + j);
+                        neighbor.push(coordinate + j);
                         new_neighbors.push(neighbor);
                     }
                 }
@@ -114,19 +119,59 @@ impl Grid {
     }
 }
 
+pub struct Day17 {
+    grid3: Grid,
+    grid4: Grid,
+}
+
+impl Day17 {
+    /// Returns the number of active cubes after running a copy of the grid for the six boot
+    /// cycles, or an error if no cubes start out active.
+    fn boot(grid: &Grid) -> Result<usize, LoadError> {
+        if grid.active() == 0 {
+            return Err(LoadError::invalid("No cubes are active."));
+        }
+
+        let mut grid = grid.clone();
+        grid.step_times(6);
+
+        Ok(grid.active())
+    }
+}
+
+impl Solution for Day17 {
+    type Part1 = Result<usize, LoadError>;
+    type Part2 = Result<usize, LoadError>;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day17 {
+            grid3: Grid::read(input.as_bytes(), 3)?,
+            grid4: Grid::read(input.as_bytes(), 4)?,
+        })
+    }
+
+    fn part1(&self) -> Result<usize, LoadError> {
+        Day17::boot(&self.grid3)
+    }
+
+    fn part2(&self) -> Result<usize, LoadError> {
+        Day17::boot(&self.grid4)
+    }
+}
+
 #[cfg(test)]
 mod grid_tests {
     use super::*;
 
     #[test]
     fn load() {
-        let grid = Grid::load("sample.txt", 3);
+        let grid = Grid::load("sample.txt", 3).unwrap();
         assert_eq!(grid.active(), 5);
     }
 
     #[test]
     fn run_sample() {
-        let mut grid = Grid::load("sample.txt", 3);
+        let mut grid = Grid::load("sample.txt", 3).unwrap();
 
         grid.step_times(6);
 
@@ -134,11 +179,31 @@ mod grid_tests {
     }
 
     #[test]
-    fn run_sample_4D() {
-        let mut grid = Grid::load("sample.txt", 4);
+    fn run_sample_4d() {
+        let mut grid = Grid::load("sample.txt", 4).unwrap();
 
         grid.step_times(6);
 
         assert_eq!(848, grid.active());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solution_sample() {
+        let day = Day17::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(112, day.part1().unwrap());
+        assert_eq!(848, day.part2().unwrap());
+    }
+
+    #[test]
+    fn solution_without_active_cubes() {
+        let day = Day17::parse("").unwrap();
+
+        assert_eq!("Invalid input: No cubes are active.", day.part1().unwrap_err().to_string());
+        assert_eq!("Invalid input: No cubes are active.", day.part2().unwrap_err().to_string());
+
+        let mut grid = Grid::read("...\n".as_bytes(), 3).unwrap();
+        grid.step();
+        assert_eq!(0, grid.active());
+    }
+}
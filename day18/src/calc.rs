@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+
+use crate::{parse, parse_statement, EvalError, Expr, Mode, Statement};
+
+/// Calculator evaluates statements in a precedence mode, remembering variables between them.
+pub struct Calculator {
+    mode: Mode,
+    variables: BTreeMap<String, i64>,
+}
+
+impl Calculator {
+    /// Returns a calculator that groups operations by the given mode, with no variables.
+    pub fn new(mode: Mode) -> Calculator {
+        Calculator { mode, variables: BTreeMap::new() }
+    }
+
+    /// Returns the mode the calculator groups operations by.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Changes the mode the calculator groups operations by.  Variables keep their values.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    /// Returns the value of the given variable, if it's been assigned.
+    pub fn variable(&self, name: &str) -> Option<i64> {
+        self.variables.get(name).copied()
+    }
+
+    /// Returns every variable and its value, in order by name.
+    pub fn variables(&self) -> &BTreeMap<String, i64> {
+        &self.variables
+    }
+
+    /// Runs a statement, returning the value of the expression or the value assigned to
+    /// the variable.
+    pub fn run(&mut self, line: &str) -> Result<i64, EvalError> {
+        match parse_statement(line, &self.mode.precedence())? {
+            Statement::Let(name, expr) => {
+                let value = self.substitute(&expr)?.eval()?;
+                self.variables.insert(name, value);
+
+                Ok(value)
+            }
+            Statement::Expr(expr) => self.substitute(&expr)?.eval(),
+        }
+    }
+
+    /// Explains how the calculator evaluates an expression, like day18::explain.  Variables are
+    /// replaced by their values before the first step.
+    pub fn explain(&self, s: &str) -> Result<Vec<String>, EvalError> {
+        let precedence = self.mode.precedence();
+        let expr = parse(s, &precedence)?;
+
+        let steps = self.substitute(&expr)?.trace()?;
+        let mut lines = vec![expr.to_string()];
+        lines.extend(steps.iter().map(|step| step.display_with(&precedence).to_string()));
+
+        Ok(lines)
+    }
+
+    /// Returns the expression with variables replaced by their values.
+    fn substitute(&self, expr: &Expr) -> Result<Expr, EvalError> {
+        expr.substitute(&|name: &str| self.variable(name))
+    }
+}
+
+const HELP: &str = "\
+let NAME = EXPR  assign a variable
+EXPR             evaluate an expression
+:mode [MODE]     show or change the mode: left-to-right (left) or add-before-times (add)
+:trace EXPR      show how the current mode groups and evaluates an expression
+:vars            list variables
+:help            show this help
+:quit            exit";
+
+/// Runs an interactive calculator, reading statements and commands from input and writing
+/// results to output until the input ends or ':quit' is entered.
+pub fn repl<R: BufRead, W: Write>(calc: &mut Calculator, input: R, output: &mut W) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let (command, arg) = match line.split_once(' ') {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            ":mode" if arg.is_empty() => writeln!(output, "{}", calc.mode())?,
+            ":mode" => match arg.parse() {
+                Ok(mode) => {
+                    calc.set_mode(mode);
+                    writeln!(output, "{}", mode)?;
+                }
+                Err(_) => writeln!(output, "expected 'left-to-right' or 'add-before-times'")?,
+            },
+            ":trace" => match calc.explain(arg) {
+                Ok(steps) => writeln!(output, "{}", steps.join("\n"))?,
+                Err(e) => writeln!(output, "{}", e)?,
+            },
+            ":vars" => {
+                for (name, value) in calc.variables() {
+                    writeln!(output, "{} = {}", name, value)?;
+                }
+            }
+            ":help" => writeln!(output, "{}", HELP)?,
+            ":quit" => break,
+            _ if command.starts_with(':') => writeln!(output, "unknown command '{}', try ':help'", command)?,
+            _ => match calc.run(line) {
+                Ok(value) => writeln!(output, "{}", value)?,
+                Err(e) => writeln!(output, "{}", e)?,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assign_and_use() {
+        let mut calc = Calculator::new(Mode::AddBeforeTimes);
+
+        assert_eq!(Ok(7), calc.run("let x = 3 + 4"));
+        assert_eq!(Ok(21), calc.run("let y = x * 2 + 1"));
+        assert_eq!(Ok(28), calc.run("y + x"));
+        assert_eq!(Some(21), calc.variable("y"));
+
+        assert_eq!(Err(EvalError::Undefined("z".to_owned())), calc.run("let w = z * 2"));
+        assert_eq!(None, calc.variable("w"));
+    }
+
+    #[test]
+    fn switch_mode() {
+        let mut calc = Calculator::new(Mode::LeftToRight);
+        calc.run("let x = 2").unwrap();
+
+        assert_eq!(Ok(26), calc.run("x * 3 + (4 * 5)"));
+
+        calc.set_mode(Mode::AddBeforeTimes);
+        assert_eq!(Ok(46), calc.run("x * 3 + (4 * 5)"));
+    }
+
+    #[test]
+    fn explain_with_variables() {
+        let mut calc = Calculator::new(Mode::AddBeforeTimes);
+        calc.run("let x = 3").unwrap();
+
+        assert_eq!(Ok(vec!["(2 * (x + 4))".to_owned(), "2 * 3 + 4".to_owned(), "2 * 7".to_owned(), "14".to_owned()]), calc.explain("2 * (x + 4)"));
+    }
+
+    #[test]
+    fn repl_session() {
+        let input = "let x = 2 * 3 + 4\n\
+                     :mode\n\
+                     :mode left\n\
+                     x * 2 + 1\n\
+                     :mode sideways\n\
+                     :trace 1 + x * 2\n\
+                     y\n\
+                     :vars\n\
+                     :quit\n\
+                     x\n";
+
+        let mut output = Vec::new();
+        repl(&mut Calculator::new(Mode::AddBeforeTimes), input.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(
+            "14\n\
+             add-before-times\n\
+             left-to-right\n\
+             29\n\
+             expected 'left-to-right' or 'add-before-times'\n\
+             ((1 + x) * 2)\n\
+             1 + 14 * 2\n\
+             15 * 2\n\
+             30\n\
+             Variable 'y' isn't defined.\n\
+             x = 14\n",
+            String::from_utf8(output).unwrap(),
+        );
+    }
+}
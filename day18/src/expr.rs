@@ -0,0 +1,318 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use common::ParseError;
+
+use crate::{Number, Precedence};
+
+/// BinOp is an operator between two values.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinOp {
+    /// Every operator, in the order Precedence stores them.
+    pub const ALL: [BinOp; 4] = [BinOp::Add, BinOp::Sub, BinOp::Mul, BinOp::Div];
+
+    /// Returns the symbol for this operator, like '+'.
+    pub fn symbol(self) -> char {
+        match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+        }
+    }
+
+    /// Applies this operator to two values from the given expression.  Division rounds
+    /// towards zero.  Returns an error naming the expression if the answer doesn't fit.
+    fn apply<N: Number>(self, a: &N, b: &N, expr: &Expr) -> Result<N, EvalError> {
+        if self == BinOp::Div && b.is_zero() {
+            return Err(EvalError::DivideByZero);
+        }
+
+        a.checked_op(self, b).ok_or_else(|| EvalError::Overflow(expr.to_string()))
+    }
+}
+
+impl Display for BinOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// Expr is a parsed expression.  Parsing decides how operations are grouped, so evaluating
+/// an expression doesn't depend on a Mode.  Variables have to be substituted with their values
+/// before the expression can be evaluated.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Expr {
+    Num(i64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Returns the value of this expression, or an error naming the first operation whose
+    /// answer doesn't fit in 64 bits.
+    pub fn eval(&self) -> Result<i64, EvalError> {
+        self.eval_as()
+    }
+
+    /// Returns the value of this expression as the given type of number.
+    pub fn eval_as<N: Number>(&self) -> Result<N, EvalError> {
+        match self {
+            Expr::Num(value) => Ok(N::from_i64(*value)),
+            Expr::Var(name) => Err(EvalError::Undefined(name.clone())),
+            Expr::Neg(expr) => expr.eval_as::<N>()?.checked_neg().ok_or_else(|| EvalError::Overflow(self.to_string())),
+            Expr::Binary(op, a, b) => op.apply(&a.eval_as()?, &b.eval_as()?, self),
+        }
+    }
+
+    /// Returns the value of this expression if it's a number or a negated number, which don't
+    /// have any operations left to run.
+    fn value(&self) -> Option<i64> {
+        match self {
+            Expr::Num(value) => Some(*value),
+            Expr::Neg(expr) => expr.value().and_then(i64::checked_neg),
+            Expr::Var(_) | Expr::Binary(..) => None,
+        }
+    }
+
+    /// Returns this expression after running its first operation whose operands are both
+    /// values, or None if the expression is already a value.  Operations are run in the same
+    /// order as eval: left operand, right operand, then the operator.
+    pub fn reduce(&self) -> Result<Option<Expr>, EvalError> {
+        if self.value().is_some() {
+            return Ok(None);
+        }
+
+        Ok(match self {
+            Expr::Num(_) => None,
+            Expr::Var(name) => return Err(EvalError::Undefined(name.clone())),
+            // A negated value that isn't a value itself is too big to negate.
+            Expr::Neg(expr) if expr.value().is_some() => return Err(EvalError::Overflow(self.to_string())),
+            Expr::Neg(expr) => expr.reduce()?.map(|expr| Expr::Neg(Box::new(expr))),
+            Expr::Binary(op, a, b) => match (a.value(), b.value()) {
+                (Some(a), Some(b)) => Some(Expr::Num(op.apply(&a, &b, self)?)),
+                _ => match a.reduce()? {
+                    Some(a) => Some(Expr::Binary(*op, Box::new(a), b.clone())),
+                    None => b.reduce()?.map(|b| Expr::Binary(*op, a.clone(), Box::new(b))),
+                },
+            },
+        })
+    }
+
+    /// Returns this expression with each variable replaced by its value, which is looked up
+    /// with the given function.  Returns an error naming the first variable without a value.
+    pub fn substitute<F: Fn(&str) -> Option<i64>>(&self, lookup: &F) -> Result<Expr, EvalError> {
+        Ok(match self {
+            Expr::Num(value) => Expr::Num(*value),
+            Expr::Var(name) => Expr::Num(lookup(name).ok_or_else(|| EvalError::Undefined(name.clone()))?),
+            Expr::Neg(expr) => Expr::Neg(Box::new(expr.substitute(lookup)?)),
+            Expr::Binary(op, a, b) => Expr::Binary(*op, Box::new(a.substitute(lookup)?), Box::new(b.substitute(lookup)?)),
+        })
+    }
+
+    /// Returns every step of evaluating this expression, one operation at a time: this
+    /// expression, each reduced expression, and finally its value.
+    pub fn trace(&self) -> Result<Vec<Expr>, EvalError> {
+        let mut steps = vec![self.clone()];
+        while let Some(next) = steps.last().unwrap().reduce()? {
+            steps.push(next);
+        }
+
+        Ok(steps)
+    }
+
+    /// Returns a Display that writes this expression with only the parentheses the given
+    /// precedence needs to parse it back into the same expression.
+    pub fn display_with<'a>(&'a self, precedence: &'a Precedence) -> Printed<'a> {
+        Printed { expr: self, precedence }
+    }
+}
+
+impl Display for Expr {
+    /// Writes the expression with every operation in parentheses, like '(2 * (3 + 4))', which
+    /// shows how parsing grouped the operations.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(value) => write!(f, "{}", value),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Neg(expr) if needs_parens_to_negate(expr) => write!(f, "-({})", expr),
+            Expr::Neg(expr) => write!(f, "-{}", expr),
+            Expr::Binary(op, a, b) => write!(f, "({} {} {})", a, op, b),
+        }
+    }
+}
+
+/// Returns whether negating the given expression needs parentheses so it doesn't run into
+/// another '-'.
+fn needs_parens_to_negate(expr: &Expr) -> bool {
+    matches!(expr, Expr::Neg(_)) || matches!(expr, Expr::Num(value) if *value < 0)
+}
+
+/// Printed writes an expression with as few parentheses as its precedence allows.
+pub struct Printed<'a> {
+    expr: &'a Expr,
+    precedence: &'a Precedence,
+}
+
+impl Printed<'_> {
+    /// Writes the given operand of an operator at the given level, in parentheses if it's an
+    /// operation that would otherwise be grouped differently.  Operators on the same level are
+    /// grouped left to right, so only right operands on the same level need parentheses.
+    fn operand(&self, f: &mut Formatter<'_>, operand: &Expr, level: u8, right: bool) -> fmt::Result {
+        let parens = match operand {
+            Expr::Binary(op, _, _) => {
+                let operand_level = self.precedence.level(*op);
+                operand_level < level || (right && operand_level == level)
+            }
+            _ => false,
+        };
+
+        let printed = operand.display_with(self.precedence);
+        if parens { write!(f, "({})", printed) } else { write!(f, "{}", printed) }
+    }
+}
+
+impl Display for Printed<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.expr {
+            Expr::Num(value) => write!(f, "{}", value),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Neg(expr) if needs_parens_to_negate(expr) || matches!(**expr, Expr::Binary(..)) => {
+                write!(f, "-({})", expr.display_with(self.precedence))
+            }
+            Expr::Neg(expr) => write!(f, "-{}", expr.display_with(self.precedence)),
+            Expr::Binary(op, a, b) => {
+                let level = self.precedence.level(*op);
+
+                self.operand(f, a, level, false)?;
+                write!(f, " {} ", op)?;
+                self.operand(f, b, level, true)
+            }
+        }
+    }
+}
+
+/// EvalError is why an expression doesn't have a value - either it couldn't be parsed, or one
+/// of its operations doesn't have an answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EvalError {
+    /// The expression couldn't be parsed.
+    Parse(ParseError),
+
+    /// The expression divides by zero.
+    DivideByZero,
+
+    /// The expression uses a variable that doesn't have a value.
+    Undefined(String),
+
+    /// The answer to this operation doesn't fit in 64 bits.
+    Overflow(String),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Parse(e) => write!(f, "{}", e),
+            EvalError::DivideByZero => write!(f, "Division by zero."),
+            EvalError::Undefined(name) => write!(f, "Variable '{}' isn't defined.", name),
+            EvalError::Overflow(expr) => write!(f, "{} overflows a 64-bit integer.", expr),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+impl From<ParseError> for EvalError {
+    fn from(e: ParseError) -> Self {
+        EvalError::Parse(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Mode};
+
+    use super::*;
+
+    /// Returns each step of evaluating the expression in the given mode, printed the way
+    /// the mode would parse it.
+    fn trace(s: &str, mode: Mode) -> Vec<String> {
+        let precedence = mode.precedence();
+        parse(s, &precedence).unwrap().trace().unwrap().iter()
+            .map(|step| step.display_with(&precedence).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn display_fully_parenthesized() {
+        let s = "2 * 3 + (4 * 5)";
+
+        assert_eq!("((2 * 3) + (4 * 5))", parse(s, &Mode::LeftToRight.precedence()).unwrap().to_string());
+        assert_eq!("(2 * (3 + (4 * 5)))", parse(s, &Mode::AddBeforeTimes.precedence()).unwrap().to_string());
+        assert_eq!("(-(-3) - -(1 + 2))", parse("- -3 - -(1 + 2)", &Precedence::flat()).unwrap().to_string());
+    }
+
+    #[test]
+    fn display_with_precedence() {
+        for s in ["1 + 2 * 3 + 4", "1 + (2 * 3) + 4", "1 - (2 - 3)", "-(2 + 3) * -4", "8 / (4 / 2) * (1 + 1)"].iter() {
+            for &mode in [Mode::LeftToRight, Mode::AddBeforeTimes].iter() {
+                let precedence = mode.precedence();
+                let expr = parse(s, &precedence).unwrap();
+                let printed = expr.display_with(&precedence).to_string();
+
+                assert_eq!(Ok(expr), parse(&printed, &precedence), "{} printed as {}", s, printed);
+            }
+        }
+
+        let precedence = Mode::LeftToRight.precedence();
+        assert_eq!("1 + 2 * 3 + 4", parse("((1 + 2) * 3) + 4", &precedence).unwrap().display_with(&precedence).to_string());
+    }
+
+    #[test]
+    fn trace_sample() {
+        assert_eq!(vec!["2 * 3 + (4 * 5)", "2 * 3 + 20", "2 * 23", "46"], trace("2 * 3 + (4 * 5)", Mode::AddBeforeTimes));
+        assert_eq!(vec!["2 * 3 + 4 * 5", "6 + 4 * 5", "10 * 5", "50"], trace("2 * 3 + 4 * 5", Mode::LeftToRight));
+        assert_eq!(vec!["-(3 - 5) * 2", "-(-2) * 2", "4"], trace("-(3 - 5) * 2", Mode::LeftToRight));
+    }
+
+    #[test]
+    fn substitute_variables() {
+        let expr = parse("x * (y + -x)", &Precedence::flat()).unwrap();
+        let lookup = |name: &str| match name { "x" => Some(3), "y" => Some(10), _ => None };
+
+        assert_eq!("(x * (y + -x))", expr.to_string());
+        assert_eq!(Err(EvalError::Undefined("x".to_owned())), expr.eval());
+        assert_eq!(Ok(21), expr.substitute(&lookup).unwrap().eval());
+        assert_eq!(Err(EvalError::Undefined("z".to_owned())), parse("z + 1", &Precedence::flat()).unwrap().substitute(&lookup));
+    }
+
+    #[test]
+    fn eval_overflow() {
+        let flat = Precedence::flat();
+        let overflow = |expr: &str| Err::<i64, _>(EvalError::Overflow(expr.to_owned()));
+
+        assert_eq!(overflow("((1 + 4611686018427387904) * 2)"), parse("1 + 4611686018427387904 * 2 - 5", &flat).unwrap().eval());
+        assert_eq!(overflow("-((0 - 9223372036854775807) - 1)"), parse("-(0 - 9223372036854775807 - 1)", &flat).unwrap().eval());
+        assert_eq!(Err(EvalError::Overflow("(9223372036854775807 + 1)".to_owned())), parse("9223372036854775807 + 1", &flat).unwrap().trace());
+    }
+
+    #[test]
+    fn eval_big() {
+        let expr = parse("(4611686018427387904 * 4 + 1) * -(3000000000 * 3000000000)", &Precedence::flat()).unwrap();
+
+        assert_eq!("-166020696663385964553000000000000000000", expr.eval_as::<num_bigint::BigInt>().unwrap().to_string());
+    }
+
+    #[test]
+    fn trace_divide_by_zero() {
+        assert_eq!(Err(EvalError::DivideByZero), parse("1 / (2 - 2)", &Precedence::flat()).unwrap().trace());
+    }
+}
@@ -1,181 +1,258 @@
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-use crate::Token::{Num, Plus, Times};
-use crate::Mode::AddBeforeTimes;
+use std::fmt::{self, Display, Formatter};
+use std::io::BufRead;
+use std::str::FromStr;
 
+use crate::Mode::{AddBeforeTimes, LeftToRight};
+use common::{LoadError, ParseError, Solution};
+use num_bigint::BigInt;
+
+pub use expr::{BinOp, EvalError, Expr};
+pub use number::Number;
+pub use parser::{parse, parse_statement, Precedence, Statement};
+
+pub mod calc;
+pub mod expr;
+mod number;
+pub mod parser;
+mod token;
+
+/// Mode is one of the puzzle's rules for grouping operations.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Mode {
+    /// Every operation has the same precedence, so expressions are evaluated left to right.
     LeftToRight,
+
+    /// Addition and subtraction are grouped before multiplication and division.
     AddBeforeTimes,
 }
 
-enum Token {
-    Num(i64),
-    Plus(Box<Token>, Box<Token>),
-    Times(Box<Token>, Box<Token>),
+impl FromStr for Mode {
+    type Err = ParseError;
+
+    /// Parses a mode's name, 'left-to-right' or 'add-before-times', or 'left' or 'add' for short.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left-to-right" | "left" => Ok(LeftToRight),
+            "add-before-times" | "add" => Ok(AddBeforeTimes),
+            _ => Err(ParseError::new(s, 1, "'left-to-right' or 'add-before-times'")),
+        }
+    }
 }
 
-impl Token {
-    fn run(&self) -> i64 {
+impl Display for Mode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Num(value) => *value,
-            Plus(a, b) => a.run() + b.run(),
-            Times(a, b) => a.run() * b.run(),
+            LeftToRight => write!(f, "left-to-right"),
+            AddBeforeTimes => write!(f, "add-before-times"),
         }
     }
 }
 
-/// Evaluates the expression in the given string.
-fn parse(s: &str, mode: Mode) -> Token {
-    let mut chars: Vec<char> = s.replace(" ", "").chars().collect();
-
-    // For add-before-times, insert parenthesis around addition
-    if mode == AddBeforeTimes {
-        let mut i = 0;
-        while i < chars.len() {
-            if chars[i] == '+' {
-                let left = match chars[i - 1] {
-                    num if num >= '0' && num <= '9' => i - 1,
-                    ')' => matching_open_paren(&chars, i - 1),
-                    _ => panic!("Plus must have a value to the left."),
-                };
-
-                chars.insert(left, '(');
-                i += 1;
-
-                let right = match chars[i + 1] {
-                    num if num >= '0' && num <= '9' => i + 2,
-                    '(' => matching_close_paren(&chars, i + 1) + 1,
-                    _ => panic!("Plus must have a value to the right."),
-                };
-
-                chars.insert(right, ')');
-
-                i += 2;
-            } else {
-                i += 1;
-            }
+impl Mode {
+    /// Returns the precedence table for this mode.
+    pub fn precedence(self) -> Precedence {
+        match self {
+            LeftToRight => Precedence::flat(),
+            AddBeforeTimes => Precedence::flat().with(BinOp::Add, 1).with(BinOp::Sub, 1),
         }
     }
+}
+
+/// Arithmetic is the kind of numbers expressions are evaluated with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Arithmetic {
+    /// 64-bit integers, where an operation that overflows is an error.
+    Checked,
+
+    /// Big integers, which never overflow, so sums are exact no matter how long the lines are.
+    /// Number literals are still read as 64-bit integers, so a line with a literal that doesn't
+    /// fit in one is a parse error in either kind of arithmetic.
+    Exact,
+}
+
+/// Evaluates the expression in the given string.
+pub fn eval(s: &str, mode: Mode) -> Result<i64, EvalError> {
+    parse(s, &mode.precedence())?.eval()
+}
+
+/// Explains how the given mode evaluates an expression: the expression with every operation
+/// in parentheses, followed by each step of evaluating it, one operation at a time.
+pub fn explain(s: &str, mode: Mode) -> Result<Vec<String>, EvalError> {
+    calc::Calculator::new(mode).explain(s)
+}
 
-    parse_section(&chars, 0, chars.len())
+/// Parses one expression per line, returning an error that points at the first line that
+/// isn't a valid expression.
+fn parse_expressions(lines: &[String], precedence: &Precedence) -> Result<Vec<Expr>, LoadError> {
+    lines.iter().enumerate()
+        .map(|(i, line)| parse(line, precedence).map_err(|e| LoadError::Parse(e.at_line(i + 1))))
+        .collect()
 }
 
-/// Evaluates a section of the given expression.
-fn parse_section(chars: &Vec<char>, from: usize, to: usize) -> Token {
-    let (mut left, mut i) = parse_value(chars, from);
+/// Returns the sum of the given expressions as the given type of number, or an error
+/// describing the first one that doesn't have a value.
+fn sum<N: Number>(expressions: &[Expr]) -> Result<N, LoadError> {
+    expressions.iter().enumerate().try_fold(N::from_i64(0), |sum, (i, expr)| {
+        let value = expr.eval_as::<N>().map_err(|e| LoadError::Invalid(format!("line {}: {}", i + 1, e)))?;
 
-    while i < to {
-        let (right, new_i) = parse_value(chars, i + 1);
+        sum.checked_op(BinOp::Add, &value)
+            .ok_or_else(|| LoadError::invalid("the sum of the expressions overflows a 64-bit integer."))
+    })
+}
 
-        left = match chars[i] {
-            '+' => Plus(Box::from(left), Box::from(right)),
-            '*' => Times(Box::from(left), Box::from(right)),
-            _ => panic!("Section {}..{} must have an operator", from, to),
-        };
+/// Loads expressions from the given file (one per line) and returns their sum.  With checked
+/// arithmetic, returns an error if any operation overflows.
+pub fn sum_expressions(filename: &str, mode: Mode, arithmetic: Arithmetic) -> Result<BigInt, LoadError> {
+    let expressions = load_expressions(filename, mode)?;
 
-        i = new_i;
+    match arithmetic {
+        Arithmetic::Checked => sum::<i64>(&expressions).map(BigInt::from),
+        Arithmetic::Exact => sum(&expressions),
     }
+}
 
-    left
+/// Loads and parses expressions from the given file, one per line.
+fn load_expressions(filename: &str, mode: Mode) -> Result<Vec<Expr>, LoadError> {
+    let lines = read_expressions(common::open(filename)?)?;
+    parse_expressions(&lines, &mode.precedence())
 }
 
-/// Parses a number or parenthesis value starting at the given position.
-/// Returns the parsed token and the index of the next unparsed character.
-fn parse_value(chars: &Vec<char>, from: usize) -> (Token, usize) {
-    match chars[from] {
-        '(' => {
-            let close = matching_close_paren(chars, from);
-            (parse_section(chars, from + 1, close), close + 1)
-        },
+/// Reads expressions from the given reader, one per line.
+pub fn read_expressions<R: BufRead>(reader: R) -> Result<Vec<String>, LoadError> {
+    common::read_lines(reader)
+}
 
-        num if num >= '0' && num <= '9' => {
-            (Num(num as i64 - '0' as i64), from + 1)
-        },
+pub struct Day18 {
+    left_to_right: Vec<Expr>,
+    add_before_times: Vec<Expr>,
+}
+
+impl Solution for Day18 {
+    type Part1 = Result<i64, LoadError>;
+    type Part2 = Result<i64, LoadError>;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        let lines = read_expressions(input.as_bytes())?;
+
+        Ok(Day18 {
+            left_to_right: parse_expressions(&lines, &LeftToRight.precedence())?,
+            add_before_times: parse_expressions(&lines, &AddBeforeTimes.precedence())?,
+        })
+    }
 
-        _ => panic!("Section must start with a number or parenthesis."),
+    fn part1(&self) -> Result<i64, LoadError> {
+        sum(&self.left_to_right)
+    }
+
+    fn part2(&self) -> Result<i64, LoadError> {
+        sum(&self.add_before_times)
     }
 }
 
-/// Returns the index of the close parentheses that matches the one under from, looking right.
-fn matching_close_paren(chars: &Vec<char>, from: usize) -> usize {
-    let mut i = from + 1;
-    let mut num_parens = 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    while num_parens > 0 {
-        match chars[i] {
-            '(' => num_parens += 1,
-            ')' => num_parens -= 1,
-            _ => {},
-        }
+    #[test]
+    fn samples_left_to_right() {
+        assert_eq!(Ok(71), eval("1 + 2 * 3 + 4 * 5 + 6", LeftToRight));
+        assert_eq!(Ok(51), eval("1 + (2 * 3) + (4 * (5 + 6))", LeftToRight));
+        assert_eq!(Ok(26), eval("2 * 3 + (4 * 5)", LeftToRight));
+        assert_eq!(Ok(437), eval("5 + (8 * 3 + 9 + 3 * 4 * 3)", LeftToRight));
+        assert_eq!(Ok(12240), eval("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", LeftToRight));
+        assert_eq!(Ok(13632), eval("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", LeftToRight));
+    }
 
-        if num_parens == 0 {
-            return i;
-        }
+    #[test]
+    fn samples_add_before_times() {
+        assert_eq!(Ok(231), eval("1 + 2 * 3 + 4 * 5 + 6", AddBeforeTimes));
+        assert_eq!(Ok(51), eval("1 + (2 * 3) + (4 * (5 + 6))", AddBeforeTimes));
+        assert_eq!(Ok(46), eval("2 * 3 + (4 * 5)", AddBeforeTimes));
+        assert_eq!(Ok(1445), eval("5 + (8 * 3 + 9 + 3 * 4 * 3)", AddBeforeTimes));
+        assert_eq!(Ok(669060), eval("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", AddBeforeTimes));
+        assert_eq!(Ok(23340), eval("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", AddBeforeTimes));
+    }
 
-        i += 1;
+    #[test]
+    fn extended_operators() {
+        assert_eq!(Ok(-10), eval("10 - 2 * 3 / -2 - -2", LeftToRight));
+        assert_eq!(Ok(100), eval("100 / 5 - 3 * 2", AddBeforeTimes));
+        assert_eq!(Ok(13), eval("-(2 + 3) * -2 + 3", LeftToRight));
+        assert_eq!(Err(EvalError::DivideByZero), eval("7 / (3 - 3)", LeftToRight));
     }
 
-    unreachable!()
-}
+    #[test]
+    fn custom_precedence() {
+        let times_first = Precedence::flat().with(BinOp::Mul, 2).with(BinOp::Div, 2).with(BinOp::Add, 1);
 
-/// Returns the index of the open parentheses that matches the one under to, looking left.
-fn matching_open_paren(chars: &Vec<char>, to: usize) -> usize {
-    let mut i = to - 1;
-    let mut num_parens = 1;
+        assert_eq!(Ok(-16), parse("1 + 2 * 3 - 4 * 5 - 3", &times_first).unwrap().eval());
+    }
 
-    while num_parens > 0 {
-        match chars[i] {
-            ')' => num_parens += 1,
-            '(' => num_parens -= 1,
-            _ => {},
-        }
+    #[test]
+    fn explain_modes() {
+        let s = "2 * 3 + (4 * 5)";
+
+        assert_eq!(Ok(vec![
+            "((2 * 3) + (4 * 5))".to_owned(), "2 * 3 + (4 * 5)".to_owned(), "6 + (4 * 5)".to_owned(), "6 + 20".to_owned(), "26".to_owned(),
+        ]), explain(s, LeftToRight));
+        assert_eq!(Ok(vec![
+            "(2 * (3 + (4 * 5)))".to_owned(), "2 * 3 + (4 * 5)".to_owned(), "2 * 3 + 20".to_owned(), "2 * 23".to_owned(), "46".to_owned(),
+        ]), explain(s, AddBeforeTimes));
+    }
+
+    #[test]
+    fn sum_overflow() {
+        let day = Day18::parse("1 + 2\n3 * 4611686018427387904\n").unwrap();
 
-        if num_parens == 0 {
-            return i;
+        match sum::<i64>(&day.left_to_right) {
+            Err(LoadError::Invalid(message)) => assert_eq!("line 2: (3 * 4611686018427387904) overflows a 64-bit integer.", message),
+            other => panic!("Expected an overflow, got {:?}", other),
         }
 
-        i -= 1;
+        assert_eq!("13835058055282163715", sum::<BigInt>(&day.left_to_right).unwrap().to_string());
     }
 
-    unreachable!()
-}
+    #[test]
+    fn exact_literal_limit() {
+        let lines = vec!["1 + 9223372036854775807".to_owned(), "2 * 9223372036854775808".to_owned()];
 
-fn eval(s: &str, mode: Mode) -> i64 {
-    let expr = parse(s, mode);
-    expr.run()
-}
+        let expressions = parse_expressions(&lines[..1], &LeftToRight.precedence()).unwrap();
+        assert_eq!("9223372036854775808", sum::<BigInt>(&expressions).unwrap().to_string());
 
-/// Loads expressions from the given file (one per line) and returns their sum.
-pub fn sum_expressions(filename: &str, mode: Mode) -> i64 {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+        match parse_expressions(&lines, &LeftToRight.precedence()) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("2 * 9223372036854775808", 5, "a number that fits in 64 bits").at_line(2), e),
+            other => panic!("Expected a parse error on line 2, got {:?}", other.map(|_| ())),
+        }
+    }
 
-    f.lines().fold(0, |sum, line| sum + eval(line.unwrap().as_str(), mode))
-}
+    #[test]
+    fn parse_error_line() {
+        match Day18::parse("1 + 2\n3 * (4 +\n") {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("3 * (4 +", 9, "a number, a variable, '-', or '('").at_line(2), e),
+            _ => panic!("Expected a parse error on line 2."),
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::Mode::{LeftToRight, AddBeforeTimes};
+    #[test]
+    fn solution_sample() {
+        let day = Day18::parse("2 * 3 + (4 * 5)\n5 + (8 * 3 + 9 + 3 * 4 * 3)\n").unwrap();
+
+        assert_eq!(26 + 437, day.part1().unwrap());
+        assert_eq!(46 + 1445, day.part2().unwrap());
+    }
 
     #[test]
-    fn samples_left_to_right() {
-        assert_eq!(71, eval("1 + 2 * 3 + 4 * 5 + 6", LeftToRight));
-        assert_eq!(51, eval("1 + (2 * 3) + (4 * (5 + 6))", LeftToRight));
-        assert_eq!(26, eval("2 * 3 + (4 * 5)", LeftToRight));
-        assert_eq!(437, eval("5 + (8 * 3 + 9 + 3 * 4 * 3)", LeftToRight));
-        assert_eq!(12240, eval("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", LeftToRight));
-        assert_eq!(13632, eval("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", LeftToRight));
+    fn solution_overflow() {
+        let day = Day18::parse("3 * 4611686018427387904\n").unwrap();
+
+        assert_eq!("Invalid input: line 1: (3 * 4611686018427387904) overflows a 64-bit integer.", day.part1().unwrap_err().to_string());
     }
 
     #[test]
-    fn samples_add_before_times() {
-        assert_eq!(231, eval("1 + 2 * 3 + 4 * 5 + 6", AddBeforeTimes));
-        assert_eq!(51, eval("1 + (2 * 3) + (4 * (5 + 6))", AddBeforeTimes));
-        assert_eq!(46, eval("2 * 3 + (4 * 5)", AddBeforeTimes));
-        assert_eq!(1445, eval("5 + (8 * 3 + 9 + 3 * 4 * 3)", AddBeforeTimes));
-        assert_eq!(669060, eval("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", AddBeforeTimes));
-        assert_eq!(23340, eval("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", AddBeforeTimes));
+    fn sum_expressions_exact() {
+        let checked = sum_expressions("input.txt", AddBeforeTimes, Arithmetic::Checked).unwrap();
+
+        assert_eq!(BigInt::from(328920644404583_i64), checked);
+        assert_eq!(checked, sum_expressions("input.txt", AddBeforeTimes, Arithmetic::Exact).unwrap());
     }
 }
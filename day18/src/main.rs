@@ -1,7 +1,10 @@
-use day18::sum_expressions;
+use common::LoadError;
+use day18::{sum_expressions, Arithmetic};
 use day18::Mode::{LeftToRight, AddBeforeTimes};
 
-fn main() {
-    println!("Part 1: {}", sum_expressions("input.txt", LeftToRight));
-    println!("Part 2: {}", sum_expressions("input.txt", AddBeforeTimes));
+fn main() -> Result<(), LoadError> {
+    println!("Part 1: {}", sum_expressions("input.txt", LeftToRight, Arithmetic::Checked)?);
+    println!("Part 2: {}", sum_expressions("input.txt", AddBeforeTimes, Arithmetic::Checked)?);
+
+    Ok(())
 }
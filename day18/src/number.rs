@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use num_bigint::BigInt;
+
+use crate::BinOp;
+
+/// Number is a type expressions can be evaluated in.  Operations return None if the answer
+/// doesn't fit in the type.
+pub trait Number: Clone + Display {
+    /// Returns the given literal as this type.
+    fn from_i64(value: i64) -> Self;
+
+    /// Returns whether this number is zero, which can't be divided by.
+    fn is_zero(&self) -> bool;
+
+    /// Returns the negation of this number.
+    fn checked_neg(&self) -> Option<Self>;
+
+    /// Returns this number combined with another by the given operator.  Division rounds
+    /// towards zero, and the divisor isn't zero.
+    fn checked_op(&self, op: BinOp, other: &Self) -> Option<Self>;
+}
+
+impl Number for i64 {
+    fn from_i64(value: i64) -> Self {
+        value
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        i64::checked_neg(*self)
+    }
+
+    fn checked_op(&self, op: BinOp, other: &Self) -> Option<Self> {
+        match op {
+            BinOp::Add => self.checked_add(*other),
+            BinOp::Sub => self.checked_sub(*other),
+            BinOp::Mul => self.checked_mul(*other),
+            BinOp::Div => self.checked_div(*other),
+        }
+    }
+}
+
+/// Big integers never overflow, so long expressions have exact values.
+impl Number for BigInt {
+    fn from_i64(value: i64) -> Self {
+        BigInt::from(value)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == BigInt::from(0)
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+
+    fn checked_op(&self, op: BinOp, other: &Self) -> Option<Self> {
+        Some(match op {
+            BinOp::Add => self + other,
+            BinOp::Sub => self - other,
+            BinOp::Mul => self * other,
+            BinOp::Div => self / other,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_i64() {
+        assert_eq!(Some(6), 2i64.checked_op(BinOp::Mul, &3));
+        assert_eq!(Some(-2), (-7i64).checked_op(BinOp::Div, &3));
+        assert_eq!(None, i64::MAX.checked_op(BinOp::Add, &1));
+        assert_eq!(None, i64::MIN.checked_op(BinOp::Div, &-1));
+        assert_eq!(None, Number::checked_neg(&i64::MIN));
+    }
+
+    #[test]
+    fn big() {
+        let big = BigInt::from(i64::MAX);
+
+        assert_eq!("85070591730234615847396907784232501249", big.checked_op(BinOp::Mul, &big).unwrap().to_string());
+        assert_eq!(BigInt::from(-2), BigInt::from(-7).checked_op(BinOp::Div, &BigInt::from(3)).unwrap());
+    }
+}
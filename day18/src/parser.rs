@@ -0,0 +1,226 @@
+use common::ParseError;
+
+use crate::{BinOp, Expr};
+use crate::token::{tokenize, Token};
+
+/// Precedence gives each operator a level.  Operators on higher levels are grouped first, and
+/// operators on the same level are grouped left to right.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Precedence {
+    levels: [u8; 4],
+}
+
+impl Precedence {
+    /// Returns a table with every operator on the same level, so expressions are evaluated
+    /// left to right.
+    pub fn flat() -> Precedence {
+        Precedence { levels: [0; 4] }
+    }
+
+    /// Returns this table with the given operator moved to a level.
+    pub fn with(mut self, op: BinOp, level: u8) -> Precedence {
+        self.levels[Precedence::index(op)] = level;
+        self
+    }
+
+    /// Returns the level of the given operator.
+    pub fn level(&self, op: BinOp) -> u8 {
+        self.levels[Precedence::index(op)]
+    }
+
+    /// Returns where the given operator's level is stored.
+    fn index(op: BinOp) -> usize {
+        BinOp::ALL.iter().position(|&other| other == op).unwrap()
+    }
+}
+
+/// Statement is one line of a calculator program: either an assignment like 'let x = 3 + 4',
+/// or an expression.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Statement {
+    Let(String, Expr),
+    Expr(Expr),
+}
+
+/// Keyword that starts an assignment.  It can't be used as a variable name.
+const LET: &str = "let";
+
+/// Parses an expression, grouping operations by the given precedence.  Negation binds tighter
+/// than any operator.
+pub fn parse(s: &str, precedence: &Precedence) -> Result<Expr, ParseError> {
+    let mut parser = Parser { s, tokens: tokenize(s)?, next: 0, precedence };
+    let expr = parser.expression(0)?;
+    parser.end()?;
+
+    Ok(expr)
+}
+
+/// Parses an assignment like 'let x = 3 + 4', or an expression.
+pub fn parse_statement(s: &str, precedence: &Precedence) -> Result<Statement, ParseError> {
+    let mut parser = Parser { s, tokens: tokenize(s)?, next: 0, precedence };
+
+    let statement = if parser.peek() == Some(Token::Name(LET)) {
+        parser.next += 1;
+
+        let name = match parser.peek() {
+            Some(Token::Name(name)) if name != LET => name.to_owned(),
+            _ => return Err(parser.error("a variable name")),
+        };
+        parser.next += 1;
+
+        if parser.peek() != Some(Token::Assign) {
+            return Err(parser.error("'='"));
+        }
+        parser.next += 1;
+
+        Statement::Let(name, parser.expression(0)?)
+    } else {
+        Statement::Expr(parser.expression(0)?)
+    };
+
+    parser.end()?;
+    Ok(statement)
+}
+
+/// Parser climbs through the precedence levels of an expression's tokens.
+struct Parser<'a> {
+    s: &'a str,
+    tokens: Vec<(usize, Token<'a>)>,
+    next: usize,
+    precedence: &'a Precedence,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.next).map(|&(_, token)| token)
+    }
+
+    /// Returns an error if there are tokens left over.
+    fn end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("an operator")),
+        }
+    }
+
+    /// Returns an error at the next token (or the end of the expression), which isn't what
+    /// was expected.
+    fn error(&self, expected: &str) -> ParseError {
+        let column = self.tokens.get(self.next).map_or(self.s.len() + 1, |&(column, _)| column);
+        ParseError::new(self.s, column, expected)
+    }
+
+    /// Parses an expression made of operators at the given level or higher.
+    fn expression(&mut self, min_level: u16) -> Result<Expr, ParseError> {
+        let mut left = self.unary()?;
+
+        while let Some(Token::Op(op)) = self.peek() {
+            let level = u16::from(self.precedence.level(op));
+            if level < min_level {
+                break;
+            }
+
+            self.next += 1;
+            let right = self.expression(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a number, a variable, a parenthesized expression, or a negated value.
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        let token = self.peek().ok_or_else(|| self.error(VALUE))?;
+
+        match token {
+            Token::Num(value) => {
+                self.next += 1;
+                Ok(Expr::Num(value))
+            }
+            Token::Name(name) if name != LET => {
+                self.next += 1;
+                Ok(Expr::Var(name.to_owned()))
+            }
+            Token::Op(BinOp::Sub) => {
+                self.next += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Token::Open => {
+                self.next += 1;
+                let expr = self.expression(0)?;
+
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.next += 1;
+                        Ok(expr)
+                    }
+                    _ => Err(self.error("an operator or ')'")),
+                }
+            }
+            _ => Err(self.error(VALUE)),
+        }
+    }
+}
+
+/// What's expected where a value goes.
+const VALUE: &str = "a number, a variable, '-', or '('";
+
+#[cfg(test)]
+mod tests {
+    use crate::Expr::{Binary, Neg, Num};
+
+    use super::*;
+
+    fn binary(op: BinOp, a: Expr, b: Expr) -> Expr {
+        Binary(op, Box::new(a), Box::new(b))
+    }
+
+    #[test]
+    fn parse_precedence() {
+        let standard = Precedence::flat().with(BinOp::Mul, 1).with(BinOp::Div, 1);
+
+        assert_eq!(
+            Ok(binary(BinOp::Sub, binary(BinOp::Add, Num(1), binary(BinOp::Mul, Num(2), Num(3))), Num(4))),
+            parse("1 + 2 * 3 - 4", &standard),
+        );
+        assert_eq!(
+            Ok(binary(BinOp::Mul, binary(BinOp::Add, Num(1), Num(2)), Neg(Box::new(Num(3))))),
+            parse("1 + 2 * -3", &Precedence::flat()),
+        );
+    }
+
+    #[test]
+    fn parse_multi_digit() {
+        assert_eq!(Ok(binary(BinOp::Div, Num(120), Num(45))), parse("120/45", &Precedence::flat()));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let flat = Precedence::flat();
+
+        assert_eq!(Err(ParseError::new("1 +", 4, VALUE)), parse("1 +", &flat));
+        assert_eq!(Err(ParseError::new("(1 + 2", 7, "an operator or ')'")), parse("(1 + 2", &flat));
+        assert_eq!(Err(ParseError::new("1 + 2)", 6, "an operator")), parse("1 + 2)", &flat));
+        assert_eq!(Err(ParseError::new("2 3", 3, "an operator")), parse("2 3", &flat));
+        assert_eq!(Err(ParseError::new("* 3", 1, VALUE)), parse("* 3", &flat));
+        assert_eq!(Err(ParseError::new("", 1, VALUE)), parse("", &flat));
+        assert_eq!(Err(ParseError::new("let x = 2", 1, VALUE)), parse("let x = 2", &flat));
+    }
+
+    #[test]
+    fn parse_statements() {
+        let flat = Precedence::flat();
+
+        assert_eq!(
+            Ok(Statement::Let("total".to_owned(), binary(BinOp::Add, Expr::Var("x".to_owned()), Num(4)))),
+            parse_statement("let total = x + 4", &flat),
+        );
+        assert_eq!(Ok(Statement::Expr(Expr::Var("total".to_owned()))), parse_statement("total", &flat));
+
+        assert_eq!(Err(ParseError::new("let = 3", 5, "a variable name")), parse_statement("let = 3", &flat));
+        assert_eq!(Err(ParseError::new("let let = 3", 5, "a variable name")), parse_statement("let let = 3", &flat));
+        assert_eq!(Err(ParseError::new("let x 3", 7, "'='")), parse_statement("let x 3", &flat));
+        assert_eq!(Err(ParseError::new("x = 3", 3, "an operator")), parse_statement("x = 3", &flat));
+    }
+}
@@ -0,0 +1,98 @@
+use common::ParseError;
+
+use crate::BinOp;
+
+/// Token is one piece of an expression.  '-' is always BinOp::Sub here - the parser decides
+/// whether it's subtraction or negation from where it appears.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Token<'a> {
+    Num(i64),
+    Op(BinOp),
+    Open,
+    Close,
+
+    /// A variable name or keyword, like 'x' or 'let'.
+    Name(&'a str),
+
+    /// The '=' in an assignment.
+    Assign,
+}
+
+/// Splits an expression into tokens, along with the column (from 1) each token starts at.
+/// Returns an error if the expression contains something that isn't a token.
+pub fn tokenize(s: &str) -> Result<Vec<(usize, Token<'_>)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            ' ' | '\t' => continue,
+            '+' => Token::Op(BinOp::Add),
+            '-' => Token::Op(BinOp::Sub),
+            '*' => Token::Op(BinOp::Mul),
+            '/' => Token::Op(BinOp::Div),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '=' => Token::Assign,
+            '0'..='9' => {
+                let mut end = i + 1;
+                while let Some(&(j, '0'..='9')) = chars.peek() {
+                    end = j + 1;
+                    chars.next();
+                }
+
+                let value = s[i..end].parse()
+                    .map_err(|_| ParseError::new(s, i + 1, "a number that fits in 64 bits"))?;
+                Token::Num(value)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = i + 1;
+                while let Some(&(j, c)) = chars.peek() {
+                    if !c.is_ascii_alphanumeric() && c != '_' {
+                        break;
+                    }
+
+                    end = j + 1;
+                    chars.next();
+                }
+
+                Token::Name(&s[i..end])
+            }
+            _ => return Err(ParseError::new(s, i + 1, "a number, a name, an operator, or a parenthesis")),
+        };
+
+        tokens.push((i + 1, token));
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_expression() {
+        let tokens: Vec<Token> = tokenize("12 *(3- -40)/5").unwrap().into_iter().map(|(_, token)| token).collect();
+
+        assert_eq!(vec![
+            Token::Num(12), Token::Op(BinOp::Mul), Token::Open, Token::Num(3), Token::Op(BinOp::Sub),
+            Token::Op(BinOp::Sub), Token::Num(40), Token::Close, Token::Op(BinOp::Div), Token::Num(5),
+        ], tokens);
+    }
+
+    #[test]
+    fn tokenize_assignment() {
+        let tokens: Vec<Token> = tokenize("let total_2=x*3").unwrap().into_iter().map(|(_, token)| token).collect();
+
+        assert_eq!(vec![
+            Token::Name("let"), Token::Name("total_2"), Token::Assign, Token::Name("x"), Token::Op(BinOp::Mul), Token::Num(3),
+        ], tokens);
+    }
+
+    #[test]
+    fn tokenize_invalid() {
+        assert_eq!(Err(ParseError::new("1 + $x", 5, "a number, a name, an operator, or a parenthesis")), tokenize("1 + $x"));
+        assert_eq!(Err(ParseError::new("2 * 99999999999999999999", 5, "a number that fits in 64 bits")), tokenize("2 * 99999999999999999999"));
+    }
+}
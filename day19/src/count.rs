@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+
+use crate::grammar::{Grammar, Rule};
+
+/// Set is the id of a set of strings that all have the same length, or None for the empty set.
+type Set = Option<usize>;
+
+/// The set that only holds the empty string.
+const EMPTY_STRING: usize = 0;
+
+/// Sets stores sets of same-length strings as trees of characters, where equal sets share an
+/// id.  Sharing keeps big languages small, and lets unions and concatenations be remembered.
+struct Sets {
+    /// children[id][c] is the set of strings that follow the alphabet's c'th character in the
+    /// set.  The empty string's set has no children.
+    children: Vec<Vec<Set>>,
+    /// counts[id] is the number of strings in the set.
+    counts: Vec<BigUint>,
+    ids: HashMap<Vec<Set>, usize>,
+    unions: HashMap<(usize, usize), Set>,
+    concats: HashMap<(usize, usize), Set>,
+}
+
+impl Sets {
+    fn new() -> Sets {
+        Sets {
+            children: vec![Vec::new()],
+            counts: vec![BigUint::from(1u32)],
+            ids: HashMap::from([(Vec::new(), EMPTY_STRING)]),
+            unions: HashMap::new(),
+            concats: HashMap::new(),
+        }
+    }
+
+    /// Returns the set of strings that start with each character followed by that character's
+    /// child set.
+    fn set(&mut self, children: Vec<Set>) -> Set {
+        if children.iter().all(Option::is_none) {
+            return None;
+        }
+
+        if let Some(&id) = self.ids.get(&children) {
+            return Some(id);
+        }
+
+        let id = self.children.len();
+        self.counts.push(children.iter().flatten().map(|&child| &self.counts[child]).sum());
+        self.children.push(children.clone());
+        self.ids.insert(children, id);
+
+        Some(id)
+    }
+
+    /// Returns the strings in either set.  Both sets must hold strings of the same length.
+    fn union(&mut self, a: Set, b: Set) -> Set {
+        let (a, b) = match (a, b) {
+            (None, set) | (set, None) => return set,
+            (Some(a), Some(b)) if a == b => return Some(a),
+            (Some(a), Some(b)) => (a.min(b), a.max(b)),
+        };
+
+        if let Some(&set) = self.unions.get(&(a, b)) {
+            return set;
+        }
+
+        let mut children = Vec::with_capacity(self.children[a].len());
+        for c in 0..self.children[a].len() {
+            children.push(self.union(self.children[a][c], self.children[b][c]));
+        }
+
+        let set = self.set(children);
+        self.unions.insert((a, b), set);
+
+        set
+    }
+
+    /// Returns every string in a followed by every string in b.
+    fn concat(&mut self, a: Set, b: Set) -> Set {
+        let (a, b) = match (a, b) {
+            (Some(EMPTY_STRING), set) | (set, Some(EMPTY_STRING)) => return set,
+            (Some(a), Some(b)) => (a, b),
+            _ => return None,
+        };
+
+        if let Some(&set) = self.concats.get(&(a, b)) {
+            return set;
+        }
+
+        let mut children = Vec::with_capacity(self.children[a].len());
+        for c in 0..self.children[a].len() {
+            children.push(self.concat(self.children[a][c], Some(b)));
+        }
+
+        let set = self.set(children);
+        self.concats.insert((a, b), set);
+
+        set
+    }
+}
+
+/// LengthCounts counts the strings each rule of a grammar matches by length, up to a maximum
+/// length.  The strings of each length are built up as shared sets, shortest first, so
+/// recursive rules are counted without listing their strings, and a string that a rule
+/// matches in more than one way is only counted once.
+pub struct LengthCounts {
+    alphabet: Vec<char>,
+    sets: Sets,
+    /// languages[rule][len] is the set of strings of length len that rule matches.
+    languages: HashMap<i32, Vec<Set>>,
+    /// counts[rule][len] is the number of strings of length len that rule matches.
+    counts: HashMap<i32, Vec<BigUint>>,
+}
+
+impl LengthCounts {
+    /// Counts every rule in the grammar by length, up to max_len characters.
+    pub fn new(grammar: &Grammar, max_len: usize) -> LengthCounts {
+        let mut alphabet: Vec<char> = grammar.rules()
+            .filter_map(|(_, rule)| match rule {
+                Rule::Char(c) => Some(*c),
+                Rule::Alternatives(_) => None,
+            })
+            .collect();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let mut sets = Sets::new();
+
+        let mut languages: HashMap<i32, Vec<Set>> = grammar.rules()
+            .map(|(id, _)| (id, vec![None; max_len + 1]))
+            .collect();
+
+        // suffixes[(rule, alt)][part][len] is the set of strings of length len that the
+        // alternative's rules from part on match together.  The last entry is past the end of
+        // the sequence, which only matches the empty string.
+        let mut suffixes = HashMap::new();
+        for (id, rule) in grammar.rules() {
+            if let Rule::Alternatives(alternatives) = rule {
+                for (alt, sequence) in alternatives.iter().enumerate() {
+                    let mut table = vec![vec![None; max_len + 1]; sequence.len() + 1];
+                    table[sequence.len()][0] = Some(EMPTY_STRING);
+
+                    suffixes.insert((id, alt), table);
+                }
+            }
+        }
+
+        for len in 0..=max_len {
+            // Single-rule alternatives and rules that match the empty string make rules depend on
+            // each other at the same length, maybe in a cycle, so the sets are rebuilt until none
+            // of them change.  Sets only grow, so this stops.
+            let mut changed = true;
+            while changed {
+                changed = false;
+
+                for (id, rule) in grammar.rules() {
+                    let language = match rule {
+                        Rule::Char(c) if len == 1 => {
+                            let children = alphabet.iter()
+                                .map(|other| if other == c { Some(EMPTY_STRING) } else { None })
+                                .collect();
+
+                            sets.set(children)
+                        }
+                        Rule::Char(_) => None,
+                        Rule::Alternatives(alternatives) => {
+                            let mut language = None;
+
+                            for (alt, sequence) in alternatives.iter().enumerate() {
+                                let table = suffixes.get_mut(&(id, alt)).unwrap();
+
+                                for part in (0..sequence.len()).rev() {
+                                    let first = languages.get(&sequence[part]);
+
+                                    let mut set = None;
+                                    for first_len in 0..=len {
+                                        let first = first.and_then(|first| first[first_len]);
+                                        let rest = table[part + 1][len - first_len];
+
+                                        if first.is_some() && rest.is_some() {
+                                            let strings = sets.concat(first, rest);
+                                            set = sets.union(set, strings);
+                                        }
+                                    }
+
+                                    table[part][len] = set;
+                                }
+
+                                language = sets.union(language, table[0][len]);
+                            }
+
+                            language
+                        }
+                    };
+
+                    let current = &mut languages.get_mut(&id).unwrap()[len];
+                    if *current != language {
+                        *current = language;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        let counts = languages.iter()
+            .map(|(&id, language)| {
+                let counts = language.iter()
+                    .map(|set| set.map_or(BigUint::ZERO, |set| sets.counts[set].clone()))
+                    .collect();
+
+                (id, counts)
+            })
+            .collect();
+
+        LengthCounts { alphabet, sets, languages, counts }
+    }
+
+    /// Returns the number of strings of each length the rule matches, indexed by length.
+    pub fn counts(&self, rule: i32) -> &[BigUint] {
+        self.counts.get(&rule).map_or(&[], |counts| counts.as_slice())
+    }
+
+    /// Returns the strings the rule matches up to the maximum length, shortest first and in
+    /// alphabetical order within each length.  Strings are built as they're needed, so the
+    /// first few strings of a big language are cheap.
+    pub fn strings(&self, rule: i32) -> impl Iterator<Item = String> + '_ {
+        self.languages.get(&rule)
+            .map_or(&[][..], |language| language.as_slice())
+            .iter()
+            .flatten()
+            .flat_map(move |&set| Strings { counts: self, stack: vec![(set, String::new())] })
+    }
+}
+
+/// Strings lists the strings in a set in alphabetical order.  Empty sets aren't stored, so
+/// every branch it follows leads to a string.
+struct Strings<'a> {
+    counts: &'a LengthCounts,
+    /// Sets left to list, with the prefix that comes before each of their strings.
+    stack: Vec<(usize, String)>,
+}
+
+impl Iterator for Strings<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some((set, prefix)) = self.stack.pop() {
+            if set == EMPTY_STRING {
+                return Some(prefix);
+            }
+
+            let children = self.counts.alphabet.iter().zip(&self.counts.sets.children[set]);
+            for (c, child) in children.rev() {
+                if let Some(child) = child {
+                    self.stack.push((*child, format!("{}{}", prefix, c)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{Explanation, Puzzle};
+
+    use super::*;
+
+    fn grammar(rules: &str) -> Grammar {
+        let mut grammar = Grammar::default();
+        grammar.patch(rules).unwrap();
+
+        grammar
+    }
+
+    fn counts(counts: &[BigUint]) -> Vec<u32> {
+        counts.iter().map(|count| u32::try_from(count).unwrap()).collect()
+    }
+
+    #[test]
+    fn count_finite() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
+        let length_counts = LengthCounts::new(puzzle.grammar(), 8);
+
+        assert_eq!(vec![0, 0, 0, 0, 0, 0, 8, 0, 0], counts(length_counts.counts(0)));
+        assert_eq!(vec![0, 0, 2, 0, 0, 0, 0, 0, 0], counts(length_counts.counts(3)));
+
+        let mut strings: Vec<String> = length_counts.strings(0).collect();
+        let mut expected = puzzle.get_rule(0);
+        strings.sort();
+        expected.sort();
+
+        assert_eq!(expected, strings);
+    }
+
+    #[test]
+    fn count_recursive() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
+        let grammar = puzzle.recursive_grammar().unwrap();
+        let length_counts = LengthCounts::new(&grammar, 20);
+
+        // Rules 42 and 31 match 16 strings each, all of length 5.  Rule 0 is some 42s followed
+        // by fewer 31s, so 15 characters are 42 42 31, and 20 are 42 42 42 31.
+        assert_eq!(BigUint::from(16u32 * 16 * 16), length_counts.counts(0)[15]);
+        assert_eq!(BigUint::from(16u32 * 16 * 16 * 16), length_counts.counts(0)[20]);
+        assert_eq!(BigUint::ZERO, length_counts.counts(0)[16]);
+
+        let first: Vec<String> = length_counts.strings(0).take(3).collect();
+        assert!(first.iter().all(|message| message.len() == 15 && grammar.matches(0, message)), "{:?}", first);
+    }
+
+    #[test]
+    fn strings_in_length_order() {
+        let grammar = grammar("0: 1 | 2 0 2\n1: \"a\"\n2: \"b\"");
+        let length_counts = LengthCounts::new(&grammar, 7);
+
+        assert_eq!(vec!["a", "bab", "bbabb", "bbbabbb"], length_counts.strings(0).collect::<Vec<_>>());
+        assert_eq!(vec![0, 1, 0, 1, 0, 1, 0, 1], counts(length_counts.counts(0)));
+    }
+
+    #[test]
+    fn unit_cycle() {
+        // Rules 0 and 1 match each other without reading anything, but still match only two strings.
+        let grammar = grammar("0: 1 | 2\n1: 0 | 3\n2: \"a\"\n3: \"b\"");
+        let length_counts = LengthCounts::new(&grammar, 3);
+
+        assert_eq!(vec![0, 2, 0, 0], counts(length_counts.counts(0)));
+        assert_eq!(vec!["a", "b"], length_counts.strings(1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ambiguous() {
+        let grammar = grammar("0: 1 1 | 2\n1: \"a\"\n2: 1 1\n3: 0 | 4 4\n4: 1 | 0");
+        let length_counts = LengthCounts::new(&grammar, 4);
+
+        assert_eq!(vec!["aa"], length_counts.strings(0).collect::<Vec<_>>());
+        assert_eq!(vec![0, 0, 1, 0, 0], counts(length_counts.counts(0)));
+
+        assert_eq!(vec!["aa", "aaa", "aaaa"], length_counts.strings(3).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty_alternatives() {
+        // 0 is any number of 'a's, and 3 skips over the empty 2s.
+        let mut grammar = Grammar::default();
+        grammar.insert(0, Rule::Alternatives(vec![vec![], vec![1, 0]]));
+        grammar.insert(1, Rule::Char('a'));
+        grammar.insert(2, Rule::Alternatives(vec![vec![]]));
+        grammar.insert(3, Rule::Alternatives(vec![vec![2, 1, 2, 3], vec![2]]));
+
+        let length_counts = LengthCounts::new(&grammar, 3);
+
+        assert_eq!(vec!["", "a", "aa", "aaa"], length_counts.strings(0).collect::<Vec<_>>());
+        assert_eq!(vec!["", "a", "aa", "aaa"], length_counts.strings(3).collect::<Vec<_>>());
+        assert_eq!(vec![1, 0, 0, 0], counts(length_counts.counts(2)));
+    }
+
+    #[test]
+    fn matches_agree_with_empty_alternatives() {
+        // 4 only completes the empty 2 before 1 waits on it at the same position.
+        let mut grammar = grammar("1: \"a\"\n5: \"b\"");
+        grammar.insert(0, Rule::Alternatives(vec![vec![], vec![1, 0]]));
+        grammar.insert(2, Rule::Alternatives(vec![vec![]]));
+        grammar.insert(3, Rule::Alternatives(vec![vec![2, 1, 2, 3], vec![2, 5]]));
+        grammar.insert(4, Rule::Alternatives(vec![vec![2, 2], vec![2, 0, 5, 2]]));
+
+        let length_counts = LengthCounts::new(&grammar, 4);
+        let mut messages = vec![String::new()];
+        for len in 1..=4 {
+            messages.extend(messages.iter()
+                .filter(|message| message.len() == len - 1)
+                .flat_map(|message| vec![format!("{}a", message), format!("{}b", message)])
+                .collect::<Vec<_>>());
+        }
+
+        for rule in 0..=4 {
+            let strings: Vec<String> = length_counts.strings(rule).collect();
+
+            for message in &messages {
+                assert_eq!(strings.contains(message), grammar.matches(rule, message), "rule {} on {:?}", rule, message);
+            }
+        }
+
+        assert!(grammar.matches(4, ""));
+        match grammar.explain(4, "aab") {
+            Explanation::Match(tree) => assert_eq!("aab", tree.text),
+            Explanation::NoMatch(failure) => panic!("Expected a match, got {}", failure),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::explain::{Failure, Tree};
+use crate::grammar::{Grammar, Rule};
+
+/// Item is a partly matched alternative of a rule: the alternative started matching at the
+/// origin, and the rules before the dot have matched up to the chart position holding the item.
+/// A Char rule's only item has alt 0, and is complete with the dot at 1.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct Item {
+    rule: i32,
+    alt: usize,
+    dot: usize,
+    origin: usize,
+}
+
+/// Chart is an Earley parse of a message: for each position in the message, the items that
+/// could be in progress there.  Earley parsing handles any grammar, including rules that refer
+/// to themselves on the left or the right, in at most cubic time.
+pub struct Chart<'a> {
+    grammar: &'a Grammar,
+    /// Rules that match the empty string.
+    nullable: HashSet<i32>,
+    start: i32,
+    message: Vec<char>,
+    sets: Vec<Vec<Item>>,
+    seen: Vec<HashSet<Item>>,
+}
+
+impl<'a> Chart<'a> {
+    /// Parses the message as the start rule.
+    pub fn parse(grammar: &'a Grammar, start: i32, message: &str) -> Chart<'a> {
+        let message: Vec<char> = message.chars().collect();
+        let positions = message.len() + 1;
+        let nullable = nullable_rules(grammar);
+        let mut chart = Chart { grammar, nullable, start, message, sets: vec![Vec::new(); positions], seen: vec![HashSet::new(); positions] };
+
+        chart.predict(start, 0);
+
+        for pos in 0..positions {
+            // Items are added to the current set while it's processed.
+            let mut i = 0;
+            while i < chart.sets[pos].len() {
+                let item = chart.sets[pos][i];
+
+                match chart.next_rule(&item) {
+                    Some(next) => {
+                        chart.predict(next, pos);
+
+                        // A rule that matches the empty string may already have completed here,
+                        // before this item started waiting on it, so the item skips over it
+                        // right away (Aycock and Horspool's fix to the predictor).
+                        if chart.nullable.contains(&next) {
+                            chart.add(pos, Item { dot: item.dot + 1, ..item });
+                        }
+                    }
+                    None => {
+                        // Complete: advance every item in the origin set that was waiting on this rule.
+                        for j in 0..chart.sets[item.origin].len() {
+                            let waiting = chart.sets[item.origin][j];
+                            if chart.next_rule(&waiting) == Some(item.rule) {
+                                chart.add(pos, Item { dot: waiting.dot + 1, ..waiting });
+                            }
+                        }
+                    }
+                }
+
+                i += 1;
+            }
+        }
+
+        chart
+    }
+
+    /// Returns whether the whole message matches the start rule.
+    pub fn accepts(&self) -> bool {
+        self.is_complete(self.start, 0, self.message.len())
+    }
+
+    /// Returns a derivation of the whole message from the start rule, or None if the message
+    /// doesn't match.  If the message matches in more than one way, returns one of them.
+    pub fn tree(&self) -> Option<Tree> {
+        self.derive(self.start, 0, self.message.len(), &mut Vec::new())
+    }
+
+    /// Explains why the message doesn't match the start rule: the longest prefix that could
+    /// start a match, and the rules that could have come after it.
+    pub fn failure(&self) -> Failure {
+        // Every item in a set was reached by reading the message up to that position, so the
+        // last non-empty set marks the longest prefix that can start a match.
+        let matched = (0..self.sets.len()).rev().find(|&pos| !self.sets[pos].is_empty()).unwrap_or(0);
+
+        let expected: BTreeSet<i32> = self.sets[matched].iter()
+            .filter_map(|item| self.next_rule(item))
+            .collect();
+
+        Failure {
+            message: self.message.iter().collect(),
+            matched,
+            expected: expected.into_iter().collect(),
+        }
+    }
+
+    /// Returns whether the given rule matches the message from start to end.
+    fn is_complete(&self, rule: i32, start: usize, end: usize) -> bool {
+        self.sets[end].iter().any(|item| item.rule == rule && item.origin == start && self.next_rule(item).is_none())
+    }
+
+    /// Returns the rule after the dot in the item, or None if the item is complete.
+    fn next_rule(&self, item: &Item) -> Option<i32> {
+        match self.grammar.rule(item.rule) {
+            Some(Rule::Alternatives(alternatives)) => alternatives[item.alt].get(item.dot).copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns a derivation of the message from start to end as the given rule.  path holds
+    /// the rules and spans being derived, so rules that only refer to each other, like
+    /// '1: 2 | 3' and '2: 1', don't recurse forever.
+    fn derive(&self, rule: i32, start: usize, end: usize, path: &mut Vec<(i32, usize, usize)>) -> Option<Tree> {
+        if !self.is_complete(rule, start, end) || path.contains(&(rule, start, end)) {
+            return None;
+        }
+
+        let text = self.message[start..end].iter().collect();
+
+        let alternatives = match self.grammar.rule(rule)? {
+            Rule::Char(_) => return Some(Tree { rule, alt: 0, start, end, text, children: Vec::new() }),
+            Rule::Alternatives(alternatives) => alternatives,
+        };
+
+        path.push((rule, start, end));
+        let derivation = (0..alternatives.len()).find_map(|alt| {
+            let complete = Item { rule, alt, dot: alternatives[alt].len(), origin: start };
+            if !self.seen[end].contains(&complete) {
+                return None;
+            }
+
+            self.derive_children(complete, end, path).map(|children| (alt, children))
+        });
+        path.pop();
+
+        derivation.map(|(alt, children)| Tree { rule, alt, start, end, text, children })
+    }
+
+    /// Returns derivations of the rules before the item's dot, where the last of them ends at
+    /// end.  Splits are tried from the right, only where the chart says the item got to.
+    fn derive_children(&self, item: Item, end: usize, path: &mut Vec<(i32, usize, usize)>) -> Option<Vec<Tree>> {
+        if item.dot == 0 {
+            return (item.origin == end).then(Vec::new);
+        }
+
+        let before = Item { dot: item.dot - 1, ..item };
+        let last = match self.grammar.rule(item.rule) {
+            Some(Rule::Alternatives(alternatives)) => alternatives[item.alt][before.dot],
+            _ => return None,
+        };
+
+        (item.origin..=end).rev()
+            .filter(|&mid| self.seen[mid].contains(&before))
+            .find_map(|mid| {
+                let last_tree = self.derive(last, mid, end, path)?;
+                let mut children = self.derive_children(before, mid, path)?;
+                children.push(last_tree);
+
+                Some(children)
+            })
+    }
+
+    /// Starts matching the given rule at a position.  A Char rule is matched right away, so
+    /// it's added to the next position if the character matches.
+    fn predict(&mut self, rule: i32, pos: usize) {
+        match self.grammar.rule(rule) {
+            Some(Rule::Char(c)) if self.message.get(pos) == Some(c) => {
+                self.add(pos + 1, Item { rule, alt: 0, dot: 1, origin: pos });
+            }
+            Some(Rule::Alternatives(alternatives)) => {
+                for alt in 0..alternatives.len() {
+                    self.add(pos, Item { rule, alt, dot: 0, origin: pos });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds an item to the set at the given position, unless it's already there.
+    fn add(&mut self, pos: usize, item: Item) {
+        if self.seen[pos].insert(item) {
+            self.sets[pos].push(item);
+        }
+    }
+}
+
+/// Returns the rules that match the empty string: rules with an alternative made up only of
+/// rules that match the empty string, including alternatives with no rules at all.
+fn nullable_rules(grammar: &Grammar) -> HashSet<i32> {
+    let mut nullable = HashSet::new();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for (id, rule) in grammar.rules() {
+            if let Rule::Alternatives(alternatives) = rule {
+                let matches_empty = alternatives.iter()
+                    .any(|sequence| sequence.iter().all(|part| nullable.contains(part)));
+
+                if matches_empty && nullable.insert(id) {
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    nullable
+}
@@ -0,0 +1,190 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Tree is a derivation of part of a message: the rule alternative that matched the span from
+/// start to end (character offsets, end exclusive), and derivations of the alternative's rules.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Tree {
+    pub rule: i32,
+    /// Which of the rule's alternatives matched, from 0.  Always 0 for a rule like '4: "a"'.
+    pub alt: usize,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub children: Vec<Tree>,
+}
+
+impl Tree {
+    /// Returns the tree as a Graphviz DOT graph, with a node for each rule that matched.
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph derivation {".to_owned(), "    node [shape=box];".to_owned()];
+        self.add_dot(&mut lines, &mut 0);
+        lines.push("}".to_owned());
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Adds DOT lines for this node and its children, numbering nodes from next_id.  Returns
+    /// this node's id.
+    fn add_dot(&self, lines: &mut Vec<String>, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        lines.push(format!("    n{} [label=\"{}\\n{}..{} {}\"];", id, self.label().replace('"', "\\\""), self.start, self.end, self.text));
+
+        for child in &self.children {
+            let child_id = child.add_dot(lines, next_id);
+            lines.push(format!("    n{} -> n{};", id, child_id));
+        }
+
+        id
+    }
+
+    /// Returns the rule and the alternative that matched, like '0: 4 1 5' or '4: "a"'.
+    fn label(&self) -> String {
+        if self.children.is_empty() {
+            format!("{}: \"{}\"", self.rule, self.text)
+        } else {
+            let rules: Vec<String> = self.children.iter().map(|child| child.rule.to_string()).collect();
+            format!("{}: {}", self.rule, rules.join(" "))
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}{}  [{}..{}] {}", "", self.label(), self.start, self.end, self.text, indent = depth * 2)?;
+
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Tree {
+    /// Writes the tree with one line per rule, with each rule's children indented under it.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// Failure explains why a message didn't match: the first `matched` characters can start a
+/// match, and one of the expected rules would have to come next.  If expected is empty, the
+/// prefix matches, but the message should have ended there.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Failure {
+    pub message: String,
+    pub matched: usize,
+    pub expected: Vec<i32>,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let prefix: String = self.message.chars().take(self.matched).collect();
+        write!(f, "'{}' can start a match, ", prefix)?;
+
+        let expected: Vec<String> = self.expected.iter().map(|rule| rule.to_string()).collect();
+        match expected.len() {
+            0 => write!(f, "but the message should end after it."),
+            1 => write!(f, "then rule {} was expected at position {}.", expected[0], self.matched),
+            _ => write!(f, "then one of rules {} was expected at position {}.", expected.join(", "), self.matched),
+        }
+    }
+}
+
+/// Explanation is why a message matches a rule, or why it doesn't.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Explanation {
+    Match(Tree),
+    NoMatch(Failure),
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Explanation::Match(tree) => write!(f, "{}", tree),
+            Explanation::NoMatch(failure) => writeln!(f, "{}", failure),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Puzzle;
+
+    use super::*;
+
+    #[test]
+    fn explain_match() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
+
+        let tree = match puzzle.grammar().explain(0, "ababbb") {
+            Explanation::Match(tree) => tree,
+            other => panic!("Expected a match, got {:?}", other),
+        };
+
+        assert_eq!((0, 6, vec![4, 1, 5]), (tree.start, tree.end, tree.children.iter().map(|child| child.rule).collect()));
+        assert_eq!("\
+0: 4 1 5  [0..6] ababbb
+  4: \"a\"  [0..1] a
+  1: 3 2  [1..5] babb
+    3: 5 4  [1..3] ba
+      5: \"b\"  [1..2] b
+      4: \"a\"  [2..3] a
+    2: 5 5  [3..5] bb
+      5: \"b\"  [3..4] b
+      5: \"b\"  [4..5] b
+  5: \"b\"  [5..6] b
+", tree.to_string());
+    }
+
+    #[test]
+    fn explain_recursive_match() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
+
+        match puzzle.recursive_grammar().unwrap().explain(0, "babbbbaabbbbbabbbbbbaabaaabaaa") {
+            Explanation::Match(tree) => {
+                let rule_8 = &tree.children[0];
+                assert_eq!((8, 1), (rule_8.rule, rule_8.alt));
+                assert_eq!(30, tree.end);
+            }
+            other => panic!("Expected a match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn explain_failure() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
+
+        let failure = Failure { message: "bababa".to_owned(), matched: 0, expected: vec![4] };
+        assert_eq!(Explanation::NoMatch(failure), puzzle.grammar().explain(0, "bababa"));
+
+        // 'aa' matches rule 2, so rule 3 starts at the 'b' and needs an 'a' after it.
+        let failure = Failure { message: "aaabbb".to_owned(), matched: 4, expected: vec![4] };
+        assert_eq!("'aaab' can start a match, then rule 4 was expected at position 4.", failure.to_string());
+        assert_eq!(Explanation::NoMatch(failure), puzzle.grammar().explain(0, "aaabbb"));
+
+        let failure = Failure { message: "aaaabbb".to_owned(), matched: 6, expected: Vec::new() };
+        assert_eq!("'aaaabb' can start a match, but the message should end after it.", failure.to_string());
+        assert_eq!(Explanation::NoMatch(failure), puzzle.grammar().explain(0, "aaaabbb"));
+    }
+
+    #[test]
+    fn dot() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
+        let explanation = puzzle.grammar().explain(2, "bb");
+
+        match explanation {
+            Explanation::Match(tree) => assert_eq!("\
+digraph derivation {
+    node [shape=box];
+    n0 [label=\"2: 5 5\\n0..2 bb\"];
+    n1 [label=\"5: \\\"b\\\"\\n0..1 b\"];
+    n0 -> n1;
+    n2 [label=\"5: \\\"b\\\"\\n1..2 b\"];
+    n0 -> n2;
+}
+", tree.to_dot()),
+            other => panic!("Expected a match, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Params size a generated puzzle.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// Length of the strings matched by rules 42 and 31.  Rule 0 matches three of them in a
+    /// row, so the number of strings it matches grows exponentially with this length.
+    pub chunk: usize,
+
+    /// Number of rules of each length below the chunk length.
+    pub rules_per_length: usize,
+
+    /// Number of messages.
+    pub messages: usize,
+
+    /// Most chunks in a message.
+    pub max_chunks: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { chunk: 5, rules_per_length: 2, messages: 400, max_chunks: 8 }
+    }
+}
+
+/// Alternatives of a rule, where each alternative is a list of rule ids.
+type Alternatives = Vec<Vec<i32>>;
+
+/// Generates a puzzle shaped like the real one: rule 0 is '8 11', rule 8 is '42', and rule 11 is
+/// '42 31', where rules 42 and 31 match strings of a fixed length that never overlap.  Messages
+/// are a mix of strings that match rule 0 before and after rules 8 and 11 become recursive,
+/// and strings that don't.
+pub fn generate<R: Rng>(rng: &mut R, params: &Params) -> String {
+    let chunk = params.chunk.max(2);
+
+    // Ids for every rule other than 0, 8, 11, 42, and 31, in a random order.
+    let reserved = [0, 8, 11, 31, 42];
+    let mut ids: Vec<i32> = (0..(chunk * params.rules_per_length.max(1) + 10) as i32)
+        .filter(|id| !reserved.contains(id))
+        .collect();
+    ids.shuffle(rng);
+
+    let mut rules: BTreeMap<i32, Alternatives> = BTreeMap::new();
+    let mut terminals: BTreeMap<i32, char> = BTreeMap::new();
+
+    // by_length[n] is the rules that match strings of length n.
+    let mut by_length: Vec<Vec<i32>> = vec![Vec::new(), Vec::new()];
+
+    let a = ids.pop().unwrap();
+    let b = ids.pop().unwrap();
+    terminals.insert(a, 'a');
+    terminals.insert(b, 'b');
+    by_length[1] = vec![a, b];
+
+    for length in 2..chunk {
+        let mut length_rules = Vec::new();
+
+        for _ in 0..params.rules_per_length.max(1) {
+            let id = ids.pop().unwrap();
+            let alternatives = (0..rng.gen_range(1..=2))
+                .map(|_| {
+                    let split = rng.gen_range(1..length);
+                    vec![*by_length[split].choose(rng).unwrap(), *by_length[length - split].choose(rng).unwrap()]
+                })
+                .collect();
+
+            rules.insert(id, alternatives);
+            length_rules.push(id);
+        }
+
+        by_length.push(length_rules);
+    }
+
+    // Rule 42 strings start with 'a' and rule 31 strings start with 'b', so they never overlap.
+    let rest = &by_length[chunk - 1];
+    rules.insert(42, rest.iter().map(|&rule| vec![a, rule]).collect());
+    rules.insert(31, rest.iter().map(|&rule| vec![b, rule]).collect());
+    rules.insert(0, vec![vec![8, 11]]);
+    rules.insert(8, vec![vec![42]]);
+    rules.insert(11, vec![vec![42, 31]]);
+
+    let mut lines: Vec<String> = rules.iter()
+        .map(|(id, alternatives)| {
+            let alternatives: Vec<String> = alternatives.iter()
+                .map(|alternative| alternative.iter().map(|rule| rule.to_string()).collect::<Vec<_>>().join(" "))
+                .collect();
+
+            format!("{}: {}", id, alternatives.join(" | "))
+        })
+        .chain(terminals.iter().map(|(id, value)| format!("{}: \"{}\"", id, value)))
+        .collect();
+    lines.shuffle(rng);
+
+    let max_chunks = params.max_chunks.max(3);
+    let messages = (0..params.messages).map(|_| {
+        // Rule 0 with recursive rules matches n 42s followed by between 1 and n - 1 31s.
+        let num_42 = rng.gen_range(2..max_chunks);
+        let num_31 = rng.gen_range(1..=(max_chunks - num_42).min(num_42 - 1));
+
+        let mut message = String::new();
+        for _ in 0..num_42 {
+            expand(rng, 42, &rules, &terminals, &mut message);
+        }
+        for _ in 0..num_31 {
+            expand(rng, 31, &rules, &terminals, &mut message);
+        }
+
+        match rng.gen_range(0..4) {
+            // Mismatched
+            0 => message.chars().map(|c| if rng.gen_bool(0.1) { flip(c) } else { c }).collect(),
+            // Random
+            1 => (0..message.len()).map(|_| if rng.gen() { 'a' } else { 'b' }).collect(),
+            // Matching
+            _ => message,
+        }
+    });
+
+    let mut puzzle = lines.join("\n");
+    puzzle.push('\n');
+    for message in messages {
+        puzzle.push('\n');
+        puzzle.push_str(&message);
+    }
+    puzzle.push('\n');
+
+    puzzle
+}
+
+/// Appends a random string matching the given rule to the message.
+fn expand<R: Rng>(rng: &mut R, rule: i32, rules: &BTreeMap<i32, Alternatives>, terminals: &BTreeMap<i32, char>, message: &mut String) {
+    if let Some(&c) = terminals.get(&rule) {
+        message.push(c);
+        return;
+    }
+
+    for &part in rules[&rule].choose(rng).unwrap() {
+        expand(rng, part, rules, terminals, message);
+    }
+}
+
+/// Returns the other letter.
+fn flip(c: char) -> char {
+    if c == 'a' { 'b' } else { 'a' }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::Puzzle;
+
+    use super::*;
+
+    #[test]
+    fn generate_valid() {
+        for seed in 0..10 {
+            let params = Params { chunk: 4, rules_per_length: 2, messages: 30, max_chunks: 6 };
+            let puzzle = Puzzle::read(generate(&mut StdRng::seed_from_u64(seed), &params).as_bytes()).unwrap();
+
+            assert_eq!(30, puzzle.messages.len());
+            assert!(puzzle.get_rule(42).iter().all(|s| s.len() == 4 && s.starts_with('a')));
+            assert!(puzzle.get_rule(31).iter().all(|s| s.len() == 4 && s.starts_with('b')));
+            assert!(puzzle.recursive_matches().unwrap() >= puzzle.matches(0));
+        }
+    }
+
+    #[test]
+    fn generate_seeded() {
+        let params = Params::default();
+
+        assert_eq!(generate(&mut StdRng::seed_from_u64(7), &params), generate(&mut StdRng::seed_from_u64(7), &params));
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+
+use common::{LoadError, ParseError};
+use regex::Regex;
+
+use crate::count::LengthCounts;
+use crate::earley::Chart;
+use crate::explain::Explanation;
+use crate::regular::{self, CompileError, Matcher};
+
+/// Rule is one rule of a grammar.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Rule {
+    /// Matches a single character, like '4: "a"'.
+    Char(char),
+
+    /// Matches any one of the alternatives, where each alternative is a sequence of rules,
+    /// like '1: 2 3 | 3 2'.
+    Alternatives(Vec<Vec<i32>>),
+}
+
+impl Display for Rule {
+    /// Writes the rule the way it appears in a puzzle, like '"a"' or '2 3 | 3 2'.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Char(c) => write!(f, "\"{}\"", c),
+            Rule::Alternatives(alternatives) => {
+                let alternatives: Vec<String> = alternatives.iter()
+                    .map(|sequence| sequence.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" "))
+                    .collect();
+
+                write!(f, "{}", alternatives.join(" | "))
+            }
+        }
+    }
+}
+
+/// Parses a rule line like '1: 2 3 | 3 2' or '4: "a"' into the rule's id and the rule.
+pub fn parse_rule(line: &str) -> Result<(i32, Rule), ParseError> {
+    let parse_err = |column: usize, expected: &str| ParseError::new(line, column, expected);
+
+    let colon_index = line.find(':').ok_or_else(|| parse_err(line.len() + 1, "':' after the rule number"))?;
+    let rule_id: i32 = line[0 .. colon_index].parse().map_err(|_| parse_err(1, "a rule number"))?;
+
+    let rule_description = line[colon_index + 1..].trim();
+    let rule_description_chars: Vec<char> = rule_description.chars().collect();
+
+    if let ['"', value, '"'] = rule_description_chars[..] {
+        return Ok((rule_id, Rule::Char(value)));
+    }
+
+    let mut alternatives = vec![Vec::new()];
+    // column is where the next token starts, just after the colon.
+    let mut column = colon_index + 2;
+
+    for token in line[colon_index + 1..].split(' ') {
+        if token == "|" {
+            if alternatives.last().unwrap().is_empty() {
+                return Err(parse_err(column, "a rule number"));
+            }
+
+            alternatives.push(Vec::new());
+        } else if !token.is_empty() {
+            let rule_ref = token.parse().map_err(|_| parse_err(column, "a rule number or '|'"))?;
+            alternatives.last_mut().unwrap().push(rule_ref);
+        }
+
+        column += token.len() + 1;
+    }
+
+    if alternatives.last().unwrap().is_empty() {
+        return Err(parse_err(line.len() + 1, "a rule number"));
+    }
+
+    Ok((rule_id, Rule::Alternatives(alternatives)))
+}
+
+/// Grammar is a set of numbered rules that match messages.  Rules can refer to themselves,
+/// directly or through other rules.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Grammar {
+    rules: BTreeMap<i32, Rule>,
+}
+
+impl Grammar {
+    /// Parses a grammar from rule lines and their line numbers, returning an error if a rule
+    /// can't be parsed or refers to a rule that doesn't exist.
+    pub fn parse(rule_lines: Vec<(usize, &str)>) -> Result<Grammar, LoadError> {
+        let mut grammar = Grammar::default();
+
+        for (number, line) in rule_lines {
+            let (id, rule) = parse_rule(line).map_err(|e| e.at_line(number))?;
+            grammar.rules.insert(id, rule);
+        }
+
+        grammar.check_references()?;
+        Ok(grammar)
+    }
+
+    /// Returns the number of rules in this grammar.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns whether this grammar has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the rule with the given id, if it exists.
+    pub fn rule(&self, id: i32) -> Option<&Rule> {
+        self.rules.get(&id)
+    }
+
+    /// Returns every rule in this grammar, in order by id.
+    pub fn rules(&self) -> impl Iterator<Item = (i32, &Rule)> {
+        self.rules.iter().map(|(&id, rule)| (id, rule))
+    }
+
+    /// Adds a rule to this grammar, replacing any rule with the same id.
+    pub fn insert(&mut self, id: i32, rule: Rule) {
+        self.rules.insert(id, rule);
+    }
+
+    /// Replaces rules with the rules in the patch, which has one rule per line like
+    /// '8: 42 | 42 8', and adds any new rules.  Blank lines are skipped.  Returns an error and
+    /// leaves the grammar unchanged if a rule can't be parsed or the patched grammar refers to
+    /// a rule that doesn't exist.
+    pub fn patch(&mut self, patch: &str) -> Result<(), LoadError> {
+        let mut patched = self.clone();
+
+        for (i, line) in patch.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (id, rule) = parse_rule(line).map_err(|e| e.at_line(i + 1))?;
+            patched.insert(id, rule);
+        }
+
+        patched.check_references()?;
+        *self = patched;
+
+        Ok(())
+    }
+
+    /// Patches this grammar with the rules in the given file, like Grammar::patch.
+    pub fn patch_file(&mut self, filename: &str) -> Result<(), LoadError> {
+        let lines = common::read_lines(common::open(filename)?)?;
+        self.patch(&lines.join("\n"))
+    }
+
+    /// Returns an error if a rule refers to a rule that isn't in this grammar.
+    pub fn check_references(&self) -> Result<(), LoadError> {
+        for (id, rule) in &self.rules {
+            if let Rule::Alternatives(alternatives) = rule {
+                if let Some(missing) = alternatives.iter().flatten().find(|rule_ref| !self.rules.contains_key(rule_ref)) {
+                    return Err(LoadError::Invalid(format!("rule {} refers to rule {}, which doesn't exist.", id, missing)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the whole message matches the given rule.
+    pub fn matches(&self, rule: i32, message: &str) -> bool {
+        Chart::parse(self, rule, message).accepts()
+    }
+
+    /// Explains how the whole message matches the given rule with a derivation tree, or why
+    /// it doesn't with the longest prefix that could start a match and the rules expected next.
+    pub fn explain(&self, rule: i32, message: &str) -> Explanation {
+        let chart = Chart::parse(self, rule, message);
+
+        match chart.tree() {
+            Some(tree) => Explanation::Match(tree),
+            None => Explanation::NoMatch(chart.failure()),
+        }
+    }
+
+    /// Compiles the given rule into a regular expression that matches whole messages.  Returns
+    /// an error if the rule depends on recursive rules, which the general matcher handles.
+    pub fn to_regex(&self, rule: i32) -> Result<Regex, CompileError> {
+        regular::compile(self, rule)
+    }
+
+    /// Returns a matcher for the given rule, which compiles it to a regular expression once if
+    /// it can, and otherwise uses the general matcher.
+    pub fn matcher(&self, rule: i32) -> Matcher<'_> {
+        Matcher::new(self, rule)
+    }
+
+    /// Returns the rules the given rule depends on, including itself, that refer back to
+    /// themselves.  These are the rules that stop it compiling to a regular expression.
+    pub fn recursive_rules(&self, rule: i32) -> Vec<i32> {
+        regular::recursive_rules(self, rule)
+    }
+
+    /// Counts the strings each rule matches of each length up to max_len, which also lists the
+    /// strings shortest first.  Unlike Grammar::strings, this works for recursive rules.
+    pub fn length_counts(&self, max_len: usize) -> LengthCounts {
+        LengthCounts::new(self, max_len)
+    }
+
+    /// Returns every string that matches the given rule.  Panics if the rule is recursive,
+    /// since it matches infinitely many strings.
+    pub fn strings(&self, rule: i32) -> Vec<String> {
+        self.expand(rule, &mut HashMap::new(), &mut Vec::new())
+    }
+
+    /// Returns every string that matches the given rule, remembering the strings for each
+    /// expanded rule.  in_progress is the rules being expanded, which would recurse forever if
+    /// they came up again.
+    fn expand(&self, rule: i32, expanded: &mut HashMap<i32, Vec<String>>, in_progress: &mut Vec<i32>) -> Vec<String> {
+        if let Some(strings) = expanded.get(&rule) {
+            return strings.clone();
+        }
+
+        assert!(!in_progress.contains(&rule), "Rule {} is recursive, so it matches infinitely many strings.", rule);
+        in_progress.push(rule);
+
+        let strings = match &self.rules[&rule] {
+            Rule::Char(c) => vec![c.to_string()],
+            Rule::Alternatives(alternatives) => alternatives.iter()
+                .flat_map(|sequence| {
+                    sequence.iter().fold(vec![String::new()], |prefixes, &part| {
+                        let suffixes = self.expand(part, expanded, in_progress);
+                        prefixes.iter()
+                            .flat_map(|prefix| suffixes.iter().map(move |suffix| format!("{}{}", prefix, suffix)))
+                            .collect()
+                    })
+                })
+                .collect(),
+        };
+
+        in_progress.pop();
+        expanded.insert(rule, strings.clone());
+
+        strings
+    }
+}
+
+impl Display for Grammar {
+    /// Writes the rules in the puzzle's format, one per line in order by id.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (id, rule) in &self.rules {
+            writeln!(f, "{}: {}", id, rule)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grammar(rules: &str) -> Grammar {
+        Grammar::parse(rules.lines().enumerate().map(|(i, line)| (i + 1, line)).collect()).unwrap()
+    }
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!(Ok((1, Rule::Alternatives(vec![vec![2, 3], vec![3, 2]]))), parse_rule("1: 2 3 | 3 2"));
+        assert_eq!(Ok((4, Rule::Char('a'))), parse_rule("4: \"a\""));
+
+        assert_eq!(Err(ParseError::new("1 2 3", 6, "':' after the rule number")), parse_rule("1 2 3"));
+        assert_eq!(Err(ParseError::new("1: 2 | | 3", 8, "a rule number")), parse_rule("1: 2 | | 3"));
+        assert_eq!(Err(ParseError::new("1: 2 |", 7, "a rule number")), parse_rule("1: 2 |"));
+    }
+
+    #[test]
+    fn missing_reference() {
+        match Grammar::parse(vec![(1, "0: 1 2"), (2, "1: \"a\"")]) {
+            Err(LoadError::Invalid(message)) => assert_eq!("rule 0 refers to rule 2, which doesn't exist.", message),
+            other => panic!("Expected a missing rule, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn patch() {
+        let mut grammar = grammar("0: 1 2\n1: \"a\"\n2: \"b\"");
+        grammar.patch("\n0: 1 | 1 0\n3: 0 2\n").unwrap();
+
+        assert_eq!("0: 1 | 1 0\n1: \"a\"\n2: \"b\"\n3: 0 2\n", grammar.to_string());
+        assert!(grammar.matches(3, "aaab"));
+    }
+
+    #[test]
+    fn patch_invalid() {
+        let mut grammar = grammar("0: 1 2\n1: \"a\"\n2: \"b\"");
+        let original = grammar.clone();
+
+        match grammar.patch("1: \"b\"\n2: 1 x") {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("2: 1 x", 6, "a rule number or '|'").at_line(2), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        assert!(grammar.patch("2: 1 | 4").is_err());
+        assert_eq!(original, grammar);
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let text = "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n";
+
+        assert_eq!(text, grammar(text).to_string());
+    }
+
+    #[test]
+    fn matches_recursive() {
+        // Balanced parentheses, written as 'a' and 'b', which can't be expanded into strings.
+        let balanced = grammar("0: 1 2 | 1 0 2 | 0 0\n1: \"a\"\n2: \"b\"");
+
+        assert!(balanced.matches(0, "ab"));
+        assert!(balanced.matches(0, "aabbab"));
+        assert!(balanced.matches(0, "abaabb"));
+        assert!(!balanced.matches(0, "aab"));
+        assert!(!balanced.matches(0, "ba"));
+        assert!(!balanced.matches(0, ""));
+    }
+
+    #[test]
+    fn matches_left_recursive() {
+        let grammar = grammar("0: 0 1 | 1\n1: \"a\"");
+
+        assert!(grammar.matches(0, "aaaa"));
+        assert!(!grammar.matches(0, "aaba"));
+    }
+
+    #[test]
+    fn strings_finite() {
+        let mut strings = grammar("0: 1 2 | 2 2\n1: \"a\"\n2: 1 | 3\n3: \"b\"").strings(0);
+        strings.sort();
+
+        assert_eq!(vec!["aa", "aa", "ab", "ab", "ba", "bb"], strings);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rule 0 is recursive")]
+    fn strings_recursive() {
+        grammar("0: 1 | 1 0\n1: \"a\"").strings(0);
+    }
+}
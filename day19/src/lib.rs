@@ -1,282 +1,306 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum RuleValue {
-    Rule(i32),
-    Value(char),
-}
+use std::fmt::{self, Display, Formatter};
+use std::io::BufRead;
+use common::{LoadError, Solution};
+
+pub use count::LengthCounts;
+pub use explain::{Explanation, Failure, Tree};
+pub use grammar::{parse_rule, Grammar, Rule};
+pub use regular::{CompileError, Matcher};
+
+mod count;
+mod earley;
+mod explain;
+pub mod generate;
+mod grammar;
+mod regular;
+
+/// Part 2's replacements for rules 8 and 11, which make them recursive.
+pub const RECURSIVE_PATCH: &str = "8: 42 | 42 8\n11: 42 31 | 42 11 31";
 
 pub struct Puzzle {
-    rules: HashMap<i32, Vec<String>>,
+    grammar: Grammar,
     messages: Vec<String>
 }
 
 impl Puzzle {
     /// Loads a puzzle from the given file.
-    pub fn load(filename: &str) -> Puzzle {
+    pub fn load(filename: &str) -> Result<Puzzle, LoadError> {
+        Puzzle::read(common::open(filename)?)
+    }
+
+    /// Reads a puzzle from the given reader.
+    pub fn read<R: BufRead>(reader: R) -> Result<Puzzle, LoadError> {
         // Lines in a puzzle file are rules, an empty line, then messages.
-        let f = File::open(filename).unwrap();
-        let mut f = BufReader::new(f);
+        let mut sections = common::sections(reader);
 
-        let mut line = String::new();
-        let mut read = f.read_line(&mut line).unwrap();
-        let mut rule_lines = Vec::new();
-        while line.trim() != "" && read > 0 {
-            rule_lines.push(line.trim().to_owned());
+        let rule_section = sections.next().transpose()?;
+        let rule_lines = rule_section.iter()
+            .flat_map(|section| section.lines())
+            .map(|(number, line)| (number, line.trim()))
+            .collect();
 
-            line = String::new();
-            read = f.read_line(&mut line).unwrap();
+        let mut messages = Vec::new();
+        for section in sections {
+            messages.extend(section?.lines.iter().map(|line| line.trim().to_owned()));
         }
 
+        let grammar = Grammar::parse(rule_lines)?;
 
-        let mut messages = Vec::new();
-        line = String::new();
-        read = f.read_line(&mut line).unwrap();
-        while read > 0 {
-            messages.push(line.trim().to_owned());
+        Ok(Puzzle { grammar, messages })
+    }
 
-            line = String::new();
-            read = f.read_line(&mut line).unwrap();
-        }
+    /// Returns the puzzle's rules.
+    pub fn grammar(&self) -> &Grammar {
+        &self.grammar
+    }
 
-        let rules = Puzzle::parse_rules(rule_lines);
+    /// Replaces or adds rules from the patch, like Grammar::patch.
+    pub fn patch(&mut self, patch: &str) -> Result<(), LoadError> {
+        self.grammar.patch(patch)
+    }
 
-        Puzzle { rules, messages }
+    /// Replaces or adds rules from the given file, like Grammar::patch_file.
+    pub fn patch_file(&mut self, filename: &str) -> Result<(), LoadError> {
+        self.grammar.patch_file(filename)
     }
 
-    /// Returns the strings that match the given rule.
+    /// Returns the strings that match the given rule.  Panics if the rule is recursive.
     pub fn get_rule(&self, num: i32) -> Vec<String> {
-        self.rules.get(&num).unwrap().clone()
+        self.grammar.strings(num)
     }
 
-    /// Parses the given list of raw rules into a map of rule id to matching strings.
-    fn parse_rules(rule_lines: Vec<String>) -> HashMap<i32, Vec<String>> {
-        // raw_rules is a map of rule id to rule values.
-        let mut raw_rules: HashMap<i32, Vec<Vec<RuleValue>>> = HashMap::new();
-        // reverse_rules is a map of rule id to the rules that reference the rule.
-        let mut reverse_rules: HashMap<i32, HashSet<i32>> = HashMap::new();
-        // resolved_rules is a list of rules that were fully resolved this round.
-        let mut resolved_rules: HashSet<i32> = HashSet::new();
-
-        // Convert the rules from lines into maps.
-        for line in rule_lines {
-            // Rule lines are either resolved '4: "a"'
-            // A list of rules '0: 4 1 5'
-            // Or multiple lists of rules '1: 2 3 | 3 2'
-            let colon_index = line.find(':').unwrap();
-            let rule_id: i32 = line[0 .. colon_index].parse().unwrap();
-
-            let rule_description = &line[colon_index + 2..];
-
-            if rule_description == "\"a\"" || rule_description == "\"b\"" {
-                let rule_description_chars: Vec<char> = rule_description.chars().collect();
-                let value = rule_description_chars[1];
-
-                raw_rules.insert(rule_id, vec![vec![RuleValue::Value(value)]]);
-                resolved_rules.insert(rule_id);
-            } else {
-                let mut raw_parts = Vec::new();
-                for part in rule_description.split("|") {
-                    let rule_refs: Vec<i32> = part.trim().split(" ")
-                        .map(|rule_ref| rule_ref.parse().unwrap())
-                        .collect();
-
-                    let mut raw_rule_refs = Vec::new();
-                    for rule_ref in rule_refs {
-                        reverse_rules.entry(rule_ref).or_insert(HashSet::new()).insert(rule_id);
-                        raw_rule_refs.push(RuleValue::Rule(rule_ref));
-                    }
-
-                    raw_parts.push(raw_rule_refs);
-                }
-
-                raw_rules.insert(rule_id, raw_parts);
-            }
-        }
+    /// Returns the number of messages that completely match the given rule.
+    pub fn matches(&self, rule_num: i32) -> usize {
+        self.count_matches(&self.grammar.matcher(rule_num))
+    }
 
-        // Resolve the rules.  Each round, push the rules that were resolved in the previous
-        // round into the rules that reference them.
-        while !resolved_rules.is_empty() {
-            let no_referenced_rules = HashSet::new();
-
-            // Expand the resolved rules into the rules that reference them.
-            for resolved_rule_id in &resolved_rules {
-                let resolved_rule = raw_rules.get(resolved_rule_id).unwrap().clone();
-
-                for referenced_rule_id in reverse_rules.get(&resolved_rule_id).unwrap_or(&no_referenced_rules) {
-                    raw_rules.entry(*referenced_rule_id)
-                        .and_modify(|referenced_rule| Puzzle::expand_rule(referenced_rule, resolved_rule_id, &resolved_rule));
-                }
-            }
-
-            // New list of resolved rules are the referenced rules that fully became values.
-            let new_resolved_rules = resolved_rules.iter()
-                .flat_map(|resolved_rule_id| reverse_rules.get(resolved_rule_id).unwrap_or(&no_referenced_rules))
-                .filter(|&referenced_rule_id| {
-                    raw_rules.get(referenced_rule_id).unwrap().iter()
-                        .flat_map(|part| part.iter())
-                        .all(|&rv| match rv {
-                            RuleValue::Value(_) => true,
-                            RuleValue::Rule(_) => false,
-                        })
-                })
-                .cloned()
-                .collect();
+    /// Returns the number of messages that the matcher accepts.
+    pub fn count_matches(&self, matcher: &Matcher) -> usize {
+        self.messages.iter().filter(|message| matcher.matches(message)).count()
+    }
 
-            resolved_rules = new_resolved_rules;
-        }
+    /// Returns the number of messages that completely match rule 0 with rules 8 and 11
+    /// replaced by recursive versions, or an error if the puzzle doesn't have rules 42 and 31.
+    pub fn recursive_matches(&self) -> Result<usize, LoadError> {
+        let grammar = self.recursive_grammar()?;
+        Ok(self.count_matches(&grammar.matcher(0)))
+    }
 
-        // Flatten the resolved rules from lists of values to strings.
-        let mut flat_rules = HashMap::new();
+    /// Returns the puzzle's grammar patched with RECURSIVE_PATCH, or an error if the puzzle
+    /// doesn't have the rules the patch refers to.
+    pub fn recursive_grammar(&self) -> Result<Grammar, LoadError> {
+        let mut grammar = self.grammar.clone();
+        grammar.patch(RECURSIVE_PATCH)?;
 
-        for (rule_id, values) in raw_rules.iter() {
-            let flat_values: Vec<String> = values.iter()
-                .map(|value| value.iter().map(|v| match v {
-                    RuleValue::Value(c) => c,
-                    RuleValue::Rule(r) => panic!("Unresolved rule reference {}.", r),
-                }).collect())
-                .collect();
+        Ok(grammar)
+    }
+
+    #[cfg(test)]
+    fn message_matches_rule_0_recursively(&self, message: &str) -> bool {
+        self.recursive_grammar().unwrap().matches(0, message)
+    }
+}
+
+impl Display for Puzzle {
+    /// Writes the puzzle in the format it's read in: rules, an empty line, then messages.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.grammar)?;
 
-            flat_rules.insert(*rule_id, flat_values);
+        for message in &self.messages {
+            write!(f, "\n{}", message)?;
         }
 
-        flat_rules
+        writeln!(f)
     }
+}
 
-    fn expand_rule(referenced_rule: &mut Vec<Vec<RuleValue>>, resolved_rule_id: &i32, resolved_rule: &Vec<Vec<RuleValue>>) {
-        let mut new_referenced_rule = Vec::new();
-        for referenced_rule_part in referenced_rule.clone() {
-            let mut new_parts: Vec<Vec<RuleValue>> = Vec::new();
+pub struct Day19 {
+    puzzle: Puzzle,
+}
 
-            for rv in referenced_rule_part {
-                if rv == RuleValue::Rule(*resolved_rule_id) {
-                    if new_parts.is_empty() {
-                        new_parts.append(resolved_rule.clone().as_mut());
-                    } else {
-                        let mut new_new_parts: Vec<Vec<RuleValue>> = Vec::new();
+impl Solution for Day19 {
+    type Part1 = usize;
+    type Part2 = Result<usize, LoadError>;
 
-                        for resolved_part in resolved_rule {
-                            for old_new_part in new_parts.clone() {
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day19 { puzzle: Puzzle::read(input.as_bytes())? })
+    }
 
-                                let new_new_part = old_new_part.iter().cloned()
-                                    .chain(resolved_part.iter().cloned())
-                                    .collect::<Vec<RuleValue>>();
+    fn part1(&self) -> usize {
+        self.puzzle.matches(0)
+    }
 
-                                new_new_parts.push(new_new_part);
-                            }
-                        }
+    fn part2(&self) -> Result<usize, LoadError> {
+        self.puzzle.recursive_matches()
+    }
+}
 
-                        new_parts = new_new_parts;
-                    }
+#[cfg(test)]
+mod tests {
+    use common::ParseError;
 
-                } else if new_parts.is_empty() {
-                    new_parts.push(vec![rv.clone()]);
+    use super::*;
+
+    #[test]
+    fn load_sample() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
 
-                } else {
-                    for np in new_parts.iter_mut() {
-                        np.push(rv.clone());
-                    }
-                }
-            }
+        assert_eq!(6, puzzle.grammar.len());
+        assert_eq!(5, puzzle.messages.len());
+    }
 
-            new_referenced_rule.append(&mut new_parts);
-        }
+    #[test]
+    fn read_invalid_rule() {
+        let input = "0: 1 2\n1: \"a\"\n2: 1 x\n\naa\n";
 
-        // raw_rules.insert(*referenced_rule_id, new_referenced_rule);
-        referenced_rule.clear();
-        referenced_rule.append(&mut new_referenced_rule);
+        match Puzzle::read(input.as_bytes()) {
+            Err(LoadError::Parse(e)) =>
+                assert_eq!(ParseError::new("2: 1 x", 6, "a rule number or '|'").at_line(3), e),
+            _ => panic!("Expected a parse error on line 3."),
+        }
     }
 
-    /// Returns the number of messages that completely match the given rule.
-    pub fn matches(&self, rule_num: i32) -> usize {
-        self.messages.iter()
-            .filter(|message| self.message_matches(message, rule_num))
-            .count()
+    #[test]
+    fn patch_sample() {
+        let mut puzzle = Puzzle::load("sample.txt").unwrap();
+        puzzle.patch("0: 4 1 5 | 4 1 5 5").unwrap();
+
+        assert_eq!(3, puzzle.matches(0));
+
+        let reread = Puzzle::read(puzzle.to_string().as_bytes()).unwrap();
+        assert_eq!(puzzle.grammar, reread.grammar);
+        assert_eq!(puzzle.messages, reread.messages);
     }
 
-    /// Returns whether the message matches the given rule.
-    fn message_matches(&self, message: &String, rule_num: i32) -> bool {
-        self.get_rule(rule_num).contains(message)
+    #[test]
+    fn matches_sample() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
+
+        assert_eq!(2, puzzle.matches(0));
     }
 
-    /// Returns the number of messages that completely match rule 0 recursively.
-    pub fn recursive_matches(&self) -> usize {
-        self.messages.iter()
-            .filter(|message| self.message_matches(message, 0) || self.message_matches_rule_0_recursively(message))
-            .count()
+    #[test]
+    fn recursive_matches_sample() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
+
+        assert_eq!(12, puzzle.recursive_matches().unwrap());
     }
 
-    fn message_matches_rule_0_recursively(&self, message: &String) -> bool {
-        // In the recursive part, rules change to:
-        // 0: 8 11
-        // 8: 42 | 42 8
-        // 11: 42 31 | 42 11 31
-        // Looking at the question and sample data, 8 = 42, and 42 and 31 have either half of the
-        // possible combinations of their space.  42 and 31 have length 5 in the sample data,
-        // and they have length 8 in the question.
-        // To match, the input needs to be some number of chunks that match 42, followed by
-        // at least one fewer chunk that matches 31.
-
-        let chunk_size = self.get_rule(42)[0].len();
-        if message.len() % chunk_size != 0 {
-            return false;
+    #[test]
+    fn recursive_matches_missing_rules() {
+        let day = Day19::parse("0: 1 1\n1: \"a\"\n\naa\n").unwrap();
+
+        assert_eq!(1, day.part1());
+        match day.part2() {
+            Err(LoadError::Invalid(message)) => assert!(message.contains("rule 42"), "{}", message),
+            other => panic!("Expected an invalid input error, got {:?}", other),
         }
+    }
 
-        let chunks: Vec<String> = message.as_bytes().chunks(chunk_size)
-            .map(|chunk| String::from_utf8(Vec::from(chunk)).unwrap())
-            .collect();
+    #[test]
+    fn recursive_matches_sample_messages() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
 
-        // Count the number of 42 chunks followed by the number of 31 chunks.
-        let num_42_chunks = chunks.iter()
-            .take_while(|chunk| self.message_matches(chunk, 42))
-            .count();
+        assert!(puzzle.message_matches_rule_0_recursively("babbbbaabbbbbabbbbbbaabaaabaaa"));
+        assert!(!puzzle.message_matches_rule_0_recursively("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa"));
+        assert!(!puzzle.message_matches_rule_0_recursively("aaaabbaaaabbaaa"));
+        assert!(!puzzle.message_matches_rule_0_recursively("babaaabbbaaabaababbaabababaaab"));
+    }
 
-        let num_31_chunks = chunks[num_42_chunks..].iter()
-            .take_while(|chunk| self.message_matches(chunk, 31))
-            .count();
+    #[test]
+    fn solution_sample() {
+        let day = Day19::parse(&std::fs::read_to_string("recursive_sample.txt").unwrap()).unwrap();
 
-        num_42_chunks + num_31_chunks == chunks.len()
-            && num_42_chunks > 0
-            && num_31_chunks > 0
-            && num_31_chunks < num_42_chunks
+        assert_eq!(3, day.part1());
+        assert_eq!(12, day.part2().unwrap());
     }
 }
 
 #[cfg(test)]
-mod tests {
+mod recursive_properties {
+    use proptest::prelude::*;
+
     use super::*;
 
-    #[test]
-    fn load_sample() {
-        let puzzle = Puzzle::load("sample.txt");
+    /// Returns a puzzle where rule 42 and rule 31 match the given strings of 'a' and 'b', and
+    /// rule 0 is '8 11'.
+    fn puzzle(rule_42: &[String], rule_31: &[String]) -> Puzzle {
+        let mut grammar = Grammar::default();
+        grammar.insert(1, Rule::Char('a'));
+        grammar.insert(2, Rule::Char('b'));
+        grammar.insert(0, Rule::Alternatives(vec![vec![8, 11]]));
+
+        for (id, strings) in [(42, rule_42), (31, rule_31)] {
+            let alternatives = strings.iter()
+                .map(|s| s.chars().map(|c| if c == 'a' { 1 } else { 2 }).collect())
+                .collect();
 
-        assert_eq!(6, puzzle.rules.len());
-        assert_eq!(5, puzzle.messages.len());
+            grammar.insert(id, Rule::Alternatives(alternatives));
+        }
+
+        Puzzle { grammar, messages: Vec::new() }
     }
 
-    #[test]
-    fn matches_sample() {
-        let puzzle = Puzzle::load("sample.txt");
+    /// Returns whether the message is made up of strings matching each of the given rules in
+    /// order, trying every way to split it.
+    fn matches_sequence(message: &str, rules: &[&[String]]) -> bool {
+        match rules.split_first() {
+            None => message.is_empty(),
+            Some((rule, rest)) => rule.iter()
+                .any(|s| message.starts_with(s.as_str()) && matches_sequence(&message[s.len()..], rest)),
+        }
+    }
 
-        assert_eq!(2, puzzle.matches(0));
+    /// Returns whether the message matches rule 0 with recursive rules 8 and 11, which is
+    /// some number of 42s followed by fewer 31s, by trying every count of each.
+    fn brute_force_matches(rule_42: &[String], rule_31: &[String], message: &str) -> bool {
+        (2..=message.len()).any(|num_42| {
+            (1..num_42).any(|num_31| {
+                let mut rules = vec![rule_42; num_42];
+                rules.extend(vec![rule_31; num_31]);
+
+                matches_sequence(message, &rules)
+            })
+        })
     }
 
-    #[test]
-    fn recursive_matches_sample() {
-        let puzzle = Puzzle::load("recursive_sample.txt");
+    /// Rules 42 and 31 as strings of the same length, which may overlap, and a message made
+    /// up of strings from either rule.
+    fn rules_and_message() -> impl Strategy<Value = (Vec<String>, Vec<String>, String)> {
+        (1..=3_usize)
+            .prop_flat_map(|len| {
+                let strings: Vec<String> = (0..1 << len)
+                    .map(|n: usize| (0..len).map(|i| if n & (1 << i) == 0 { 'a' } else { 'b' }).collect())
+                    .collect();
+
+                let count = strings.len();
+                (prop::sample::subsequence(strings.clone(), 1..=count), prop::sample::subsequence(strings, 1..=count))
+            })
+            .prop_flat_map(|(rule_42, rule_31)| {
+                let chunks: Vec<String> = rule_42.iter().chain(rule_31.iter()).cloned().collect();
+                let message = prop::collection::vec(prop::sample::select(chunks), 0..=7)
+                    .prop_map(|chunks| chunks.concat());
+
+                (Just(rule_42), Just(rule_31), message)
+            })
+    }
 
-        assert_eq!(12, puzzle.recursive_matches());
+    proptest! {
+        #[test]
+        fn recursive_matches_brute_force((rule_42, rule_31, message) in rules_and_message()) {
+            let expected = brute_force_matches(&rule_42, &rule_31, &message);
+
+            let puzzle = puzzle(&rule_42, &rule_31);
+
+            prop_assert_eq!(expected, puzzle.message_matches_rule_0_recursively(&message));
+        }
     }
 
     #[test]
-    fn recursive_matches_sample_messages() {
-        let puzzle = Puzzle::load("recursive_sample.txt");
+    fn recursive_matches_overlapping_rules() {
+        let puzzle = puzzle(&["a".to_owned()], &["a".to_owned(), "b".to_owned()]);
 
-        assert!(puzzle.message_matches_rule_0_recursively(&"babbbbaabbbbbabbbbbbaabaaabaaa".to_owned()));
-        assert!(!puzzle.message_matches_rule_0_recursively(&"abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa".to_owned()));
-        assert!(!puzzle.message_matches_rule_0_recursively(&"aaaabbaaaabbaaa".to_owned()));
-        assert!(!puzzle.message_matches_rule_0_recursively(&"babaaabbbaaabaababbaabababaaab".to_owned()));
+        assert!(puzzle.message_matches_rule_0_recursively("aaa"));
+        assert!(!puzzle.message_matches_rule_0_recursively("aa"));
     }
-}
\ No newline at end of file
+}
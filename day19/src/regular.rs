@@ -0,0 +1,193 @@
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use regex::Regex;
+
+use crate::grammar::{Grammar, Rule};
+
+/// CompileError is why a rule couldn't be compiled to a regular expression.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CompileError {
+    /// The grammar doesn't have the rule.
+    Undefined(i32),
+
+    /// The rule depends on rules that refer back to themselves, so its language may not be
+    /// regular.  Some recursive rules, like '8: 42 | 42 8', do match regular languages, but
+    /// only rules without recursion are compiled.
+    Recursive { rule: i32, recursive_rules: Vec<i32> },
+
+    /// The regex crate couldn't build the expression, usually because it's too big.
+    Regex(String),
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Undefined(rule) => write!(f, "Rule {} doesn't exist.", rule),
+            CompileError::Recursive { rule, recursive_rules } => {
+                let rules: Vec<String> = recursive_rules.iter().map(|rule| rule.to_string()).collect();
+                write!(f, "Rule {} isn't regular: it depends on recursive rules {}.", rule, rules.join(", "))
+            }
+            CompileError::Regex(message) => write!(f, "Unable to build a regular expression:\n{}", message),
+        }
+    }
+}
+
+impl Error for CompileError {}
+
+/// Matcher matches whole messages against a rule.  The rule is compiled to a regular expression
+/// once, if it can be, and messages fall back to the general matcher if it can't.
+pub struct Matcher<'a> {
+    grammar: &'a Grammar,
+    rule: i32,
+    regex: Result<Regex, CompileError>,
+}
+
+impl<'a> Matcher<'a> {
+    /// Builds a matcher for the given rule.
+    pub fn new(grammar: &'a Grammar, rule: i32) -> Matcher<'a> {
+        Matcher { grammar, rule, regex: compile(grammar, rule) }
+    }
+
+    /// Returns whether the whole message matches the rule.
+    pub fn matches(&self, message: &str) -> bool {
+        match &self.regex {
+            Ok(regex) => regex.is_match(message),
+            Err(_) => self.grammar.matches(self.rule, message),
+        }
+    }
+
+    /// Returns why the rule couldn't be compiled to a regular expression, if it couldn't.
+    pub fn compile_error(&self) -> Option<&CompileError> {
+        self.regex.as_ref().err()
+    }
+}
+
+/// Returns the rules the given rule depends on, including itself, that refer back to
+/// themselves directly or through other rules.
+pub fn recursive_rules(grammar: &Grammar, rule: i32) -> Vec<i32> {
+    reachable(grammar, &[rule]).into_iter()
+        .filter(|&r| reachable(grammar, &references(grammar, r)).contains(&r))
+        .collect()
+}
+
+/// Compiles the given rule into a regular expression that matches whole messages, or returns
+/// an error if the rule can't be compiled.
+pub fn compile(grammar: &Grammar, rule: i32) -> Result<Regex, CompileError> {
+    if grammar.rule(rule).is_none() {
+        return Err(CompileError::Undefined(rule));
+    }
+
+    let recursive_rules = recursive_rules(grammar, rule);
+    if !recursive_rules.is_empty() {
+        return Err(CompileError::Recursive { rule, recursive_rules });
+    }
+
+    let pattern = format!("^{}$", pattern(grammar, rule, &mut HashMap::new()));
+    Regex::new(&pattern).map_err(|e| CompileError::Regex(e.to_string()))
+}
+
+/// Returns a pattern matching the given rule, which has no recursion.  patterns remembers the
+/// patterns of rules that have already been built.
+fn pattern(grammar: &Grammar, rule: i32, patterns: &mut HashMap<i32, String>) -> String {
+    if let Some(pattern) = patterns.get(&rule) {
+        return pattern.clone();
+    }
+
+    let pattern = match grammar.rule(rule) {
+        Some(Rule::Char(c)) => regex::escape(&c.to_string()),
+        Some(Rule::Alternatives(alternatives)) => {
+            let alternatives: Vec<String> = alternatives.iter()
+                .map(|sequence| sequence.iter().map(|&part| pattern(grammar, part, patterns)).collect())
+                .collect();
+
+            format!("(?:{})", alternatives.join("|"))
+        }
+        None => unreachable!("Rule {} was checked before compiling.", rule),
+    };
+
+    patterns.insert(rule, pattern.clone());
+    pattern
+}
+
+/// Returns the rules the given rule refers to directly.
+fn references(grammar: &Grammar, rule: i32) -> Vec<i32> {
+    match grammar.rule(rule) {
+        Some(Rule::Alternatives(alternatives)) => alternatives.iter().flatten().copied().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the rules that can be reached from the given rules, including the rules themselves.
+fn reachable(grammar: &Grammar, rules: &[i32]) -> BTreeSet<i32> {
+    let mut seen = BTreeSet::new();
+    let mut to_visit = rules.to_vec();
+
+    while let Some(rule) = to_visit.pop() {
+        if seen.insert(rule) {
+            to_visit.extend(references(grammar, rule));
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Puzzle;
+
+    use super::*;
+
+    #[test]
+    fn compile_sample() {
+        let puzzle = Puzzle::load("sample.txt").unwrap();
+        let regex = compile(puzzle.grammar(), 0).unwrap();
+
+        assert!(regex.is_match("ababbb"));
+        assert!(!regex.is_match("aaaabbb"));
+        assert!(compile(puzzle.grammar(), 3).unwrap().is_match("ba"));
+    }
+
+    #[test]
+    fn compile_recursive() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
+        let grammar = puzzle.recursive_grammar().unwrap();
+
+        assert_eq!(vec![8, 11], recursive_rules(&grammar, 0));
+        assert!(recursive_rules(&grammar, 42).is_empty());
+
+        let err = compile(&grammar, 0).unwrap_err();
+        assert_eq!(CompileError::Recursive { rule: 0, recursive_rules: vec![8, 11] }, err);
+        assert_eq!("Rule 0 isn't regular: it depends on recursive rules 8, 11.", err.to_string());
+
+        assert!(compile(&grammar, 42).is_ok());
+        assert_eq!(Err(CompileError::Undefined(99)), compile(&grammar, 99).map(|_| ()));
+    }
+
+    #[test]
+    fn regex_agrees_with_chart() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
+        let regex = compile(puzzle.grammar(), 0).unwrap();
+
+        for message in &puzzle.messages {
+            assert_eq!(puzzle.grammar().matches(0, message), regex.is_match(message), "{}", message);
+        }
+    }
+
+    #[test]
+    fn matcher_falls_back() {
+        let puzzle = Puzzle::load("recursive_sample.txt").unwrap();
+        let grammar = puzzle.recursive_grammar().unwrap();
+
+        let regular = Matcher::new(puzzle.grammar(), 0);
+        let recursive = Matcher::new(&grammar, 0);
+
+        assert_eq!(None, regular.compile_error());
+        assert_eq!(Some(&CompileError::Recursive { rule: 0, recursive_rules: vec![8, 11] }), recursive.compile_error());
+
+        assert!(regular.matches("bbabbbbaabaabba"));
+        assert!(!regular.matches("babbbbaabbbbbabbbbbbaabaaabaaa"));
+        assert!(recursive.matches("babbbbaabbbbbabbbbbbaabaaabaaa"));
+    }
+}
@@ -0,0 +1,188 @@
+use std::ops::Range;
+use std::str::FromStr;
+use regex::Regex;
+use std::io::BufRead;
+use common::{LoadError, ParseError, Solution};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct PasswordPolicy {
+    password: String,
+    policy: Policy
+}
+
+impl PasswordPolicy {
+    /// Returns whether the password is valid by checking whether the count of the letter
+    /// in the password fits in the range.
+    fn validate_range(&self) -> bool {
+        let letter_count = self.password.chars()
+            .filter(|c| *c == self.policy.letter)
+            .count();
+
+        letter_count >= self.policy.count.start && letter_count <= self.policy.count.end
+    }
+
+    /// Returns whether the password is valid by checking whether the character appears exactly
+    /// once at the range positions.  Positions are 1-indexed, and `1-3 a: abcde` is valid because
+    /// `a` appears at position 1 (and not at 3).
+    fn validate_position(&self) -> bool {
+        let start_letter = self.password.as_bytes()[self.policy.count.start - 1] as char;
+        let end_letter = self.password.as_bytes()[self.policy.count.end - 1] as char;
+
+        (start_letter == self.policy.letter && end_letter != self.policy.letter)
+            || (start_letter != self.policy.letter && end_letter == self.policy.letter)
+    }
+}
+
+impl FromStr for PasswordPolicy {
+    type Err = ParseError;
+
+    /// Parses a PasswordPolicy from the given string.  Policies look like `1-3 a: abcde`,
+    /// and consist of a range, a letter, and a password.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let re = Regex::new(r"^(\d+)-(\d+) ([a-z]): ([a-z]+)$").unwrap();
+
+        let captures = re.captures(s)
+            .ok_or_else(|| ParseError::new(s, 1, "a policy and password like '1-3 a: abcde'"))?;
+
+        // Counts match digits, but can still be too large to fit in a usize.
+        let count = |i: usize| {
+            let m = captures.get(i).unwrap();
+            m.as_str().parse::<usize>().map_err(|_| ParseError::new(s, m.start() + 1, "a count"))
+        };
+
+        Ok(PasswordPolicy {
+            password: String::from(&captures[4]),
+            policy: Policy {
+                count: count(1)? .. count(2)?,
+                letter: captures[3].parse().unwrap(),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Policy {
+    count: Range<usize>,
+    letter: char,
+}
+
+/// Loads passwords and policies from the given file.
+pub fn load(filename: &str) -> Result<Vec<PasswordPolicy>, LoadError> {
+    read(common::open(filename)?)
+}
+
+/// Reads passwords and policies from the given reader, one per line.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<PasswordPolicy>, LoadError> {
+    common::parse_lines(reader)
+}
+
+/// Counts the number of valid passwords in the list using the range validator.
+pub fn count_valid_range(passwords: &[PasswordPolicy]) -> usize {
+    passwords.iter()
+        .filter(|p| p.validate_range())
+        .count()
+}
+
+/// Counts the number of valid passwords in the list using the position validator.
+pub fn count_valid_position(passwords: &[PasswordPolicy]) -> usize {
+    passwords.iter()
+        .filter(|p| p.validate_position())
+        .count()
+}
+
+pub struct Day2 {
+    passwords: Vec<PasswordPolicy>,
+}
+
+impl Solution for Day2 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day2 { passwords: read(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        count_valid_range(&self.passwords)
+    }
+
+    fn part2(&self) -> usize {
+        count_valid_position(&self.passwords)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_password_policy() {
+        let parsed = "1-3 a: abcde".parse::<PasswordPolicy>().unwrap();
+        let expected = PasswordPolicy {
+            password: String::from("abcde"),
+            policy: Policy { count: 1..3, letter: 'a' },
+        };
+
+        assert_eq!(expected, parsed);
+    }
+
+    #[test]
+    fn parse_invalid_password_policy() {
+        assert_eq!(Err(ParseError::new("1-3 a abcde", 1, "a policy and password like '1-3 a: abcde'")),
+                   "1-3 a abcde".parse::<PasswordPolicy>());
+        assert_eq!(Err(ParseError::new("1-99999999999999999999 a: abcde", 3, "a count")),
+                   "1-99999999999999999999 a: abcde".parse::<PasswordPolicy>());
+    }
+
+    #[test]
+    fn validate_password_policy_range() {
+        let a = PasswordPolicy {
+            password: String::from("abcde"),
+            policy: Policy { count: 1..3, letter: 'a' },
+        };
+
+        let b = PasswordPolicy {
+            password: String::from("cdefg"),
+            policy: Policy { count: 1..3, letter: 'b' },
+        };
+
+        assert!(a.validate_range());
+        assert!(!b.validate_range());
+    }
+
+    #[test]
+    fn validate_password_policy_position() {
+        let a = PasswordPolicy {
+            password: String::from("abcde"),
+            policy: Policy { count: 1..3, letter: 'a' },
+        };
+
+        let b = PasswordPolicy {
+            password: String::from("cdefg"),
+            policy: Policy { count: 1..3, letter: 'b' },
+        };
+
+        assert!(a.validate_position());
+        assert!(!b.validate_position());
+    }
+
+    #[test]
+    fn test_count_valid_range() {
+        let passwords = load("sample.txt").unwrap();
+        assert_eq!(count_valid_range(&passwords), 2);
+    }
+
+    #[test]
+    fn test_count_valid_position() {
+        let passwords = load("sample.txt").unwrap();
+        assert_eq!(count_valid_position(&passwords), 1);
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day2::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(2, day.part1());
+        assert_eq!(1, day.part2());
+    }
+}
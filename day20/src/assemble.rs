@@ -0,0 +1,511 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use grid::{Grid, Transform};
+
+use crate::{edge_num, Picture, Tile};
+
+/// Shape is the number of tiles down and across an assembled image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Shape {
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Shape {
+    /// Returns the largest square shape that the given number of tiles can fill.
+    pub fn square(tiles: usize) -> Shape {
+        let mut side = (tiles as f64).sqrt() as usize;
+        while side * side > tiles {
+            side -= 1;
+        }
+        while (side + 1) * (side + 1) <= tiles {
+            side += 1;
+        }
+
+        Shape { rows: side, cols: side }
+    }
+
+    fn area(&self) -> usize {
+        self.rows * self.cols
+    }
+}
+
+/// AssembleError is why tiles couldn't be assembled into an image.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AssembleError {
+    /// The shape has no rows or no columns.
+    EmptyShape,
+
+    /// The shape needs more tiles than there are.
+    NotEnoughTiles { needed: usize, available: usize },
+
+    /// No arrangement of the tiles fits.  placed is the most tiles that could be fit together,
+    /// in order across each row, and nothing fit at (row, col) next to the conflicting tiles
+    /// (the tiles above and to the left of that spot).
+    NoFit { placed: Vec<i32>, row: usize, col: usize, conflicting: Vec<i32> },
+}
+
+impl Display for AssembleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AssembleError::EmptyShape => write!(f, "An image needs at least one row and one column of tiles."),
+            AssembleError::NotEnoughTiles { needed, available } =>
+                write!(f, "The image needs {} tiles, but there are only {}.", needed, available),
+            AssembleError::NoFit { placed, row, col, conflicting } => {
+                let conflicting: Vec<String> = conflicting.iter().map(|id| id.to_string()).collect();
+                write!(f, "The tiles don't fit together: at most {} fit, and no tile fits at row {}, column {}", placed.len(), row + 1, col + 1)?;
+
+                match conflicting.len() {
+                    0 => write!(f, "."),
+                    _ => write!(f, " next to tiles {}.", conflicting.join(" and ")),
+                }
+            }
+        }
+    }
+}
+
+impl Error for AssembleError {}
+
+/// Assembly is tiles rotated, flipped, and placed so that every pair of neighboring tiles
+/// shares an edge.
+#[derive(Debug, Clone)]
+pub struct Assembly {
+    tiles: Vec<Vec<Tile>>,
+    unused: Vec<i32>,
+}
+
+impl Assembly {
+    /// Returns the ids of the placed tiles, by row.
+    pub fn ids(&self) -> Vec<Vec<i32>> {
+        self.tiles.iter().map(|row| row.iter().map(|tile| tile.id).collect()).collect()
+    }
+
+    /// Returns the ids of the tiles that weren't needed to fill the shape, which don't belong
+    /// in the image.
+    pub fn unused(&self) -> &[i32] {
+        &self.unused
+    }
+
+    /// Returns the ids of the corner tiles multiplied together.  Each tile is only counted
+    /// once, so an image that's one tile across has two corners.
+    pub fn corners(&self) -> i64 {
+        let last_row = self.tiles.len() - 1;
+        let last_col = self.tiles[0].len() - 1;
+
+        let corners: HashSet<(usize, usize)> = [(0, 0), (0, last_col), (last_row, 0), (last_row, last_col)].iter().cloned().collect();
+        corners.iter().map(|&(row, col)| self.tiles[row][col].id as i64).product()
+    }
+
+    /// Returns the picture made by removing each tile's edges.
+    pub fn picture(&self) -> Picture {
+        let middle = Tile::SIZE - 2;
+        let mut values = Grid::new(self.tiles[0].len() * middle, self.tiles.len() * middle, ' ');
+
+        for (tile_row, row) in self.tiles.iter().enumerate() {
+            for (tile_col, tile) in row.iter().enumerate() {
+                let without_edges = tile.without_edges();
+
+                for (r, c) in without_edges.positions() {
+                    values[(tile_row * middle + r, tile_col * middle + c)] = without_edges[(r, c)];
+                }
+            }
+        }
+
+        Picture { values }
+    }
+
+    /// Returns the assembled image with the tiles' edges, which identifies the assembly along
+    /// with the tile ids.
+    fn image(&self) -> Grid<char> {
+        let mut values = Grid::new(self.tiles[0].len() * Tile::SIZE, self.tiles.len() * Tile::SIZE, ' ');
+
+        for (tile_row, row) in self.tiles.iter().enumerate() {
+            for (tile_col, tile) in row.iter().enumerate() {
+                for (r, c) in tile.values.positions() {
+                    values[(tile_row * Tile::SIZE + r, tile_col * Tile::SIZE + c)] = tile.values[(r, c)];
+                }
+            }
+        }
+
+        values
+    }
+
+    /// Returns a key that's the same for this assembly and any rotated or flipped copy of it
+    /// with the same shape.
+    fn symmetry_key(&self) -> (Vec<Vec<i32>>, Vec<Vec<char>>) {
+        let ids = Grid::from_rows(self.ids());
+        let image = self.image();
+
+        Transform::ALL.iter()
+            .map(|&transform| (ids.transform(transform), image.transform(transform)))
+            .filter(|(ids, _)| ids.height() == self.tiles.len())
+            .map(|(ids, image)| (grid_rows(&ids), grid_rows(&image)))
+            .min()
+            .unwrap()
+    }
+}
+
+/// Returns the grid's rows, which can be compared.
+fn grid_rows<T: Clone>(grid: &Grid<T>) -> Vec<Vec<T>> {
+    grid.rows().map(|row| row.to_vec()).collect()
+}
+
+impl Display for Assembly {
+    /// Writes the tile ids a row at a time, followed by any unused tiles.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.ids() {
+            let row: Vec<String> = row.iter().map(|id| id.to_string()).collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+
+        if !self.unused.is_empty() {
+            let unused: Vec<String> = self.unused.iter().map(|id| id.to_string()).collect();
+            writeln!(f, "Unused: {}", unused.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Piece is a tile in one of its 8 orientations, along with its edges read left to right or
+/// top to bottom.
+struct Piece {
+    tile: usize,
+    values: Grid<char>,
+    top: u32,
+    bottom: u32,
+    left: u32,
+    right: u32,
+}
+
+impl Piece {
+    fn new(tile: usize, values: Grid<char>) -> Piece {
+        let last = Tile::SIZE - 1;
+        let edge = |position: &dyn Fn(usize) -> (usize, usize)| -> u32 {
+            edge_num(&(0..Tile::SIZE).map(|i| values[position(i)]).collect::<String>())
+        };
+
+        let top = edge(&|i| (0, i));
+        let bottom = edge(&|i| (last, i));
+        let left = edge(&|i| (i, 0));
+        let right = edge(&|i| (i, last));
+
+        Piece { tile, values, top, bottom, left, right }
+    }
+}
+
+/// Search places pieces across each row in turn, backing up when nothing fits.
+struct Search<'a> {
+    tiles: &'a [Tile],
+    shape: Shape,
+    pieces: Vec<Piece>,
+    /// Pieces by their left edge and by their top edge.
+    by_left: HashMap<u32, Vec<usize>>,
+    by_top: HashMap<u32, Vec<usize>>,
+    used: Vec<bool>,
+    placed: Vec<usize>,
+    /// The most pieces placed at once, for explaining failures.
+    best: Vec<usize>,
+    find_all: bool,
+    found: Vec<Assembly>,
+}
+
+impl<'a> Search<'a> {
+    fn new(tiles: &'a [Tile], shape: Shape, find_all: bool) -> Search<'a> {
+        let pieces: Vec<Piece> = tiles.iter().enumerate()
+            .flat_map(|(i, tile)| Transform::ALL.iter().map(move |&transform| Piece::new(i, tile.values.transform(transform))))
+            .collect();
+
+        let mut by_left: HashMap<u32, Vec<usize>> = HashMap::new();
+        let mut by_top: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, piece) in pieces.iter().enumerate() {
+            by_left.entry(piece.left).or_default().push(i);
+            by_top.entry(piece.top).or_default().push(i);
+        }
+
+        Search {
+            tiles, shape, pieces, by_left, by_top,
+            used: vec![false; tiles.len()],
+            placed: Vec::new(),
+            best: Vec::new(),
+            find_all,
+            found: Vec::new(),
+        }
+    }
+
+    /// Places pieces from the current position on.  Returns true once an assembly is found,
+    /// unless every assembly is wanted.
+    fn search(&mut self) -> bool {
+        if self.placed.len() > self.best.len() {
+            self.best = self.placed.clone();
+        }
+
+        if self.placed.len() == self.shape.area() {
+            self.found.push(self.assembly(&self.placed));
+            return !self.find_all;
+        }
+
+        for candidate in self.candidates() {
+            let tile = self.pieces[candidate].tile;
+            if self.used[tile] {
+                continue;
+            }
+
+            self.used[tile] = true;
+            self.placed.push(candidate);
+
+            if self.search() {
+                return true;
+            }
+
+            self.placed.pop();
+            self.used[tile] = false;
+        }
+
+        false
+    }
+
+    /// Returns the pieces whose edges fit the pieces above and to the left of the next
+    /// position, whether or not their tiles are used.
+    fn candidates(&self) -> Vec<usize> {
+        let position = self.placed.len();
+        let (row, col) = (position / self.shape.cols, position % self.shape.cols);
+
+        let above = (row > 0).then(|| &self.pieces[self.placed[position - self.shape.cols]]);
+        let left = (col > 0).then(|| &self.pieces[self.placed[position - 1]]);
+
+        match (above, left) {
+            (_, Some(left)) => self.by_left.get(&left.right).cloned().unwrap_or_default().into_iter()
+                .filter(|&candidate| above.is_none_or(|above| above.bottom == self.pieces[candidate].top))
+                .collect(),
+            (Some(above), None) => self.by_top.get(&above.bottom).cloned().unwrap_or_default(),
+            (None, None) => (0..self.pieces.len()).collect(),
+        }
+    }
+
+    /// Returns the assembly made by the given pieces.
+    fn assembly(&self, placed: &[usize]) -> Assembly {
+        let tiles = placed.chunks(self.shape.cols)
+            .map(|row| row.iter().map(|&piece| {
+                let piece = &self.pieces[piece];
+                Tile { id: self.tiles[piece.tile].id, values: piece.values.clone() }
+            }).collect())
+            .collect();
+
+        let used: HashSet<usize> = placed.iter().map(|&piece| self.pieces[piece].tile).collect();
+        let unused = (0..self.tiles.len())
+            .filter(|tile| !used.contains(tile))
+            .map(|tile| self.tiles[tile].id)
+            .collect();
+
+        Assembly { tiles, unused }
+    }
+
+    /// Returns the error explaining why nothing was found, from the most pieces placed.
+    fn no_fit(&self) -> AssembleError {
+        let position = self.best.len();
+        let (row, col) = (position / self.shape.cols, position % self.shape.cols);
+        let id = |position: usize| self.tiles[self.pieces[self.best[position]].tile].id;
+
+        let mut conflicting = Vec::new();
+        if row > 0 {
+            conflicting.push(id(position - self.shape.cols));
+        }
+        if col > 0 {
+            conflicting.push(id(position - 1));
+        }
+
+        AssembleError::NoFit { placed: (0..position).map(id).collect(), row, col, conflicting }
+    }
+}
+
+/// Finds assemblies of the tiles in the given shape, trying every tile in every orientation
+/// and backing up when a tile doesn't fit.  Tiles left over when the shape is full are unused.
+/// If find_all is false, stops at the first assembly.  Otherwise returns every assembly, with
+/// only one of the rotated and flipped copies of each.
+pub fn assemble(tiles: &[Tile], shape: Shape, find_all: bool) -> Result<Vec<Assembly>, AssembleError> {
+    if shape.area() == 0 {
+        return Err(AssembleError::EmptyShape);
+    }
+
+    if shape.area() > tiles.len() {
+        return Err(AssembleError::NotEnoughTiles { needed: shape.area(), available: tiles.len() });
+    }
+
+    let mut search = Search::new(tiles, shape, find_all);
+    search.search();
+
+    if search.found.is_empty() {
+        return Err(search.no_fit());
+    }
+
+    let mut seen = HashSet::new();
+    Ok(search.found.into_iter()
+        .filter(|assembly| seen.insert(assembly.symmetry_key()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+
+    use crate::Tiles;
+
+    use super::*;
+
+    /// Cuts a random image into tiles in the given shape, where neighbors share their edges,
+    /// and rotates, flips, and shuffles them.  Tile ids are numbered from 1 across each row.
+    fn cut(rng: &mut StdRng, shape: Shape) -> Vec<Tile> {
+        let step = Tile::SIZE - 1;
+        let mut image = Grid::new(shape.cols * step + 1, shape.rows * step + 1, '.');
+        for position in image.positions().collect::<Vec<_>>() {
+            image[position] = if rng.gen() { '#' } else { '.' };
+        }
+
+        let mut tiles: Vec<Tile> = (0..shape.area())
+            .map(|i| {
+                let values = image.sub_grid(i / shape.cols * step, i % shape.cols * step, Tile::SIZE, Tile::SIZE);
+                Tile { id: i as i32 + 1, values: values.transform(*Transform::ALL.choose(rng).unwrap()) }
+            })
+            .collect();
+        tiles.shuffle(rng);
+
+        tiles
+    }
+
+    /// Returns a tile with random squares.
+    fn random_tile(rng: &mut StdRng, id: i32) -> Tile {
+        let mut values = Grid::new(Tile::SIZE, Tile::SIZE, '.');
+        for position in values.positions().collect::<Vec<_>>() {
+            values[position] = if rng.gen() { '#' } else { '.' };
+        }
+
+        Tile { id, values }
+    }
+
+    /// Returns whether every pair of neighbors in the assembly shares an edge.
+    fn fits(assembly: &Assembly) -> bool {
+        let last = Tile::SIZE - 1;
+
+        assembly.tiles.iter().enumerate().all(|(row, tiles)| {
+            tiles.iter().enumerate().all(|(col, tile)| {
+                (0..Tile::SIZE).all(|i| {
+                    (col == 0 || tiles[col - 1].values[(i, last)] == tile.values[(i, 0)])
+                        && (row == 0 || assembly.tiles[row - 1][col].values[(last, i)] == tile.values[(0, i)])
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn square_shape() {
+        assert_eq!(Shape { rows: 3, cols: 3 }, Shape::square(9));
+        assert_eq!(Shape { rows: 3, cols: 3 }, Shape::square(10));
+        assert_eq!(Shape { rows: 12, cols: 12 }, Shape::square(144));
+    }
+
+    #[test]
+    fn assemble_sample() {
+        let tiles = Tiles::load("sample.txt").unwrap();
+        let assembly = tiles.assemble(Shape::square(9)).unwrap();
+
+        assert!(fits(&assembly));
+        assert!(assembly.unused().is_empty());
+        assert_eq!(20899048083289, assembly.corners());
+        assert_eq!(273, assembly.picture().roughness());
+    }
+
+    #[test]
+    fn assemble_all_sample() {
+        let tiles = Tiles::load("sample.txt").unwrap();
+
+        // The 8 rotated and flipped copies of the image count as one assembly.
+        assert_eq!(1, tiles.assemble_all(Shape::square(9)).unwrap().len());
+    }
+
+    #[test]
+    fn assemble_non_square() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let tiles = Tiles { tiles: cut(&mut rng, Shape { rows: 2, cols: 5 }) };
+
+        let assembly = tiles.assemble(Shape { rows: 2, cols: 5 }).unwrap();
+        assert!(fits(&assembly));
+        assert_eq!(5 * 6 * 10, assembly.corners());
+
+        let tall = tiles.assemble(Shape { rows: 5, cols: 2 }).unwrap();
+        assert_eq!(5, tall.ids().len());
+
+        let row = Tiles { tiles: cut(&mut rng, Shape { rows: 1, cols: 4 }) }.assemble(Shape { rows: 1, cols: 4 }).unwrap();
+        assert_eq!(4, row.corners());
+    }
+
+    #[test]
+    fn assemble_with_extra_tiles() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut tiles = cut(&mut rng, Shape { rows: 3, cols: 3 });
+
+        // A copy of the center tile with one edge redrawn matches the center's neighbors on
+        // three sides, so it's only ruled out once the fourth neighbor is placed.
+        let mut decoy = tiles.iter().find(|tile| tile.id == 5).unwrap().clone();
+        decoy.id = 100;
+        for r in 1..Tile::SIZE - 1 {
+            decoy.values[(r, 0)] = if decoy.values[(r, 0)] == '#' { '.' } else { '#' };
+        }
+        tiles.push(decoy);
+        tiles.push(random_tile(&mut rng, 101));
+
+        let assembly = Tiles { tiles }.assemble(Shape { rows: 3, cols: 3 }).unwrap();
+
+        assert!(fits(&assembly));
+        assert_eq!(vec![100, 101], assembly.unused());
+    }
+
+    #[test]
+    fn assemble_ambiguous_edges() {
+        // Every tile is blank, so every edge matches every other, and any arrangement works.
+        let tiles: Vec<Tile> = (1..=3).map(|id| Tile { id, values: Grid::new(Tile::SIZE, Tile::SIZE, '.') }).collect();
+        let tiles = Tiles { tiles };
+
+        assert!(fits(&tiles.assemble(Shape { rows: 1, cols: 3 }).unwrap()));
+
+        // Assemblies differ by which tile is where: 3! orders, where each order and its reverse
+        // are copies of each other.
+        assert_eq!(3, tiles.assemble_all(Shape { rows: 1, cols: 3 }).unwrap().len());
+    }
+
+    #[test]
+    fn assemble_unsolvable() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut tiles = cut(&mut rng, Shape { rows: 2, cols: 2 });
+
+        assert_eq!(
+            Err(AssembleError::NotEnoughTiles { needed: 9, available: 4 }),
+            Tiles { tiles: tiles.clone() }.assemble(Shape { rows: 3, cols: 3 }).map(|_| ()),
+        );
+        assert_eq!(Err(AssembleError::EmptyShape), Tiles { tiles: tiles.clone() }.assemble(Shape { rows: 0, cols: 2 }).map(|_| ()));
+
+        let replaced = tiles.pop().unwrap();
+        tiles.push(random_tile(&mut rng, replaced.id));
+
+        match (Tiles { tiles }).assemble(Shape { rows: 2, cols: 2 }) {
+            Err(AssembleError::NoFit { placed, row, col, conflicting }) => {
+                assert_eq!(3, placed.len());
+                assert_eq!((1, 1), (row, col));
+                assert_eq!(2, conflicting.len());
+                assert!(!conflicting.contains(&replaced.id));
+            }
+            other => panic!("Expected no fit, got {:?}", other.map(|assembly| assembly.ids())),
+        }
+    }
+
+    #[test]
+    fn no_fit_message() {
+        let err = AssembleError::NoFit { placed: vec![1, 2, 3], row: 1, col: 1, conflicting: vec![2, 3] };
+
+        assert_eq!("The tiles don't fit together: at most 3 fit, and no tile fits at row 2, column 2 next to tiles 2 and 3.", err.to_string());
+    }
+}
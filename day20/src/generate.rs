@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+use rand::seq::{index, SliceRandom};
+
+use grid::{Grid, Transform};
+
+use crate::{edge_num, MONSTER, Tile};
+
+/// Params size a generated set of tiles.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// Number of tiles along each side of the image, at most 12.  Edges are 10 squares long,
+    /// so there aren't enough distinct edges for larger images.
+    pub dimension: usize,
+
+    /// Number of sea monsters drawn into the image.
+    pub monsters: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { dimension: 12, monsters: 20 }
+    }
+}
+
+/// Generates tiles by cutting up a random image.  Neighboring tiles share the squares along
+/// their common edge, and every edge is unique so there's only one way to put the image back
+/// together.  Each tile is randomly rotated and flipped, and the tiles are shuffled.
+pub fn generate<R: Rng>(rng: &mut R, params: &Params) -> String {
+    let dimension = params.dimension.clamp(1, 12);
+
+    let image = loop {
+        if let Some(image) = random_image(rng, dimension, params.monsters) {
+            break image;
+        }
+    };
+
+    let step = Tile::SIZE - 1;
+    let ids = index::sample(rng, 9000, dimension * dimension);
+
+    let mut tiles: Vec<String> = ids.iter().enumerate()
+        .map(|(i, id)| {
+            let (row, col) = (i / dimension * step, i % dimension * step);
+            let transform = *Transform::ALL.choose(rng).unwrap();
+            let values = image.sub_grid(row, col, Tile::SIZE, Tile::SIZE).transform(transform);
+
+            format!("Tile {}:\n{}", id + 1000, values)
+        })
+        .collect();
+    tiles.shuffle(rng);
+
+    tiles.join("\n")
+}
+
+/// Returns a random image made up of the given number of tiles on each side, with sea monsters
+/// drawn in the parts that are left when the tile edges are removed.  Returns None if the image
+/// couldn't be given unique edges.
+fn random_image<R: Rng>(rng: &mut R, dimension: usize, monsters: usize) -> Option<Grid<char>> {
+    let step = Tile::SIZE - 1;
+    let size = dimension * step + 1;
+
+    let square = |rng: &mut R| if rng.gen() { '#' } else { '.' };
+
+    let mut image = Grid::new(size, size, '.');
+    for position in image.positions().collect::<Vec<_>>() {
+        image[position] = square(rng);
+    }
+
+    // Sea monsters go in the picture that's left after removing edges, which is 8 squares per tile.
+    let picture_size = dimension * (Tile::SIZE - 2);
+    let image_position = |row: usize, col: usize| (row / 8 * step + 1 + row % 8, col / 8 * step + 1 + col % 8);
+
+    if picture_size >= MONSTER[0].len() {
+        for _ in 0..monsters {
+            let row = rng.gen_range(0..=picture_size - MONSTER.len());
+            let col = rng.gen_range(0..=picture_size - MONSTER[0].len());
+
+            for r in 0..MONSTER.len() {
+                for c in 0..MONSTER[r].len() {
+                    if MONSTER[r][c] == '#' {
+                        image[image_position(row + r, col + c)] = '#';
+                    }
+                }
+            }
+        }
+    }
+
+    // Redraw the middle of each edge until it's distinct from every other edge, forwards and
+    // backwards.  Corners are shared by several edges, so they're left alone.
+    let mut used = HashSet::new();
+    for line in 0..=dimension {
+        for segment in 0..dimension {
+            for &vertical in [false, true].iter() {
+                let position = |i: usize| {
+                    let along = segment * step + i;
+                    if vertical { (along, line * step) } else { (line * step, along) }
+                };
+
+                let mut attempts = 0;
+                loop {
+                    let edge: String = (0..Tile::SIZE).map(|i| image[position(i)]).collect();
+                    let reversed: String = edge.chars().rev().collect();
+
+                    if edge != reversed && !used.contains(&edge_num(&edge)) && !used.contains(&edge_num(&reversed)) {
+                        used.insert(edge_num(&edge));
+                        used.insert(edge_num(&reversed));
+                        break;
+                    }
+
+                    attempts += 1;
+                    if attempts > 1000 {
+                        return None;
+                    }
+
+                    for i in 1..Tile::SIZE - 1 {
+                        image[position(i)] = square(rng);
+                    }
+                }
+            }
+        }
+    }
+
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use common::Solution;
+
+    use crate::Day20;
+
+    use super::*;
+
+    #[test]
+    fn generate_solvable() {
+        for seed in 0..5 {
+            let input = generate(&mut StdRng::seed_from_u64(seed), &Params { dimension: 4, monsters: 2 });
+            let day = Day20::parse(&input).unwrap();
+
+            assert_eq!(16, day.tiles.tiles.len());
+            assert!(day.tiles.to_picture().unwrap().count_sea_monsters() >= 1);
+            day.part1();
+        }
+    }
+
+    #[test]
+    fn generate_unique_edges() {
+        let input = generate(&mut StdRng::seed_from_u64(1), &Params::default());
+        let day = Day20::parse(&input).unwrap();
+
+        assert_eq!(144, day.tiles.tiles.len());
+        assert_eq!(144 * 8, day.tiles.tiles.iter().flat_map(|tile| tile.all_edges()).collect::<HashSet<_>>().len() + 2 * 12 * 11 * 2);
+    }
+
+    #[test]
+    fn generate_seeded() {
+        let params = Params::default();
+
+        assert_eq!(generate(&mut StdRng::seed_from_u64(7), &params), generate(&mut StdRng::seed_from_u64(7), &params));
+    }
+}
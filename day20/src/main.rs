@@ -1,8 +1,14 @@
+use common::LoadError;
 use day20::Tiles;
 
-fn main() {
-    let puzzle = Tiles::load("input.txt");
+fn main() -> Result<(), LoadError> {
+    let puzzle = Tiles::load("input.txt")?;
 
     println!("Part 1: {}", puzzle.corners());
-    println!("Part 2: {}", puzzle.to_picture().roughness());
+    match puzzle.to_picture() {
+        Ok(picture) => println!("Part 2: {}", picture.roughness()),
+        Err(e) => println!("Part 2: {}", e),
+    }
+
+    Ok(())
 }
@@ -1,13 +1,8 @@
 use std::str::FromStr;
-use std::fs::File;
-use std::io::{BufReader, BufRead};
+use std::io::BufRead;
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
-
-#[derive(Debug, Eq, PartialEq)]
-pub enum ParseErr {
-    Invalid
-}
+use common::{LoadError, ParseError, Solution};
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct Food {
@@ -16,12 +11,16 @@ pub struct Food {
 }
 
 impl FromStr for Food {
-    type Err = ParseErr;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // s is a set of ingredients separated by spaces, followed by a list of comma space
         // separated allergens like '(contains dairy, fish)'
-        let contains_index = s.find(" (contains ").unwrap();
+        let contains_index = s.find(" (contains ")
+            .ok_or_else(|| ParseError::new(s, s.len() + 1, "an allergen list like ' (contains dairy, fish)'"))?;
+        if !s.ends_with(')') {
+            return Err(ParseError::new(s, s.len() + 1, "')'"));
+        }
 
         let ingredients: Vec<String> = s[0..contains_index].split(" ")
             .map(str::to_owned)
@@ -37,15 +36,17 @@ impl FromStr for Food {
 }
 
 /// Loads a list of food from the given file.
-pub fn load_food(filename: &str) -> Vec<Food> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+pub fn load_food(filename: &str) -> Result<Vec<Food>, LoadError> {
+    read_food(common::open(filename)?)
+}
 
-    f.lines().map(|line| line.unwrap().parse().unwrap()).collect()
+/// Reads a list of food from the given reader, one per line.
+pub fn read_food<R: BufRead>(reader: R) -> Result<Vec<Food>, LoadError> {
+    common::parse_lines(reader)
 }
 
 /// Counts the number of allergens that can't contain any allergens in the list of food.
-pub fn count_non_allergens(foods: &Vec<Food>) -> usize {
+pub fn count_non_allergens(foods: &[Food]) -> usize {
     // Allergen ingredient must show up in all of the foods.
     // Ingredient can't belong to multiple allergens.
     // Foods may have allergens that aren't labeled.
@@ -89,8 +90,9 @@ pub fn count_non_allergens(foods: &Vec<Food>) -> usize {
 }
 
 /// Determines which ingredients are allergens in the foods, sorts them alphabetically by allergen,
-/// and returns a comma-separated list of the allergen ingredients.
-pub fn dangerous_ingredients(foods: &Vec<Food>) -> String {
+/// and returns a comma-separated list of the allergen ingredients.  Returns an error if no
+/// ingredient can be identified as an allergen.
+pub fn dangerous_ingredients(foods: &[Food]) -> Result<String, LoadError> {
     // Allergen ingredient must show up in all of the foods.
     // Ingredient can't belong to multiple allergens.
     // Foods may have allergens that aren't labeled.
@@ -158,8 +160,32 @@ pub fn dangerous_ingredients(foods: &Vec<Food>) -> String {
 
     let mut ingredients_iter = allergens.iter().map(|allergen| identified_allergens.get(allergen).unwrap());
 
-    let first_ingredient = ingredients_iter.next().unwrap().clone().clone();
-    ingredients_iter.fold(first_ingredient, |str, ingredient| format!("{},{}", str, ingredient))
+    let first_ingredient = ingredients_iter.next()
+        .ok_or_else(|| LoadError::invalid("No ingredients can be identified as allergens."))?
+        .to_string();
+
+    Ok(ingredients_iter.fold(first_ingredient, |str, ingredient| format!("{},{}", str, ingredient)))
+}
+
+pub struct Day21 {
+    foods: Vec<Food>,
+}
+
+impl Solution for Day21 {
+    type Part1 = usize;
+    type Part2 = Result<String, LoadError>;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day21 { foods: read_food(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        count_non_allergens(&self.foods)
+    }
+
+    fn part2(&self) -> Result<String, LoadError> {
+        dangerous_ingredients(&self.foods)
+    }
 }
 
 #[cfg(test)]
@@ -169,16 +195,20 @@ mod tests {
     #[test]
     fn parse_food() {
         let expected = Food {
-            ingredients: vec!["mxmxvkd", "kfcds", "sqjhc", "nhms"].iter().map(|&s| s.to_owned()).collect(),
-            allergens: vec!["dairy", "fish"].iter().map(|&s| s.to_owned()).collect(),
+            ingredients: ["mxmxvkd", "kfcds", "sqjhc", "nhms"].iter().map(|&s| s.to_owned()).collect(),
+            allergens: ["dairy", "fish"].iter().map(|&s| s.to_owned()).collect(),
         };
 
-        assert_eq!(Ok(expected), "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)".parse())
+        assert_eq!(Ok(expected), "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)".parse());
+        assert_eq!(Err(ParseError::new("mxmxvkd kfcds sqjhc nhms", 25, "an allergen list like ' (contains dairy, fish)'")),
+                   "mxmxvkd kfcds sqjhc nhms".parse::<Food>());
+        assert_eq!(Err(ParseError::new("mxmxvkd (contains dairy", 24, "')'")),
+                   "mxmxvkd (contains dairy".parse::<Food>());
     }
 
     #[test]
     fn load_sample() {
-        let sample = load_food("sample.txt");
+        let sample = load_food("sample.txt").unwrap();
 
         assert_eq!(4, sample.len());
 
@@ -188,15 +218,31 @@ mod tests {
 
     #[test]
     fn count_sample() {
-        let sample = load_food("sample.txt");
+        let sample = load_food("sample.txt").unwrap();
 
         assert_eq!(5, count_non_allergens(&sample));
     }
 
     #[test]
     fn dangerous_sample() {
-        let sample = load_food("sample.txt");
+        let sample = load_food("sample.txt").unwrap();
+
+        assert_eq!("mxmxvkd,sqjhc,fvjkl", dangerous_ingredients(&sample).unwrap());
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day21::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(5, day.part1());
+        assert_eq!("mxmxvkd,sqjhc,fvjkl", day.part2().unwrap());
+    }
 
-        assert_eq!("mxmxvkd,sqjhc,fvjkl", dangerous_ingredients(&sample));
+    #[test]
+    fn solution_without_allergens() {
+        let day = Day21::parse("").unwrap();
+
+        assert_eq!(0, day.part1());
+        assert_eq!("Invalid input: No ingredients can be identified as allergens.", day.part2().unwrap_err().to_string());
     }
-}
\ No newline at end of file
+}
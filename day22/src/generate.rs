@@ -0,0 +1,59 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Params size a generated game of Combat.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// Number of cards each player starts with.
+    pub cards: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { cards: 25 }
+    }
+}
+
+/// Generates starting decks for a game of Combat: a shuffled deck of distinct cards numbered
+/// from 1, dealt evenly between the two players.
+pub fn generate<R: Rng>(rng: &mut R, params: &Params) -> String {
+    let cards = params.cards.max(1);
+
+    let mut deck: Vec<usize> = (1..=cards * 2).collect();
+    deck.shuffle(rng);
+
+    let player = |number: usize, cards: &[usize]| {
+        let cards: String = cards.iter().map(|card| format!("{}\n", card)).collect();
+        format!("Player {}:\n{}", number, cards)
+    };
+
+    format!("{}\n{}", player(1, &deck[..cards]), player(2, &deck[cards..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::Game;
+
+    use super::*;
+
+    #[test]
+    fn generate_valid() {
+        let game = Game::read(generate(&mut StdRng::seed_from_u64(3), &Params { cards: 10 }).as_bytes()).unwrap();
+
+        let mut cards: Vec<i32> = game.player1.iter().chain(game.player2.iter()).cloned().collect();
+        cards.sort_unstable();
+
+        assert_eq!(10, game.player1.len());
+        assert_eq!((1..=20).collect::<Vec<_>>(), cards);
+    }
+
+    #[test]
+    fn generate_seeded() {
+        let params = Params::default();
+
+        assert_eq!(generate(&mut StdRng::seed_from_u64(7), &params), generate(&mut StdRng::seed_from_u64(7), &params));
+    }
+}
@@ -1,9 +1,12 @@
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
+use std::io::BufRead;
+
+use common::{LoadError, ParseError, Sections, Solution};
 
 use crate::Winner::{NoPlayer, Player1, Player2};
 
+pub mod generate;
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum Winner {
     Player1,
@@ -12,36 +15,30 @@ pub enum Winner {
 }
 
 struct PlayerReader<B> where B: BufRead {
-    lines: Lines<B>
+    sections: Sections<B>
 }
 
-impl Iterator for PlayerReader<BufReader<File>> {
-    type Item = Vec<i32>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.lines.next() {
-            Some(Ok(line)) if line.starts_with("Player") => {},
-            _ => panic!("Player section must start with player number."),
-        }
-
-        let mut cards: Vec<i32> = Vec::new();
-        let mut next_line = self.lines.next().unwrap().unwrap();
-        while !next_line.trim().is_empty() {
-            cards.push(next_line.parse().unwrap());
+impl<B: BufRead> PlayerReader<B> {
+    fn new(reader: B) -> PlayerReader<B> {
+        PlayerReader { sections: common::sections(reader) }
+    }
 
-            let maybe_next_line = self.lines.next();
-            if maybe_next_line.is_none() {
-                return Some(cards);
-            }
+    /// Reads the next player's cards, returning an error if the player is missing or invalid.
+    fn read_player(&mut self) -> Result<Vec<i32>, LoadError> {
+        let section = match self.sections.next() {
+            Some(section) => section?,
+            None => return Err(LoadError::invalid("input is missing a player")),
+        };
 
-            next_line = maybe_next_line.unwrap().unwrap();
-        }
+        section.header::<i32>("Player ", ":")?;
 
-        Some(cards)
+        section.body()
+            .map(|(number, line)| line.trim().parse().map_err(|_| ParseError::new(line, 1, "a card").at_line(number).into()))
+            .collect()
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Game {
     player1: Vec<i32>,
     player2: Vec<i32>,
@@ -49,17 +46,19 @@ pub struct Game {
 
 impl Game {
     /// Loads a game of combat from the given file.
-    pub fn load(filename: &str) -> Game {
+    pub fn load(filename: &str) -> Result<Game, LoadError> {
         // File is two sections separated by an empty line of 'Player #:', then numbers.
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
+        Game::read(common::open(filename)?)
+    }
 
-        let mut reader = PlayerReader { lines: f.lines() };
+    /// Reads a game of combat from the given reader.
+    pub fn read<R: BufRead>(reader: R) -> Result<Game, LoadError> {
+        let mut reader = PlayerReader::new(reader);
 
-        Game {
-            player1: reader.next().unwrap(),
-            player2: reader.next().unwrap(),
-        }
+        Ok(Game {
+            player1: reader.read_player()?,
+            player2: reader.read_player()?,
+        })
     }
 
     /// Plays a game of combat, and returns the winning player's score.  Modifies this game.
@@ -95,6 +94,7 @@ impl Game {
     }
 }
 
+#[derive(Clone)]
 pub struct RecursiveGame {
     player1: Vec<i32>,
     player2: Vec<i32>,
@@ -103,18 +103,20 @@ pub struct RecursiveGame {
 
 impl RecursiveGame {
     /// Loads a game of recursive combat from the given file.
-    pub fn load(filename: &str) -> RecursiveGame {
+    pub fn load(filename: &str) -> Result<RecursiveGame, LoadError> {
         // File is two sections separated by an empty line of 'Player #:', then numbers.
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
+        RecursiveGame::read(common::open(filename)?)
+    }
 
-        let mut reader = PlayerReader { lines: f.lines() };
+    /// Reads a game of recursive combat from the given reader.
+    pub fn read<R: BufRead>(reader: R) -> Result<RecursiveGame, LoadError> {
+        let mut reader = PlayerReader::new(reader);
 
-        RecursiveGame {
-            player1: reader.next().unwrap(),
-            player2: reader.next().unwrap(),
+        Ok(RecursiveGame {
+            player1: reader.read_player()?,
+            player2: reader.read_player()?,
             seen: HashSet::new(),
-        }
+        })
     }
 
     /// Plays a game of recursive combat, and returns the winner.  Modifies this game.
@@ -222,33 +224,68 @@ impl RecursiveGame {
 }
 
 /// Computes the score for the given hand.
-fn score(hand: &Vec<i32>) -> i32 {
+fn score(hand: &[i32]) -> i32 {
     let hand_len = hand.len();
 
     hand.iter().enumerate().fold(0, |score, (i, &card)| score + card * (hand_len - i) as i32)
 }
 
+pub struct Day22 {
+    game: Game,
+    recursive_game: RecursiveGame,
+}
+
+impl Solution for Day22 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day22 {
+            game: Game::read(input.as_bytes())?,
+            recursive_game: RecursiveGame::read(input.as_bytes())?,
+        })
+    }
+
+    fn part1(&self) -> i32 {
+        self.game.clone().play()
+    }
+
+    fn part2(&self) -> i32 {
+        let mut game = self.recursive_game.clone();
+        game.play();
+
+        game.score()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn load_sample() {
-        let game = Game::load("sample.txt");
+        let game = Game::load("sample.txt").unwrap();
 
         assert_eq!(vec![9, 2, 6, 3, 1], game.player1);
         assert_eq!(vec![5, 8, 4, 7, 10], game.player2);
     }
 
+    #[test]
+    fn read_missing_player() {
+        let result = Game::read("Player 1:\n9\n2\n".as_bytes());
+
+        assert!(matches!(result, Err(LoadError::Invalid(_))));
+    }
+
     #[test]
     fn play_sample() {
-        let mut game = Game::load("sample.txt");
+        let mut game = Game::load("sample.txt").unwrap();
         assert_eq!(306, game.play());
     }
 
     #[test]
     fn play_round_sample() {
-        let mut game = Game::load("sample.txt");
+        let mut game = Game::load("sample.txt").unwrap();
 
         let round2_game = Game {
             player1: vec![2, 6, 3, 1, 9, 5],
@@ -277,21 +314,29 @@ mod tests {
 
     #[test]
     fn recursive_state_sample() {
-        let game = RecursiveGame::load("sample.txt");
+        let game = RecursiveGame::load("sample.txt").unwrap();
 
         assert_eq!("9 2 6 3 1 | 5 8 4 7 10", game.state());
     }
 
     #[test]
     fn play_recursive_sample() {
-        let mut game = RecursiveGame::load("sample.txt");
+        let mut game = RecursiveGame::load("sample.txt").unwrap();
         assert_eq!(Player2, game.play());
         assert_eq!(291, game.score());
     }
 
     #[test]
     fn play_recursive_infinite_sample() {
-        let mut game = RecursiveGame::load("sample_infinite.txt");
+        let mut game = RecursiveGame::load("sample_infinite.txt").unwrap();
         assert_eq!(Player1, game.play());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solution_sample() {
+        let day = Day22::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(306, day.part1());
+        assert_eq!(291, day.part2());
+    }
+}
@@ -1,3 +1,5 @@
+use common::{LoadError, ParseError, Solution};
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct Cups {
     /// curr is the current cup's number.
@@ -32,7 +34,7 @@ impl Cups {
 
         // Next maps a cup (at an index) to the next cup (value)
         let max_cup = *cups.iter().max().unwrap();
-        let mut next = vec![0; max_cup as usize + 1];
+        let mut next = vec![0; max_cup + 1];
         for i in 0..cups.len() - 1 {
             next[cups[i]] = cups[i + 1];
         }
@@ -140,6 +142,31 @@ impl Cups {
     }
 }
 
+pub struct Day23 {
+    labels: i32,
+}
+
+impl Solution for Day23 {
+    type Part1 = i32;
+    type Part2 = i64;
+
+    /// Parses the cup labels, which are a single number.
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        let labels = input.trim().parse()
+            .map_err(|_| ParseError::new(input.trim(), 1, "cup labels like '389125467'").at_line(1))?;
+
+        Ok(Day23 { labels })
+    }
+
+    fn part1(&self) -> i32 {
+        Cups::from(self.labels).shift_times(100).code_after(1)
+    }
+
+    fn part2(&self) -> i64 {
+        Cups::million_from(self.labels).shift_times(10_000_000).product_after(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +231,12 @@ mod tests {
         cups.shift_times(10_000_000);
         assert_eq!(149245887792, cups.product_after(1));
     }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day23::parse("389125467\n").unwrap();
+
+        assert_eq!(67384529, day.part1());
+        assert_eq!(149245887792, day.part2());
+    }
 }
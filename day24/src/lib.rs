@@ -1,13 +1,10 @@
-use std::collections::HashSet;
 use std::ops::Add;
 use std::str::FromStr;
 
 use crate::Direction::{E, NE, NW, SE, SW, W};
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-
-#[derive(Debug, Eq, PartialEq)]
-pub struct ParseErr {}
+use std::io::BufRead;
+use common::{LoadError, ParseError, Solution};
+use grid::Neighborhood;
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum Direction { E, SE, SW, W, NW, NE }
@@ -18,7 +15,7 @@ pub struct Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = ParseErr;
+    type Err = ParseError;
 
     /// Parses a string into an instruction.  An instruction is made up of
     /// directions (se, sw, ne, nw, e, and w) without any separators.
@@ -32,18 +29,18 @@ impl FromStr for Instruction {
             let dir = match chars[i] {
                 's' => {
                     i += 2;
-                    match chars[i - 1] {
-                        'e' => SE,
-                        'w' => SW,
-                        _ => return Err(ParseErr {}),
+                    match chars.get(i - 1) {
+                        Some('e') => SE,
+                        Some('w') => SW,
+                        _ => return Err(ParseError::new(s, i, "'e' or 'w'")),
                     }
                 }
                 'n' => {
                     i += 2;
-                    match chars[i - 1] {
-                        'e' => NE,
-                        'w' => NW,
-                        _ => return Err(ParseErr {}),
+                    match chars.get(i - 1) {
+                        Some('e') => NE,
+                        Some('w') => NW,
+                        _ => return Err(ParseError::new(s, i, "'e' or 'w'")),
                     }
                 }
                 'e' => {
@@ -54,7 +51,7 @@ impl FromStr for Instruction {
                     i += 1;
                     W
                 }
-                _ => return Err(ParseErr {}),
+                _ => return Err(ParseError::new(s, i + 1, "a direction (e, se, sw, w, nw, or ne)")),
             };
 
             directions.push(dir);
@@ -65,11 +62,13 @@ impl FromStr for Instruction {
 }
 
 /// Loads a list of instructions from the given file.
-pub fn load_instructions(filename: &str) -> Vec<Instruction> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+pub fn load_instructions(filename: &str) -> Result<Vec<Instruction>, LoadError> {
+    read_instructions(common::open(filename)?)
+}
 
-    f.lines().map(|line| line.unwrap().parse().unwrap()).collect()
+/// Reads a list of instructions from the given reader, one per line.
+pub fn read_instructions<R: BufRead>(reader: R) -> Result<Vec<Instruction>, LoadError> {
+    common::parse_lines(reader)
 }
 
 /// Tile uses cube coordinates to identify a tile - see: https://www.redblobgames.com/grids/hexagons/
@@ -86,9 +85,10 @@ impl Tile {
         Tile { x: 0, y: 0, z: 0 }
     }
 
-    /// Returns the tiles around this tile.
-    fn neighbors(&self) -> Vec<Tile> {
-        [E, SE, SW, W, NW, NE].iter().map(|dir| *self + *dir).collect()
+    /// Returns this tile's axial coordinates as a (row, col) offset from the origin, where
+    /// rows run from northwest to southeast.
+    fn axial(&self) -> (isize, isize) {
+        (self.z as isize, self.x as isize)
     }
 }
 
@@ -133,15 +133,20 @@ impl Add<Direction> for Tile {
     }
 }
 
+/// Grid is the hexagonal floor, stored in axial coordinates so it fits in a square grid.  The
+/// floor grows as tiles further from the origin are flipped.
 #[derive(Debug, Eq, PartialEq)]
 pub struct Grid {
-    black_tiles: HashSet<Tile>
+    /// Whether each tile is black.
+    black: grid::Grid<bool>,
+    /// The (row, col) of the tile at 0,0,0 in black.
+    origin: (isize, isize),
 }
 
 impl Grid {
     /// Returns a new grid.
-    pub fn new(instructions: &Vec<Instruction>) -> Grid {
-        let mut grid = Grid { black_tiles: HashSet::new() };
+    pub fn new(instructions: &[Instruction]) -> Grid {
+        let mut grid = Grid { black: grid::Grid::new(1, 1, false), origin: (0, 0) };
 
         grid.run_all(instructions);
 
@@ -149,7 +154,7 @@ impl Grid {
     }
 
     /// Runs all of the instructions, returning the modified grid.
-    fn run_all(&mut self, instructions: &Vec<Instruction>) -> &Self {
+    fn run_all(&mut self, instructions: &[Instruction]) -> &Self {
         for instruction in instructions {
             self.run(instruction);
         }
@@ -170,11 +175,33 @@ impl Grid {
 
     /// Flips the given tile - black becomes white, white becomes black.
     fn flip(&mut self, tile: Tile) {
-        if self.black_tiles.contains(&tile) {
-            self.black_tiles.remove(&tile);
-        } else {
-            self.black_tiles.insert(tile);
+        let (row, col) = tile.axial();
+        let (row, col) = (self.origin.0 + row, self.origin.1 + col);
+
+        // Grow the floor so the tile is on it.
+        let top = (-row).max(0) as usize;
+        let left = (-col).max(0) as usize;
+        let bottom = (row + 1 - self.black.height() as isize).max(0) as usize;
+        let right = (col + 1 - self.black.width() as isize).max(0) as usize;
+        self.grow(top, left, bottom, right);
+
+        let position = ((row + top as isize) as usize, (col + left as isize) as usize);
+        self.black[position] = !self.black[position];
+    }
+
+    /// Adds white tiles to each side of the floor.
+    fn grow(&mut self, top: usize, left: usize, bottom: usize, right: usize) {
+        if top + left + bottom + right == 0 {
+            return;
+        }
+
+        let mut black = grid::Grid::new(self.black.width() + left + right, self.black.height() + top + bottom, false);
+        for (row, col) in self.black.positions() {
+            black[(row + top, col + left)] = self.black[(row, col)];
         }
+
+        self.black = black;
+        self.origin = (self.origin.0 + top as isize, self.origin.1 + left as isize);
     }
 
     /// Flips tiles according to rules, returning this modified grid.
@@ -183,31 +210,26 @@ impl Grid {
         // - Black tiles with zero or >2 adjacent black tiles are flipped to white.
         // - White tiles with 2 adjacent black tiles are flipped to black.
 
-        // Consider the black tiles and their neighbors.
-        let consider: HashSet<Tile> = self.black_tiles.iter().cloned()
-            .chain(self.black_tiles.iter().flat_map(|tile| tile.neighbors()))
-            .collect();
+        // Tiles just past the edge of the floor can turn black.
+        self.grow(1, 1, 1, 1);
 
-        // Figure out which ones to flip based on the rules.
-        let flip: Vec<Tile> = consider.into_iter().filter(|tile| {
-            let black_neighbors = tile.neighbors().into_iter()
-                .filter(|neighbor| self.black_tiles.contains(neighbor))
+        let mut black = self.black.clone();
+        for (row, col) in self.black.positions() {
+            let black_neighbors = self.black.neighbors(row, col, Neighborhood::Hex).into_iter()
+                .filter(|&neighbor| self.black[neighbor])
                 .count();
 
-            if self.black_tiles.contains(tile) {
-                // Currently a black tile - flip if 0 or >2 neighbors are black tiles.
-                black_neighbors == 0 || black_neighbors > 2
+            black[(row, col)] = if self.black[(row, col)] {
+                // Currently a black tile - stays black if 1 or 2 neighbors are black tiles.
+                black_neighbors == 1 || black_neighbors == 2
             } else {
                 // Currently a white tile - flip if exactly 2 neighbors are black tiles.
                 black_neighbors == 2
-            }
-        }).collect();
-
-        // Flip the tiles.
-        for tile in flip {
-            self.flip(tile);
+            };
         }
 
+        self.black = black;
+
         self
     }
 
@@ -222,7 +244,28 @@ impl Grid {
 
     /// Returns the number of black tiles on this grid.
     pub fn num_black(&self) -> usize {
-        self.black_tiles.len()
+        self.black.iter().filter(|&&black| black).count()
+    }
+}
+
+pub struct Day24 {
+    instructions: Vec<Instruction>,
+}
+
+impl Solution for Day24 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day24 { instructions: read_instructions(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        Grid::new(&self.instructions).num_black()
+    }
+
+    fn part2(&self) -> usize {
+        Grid::new(&self.instructions).tick_times(100).num_black()
     }
 }
 
@@ -241,6 +284,9 @@ mod tests {
 
         assert_eq!(Ok(expected), instruction);
         assert_eq!(Instruction { directions: vec![E, SE, NE, E] }, "esenee".parse().unwrap());
+
+        assert_eq!(Err(ParseError::new("esen", 5, "'e' or 'w'")), "esen".parse::<Instruction>());
+        assert_eq!(Err(ParseError::new("esex", 4, "a direction (e, se, sw, w, nw, or ne)")), "esex".parse::<Instruction>());
     }
 
     #[test]
@@ -254,27 +300,34 @@ mod tests {
         assert_eq!(Tile { x: 1, y: 0, z: -1 }, Tile::origin() + NE);
     }
 
+    /// Returns whether the given tile is black.
+    fn is_black(grid: &Grid, tile: Tile) -> bool {
+        let (row, col) = tile.axial();
+
+        grid.black.get(grid.origin.0 + row, grid.origin.1 + col) == Some(&true)
+    }
+
     #[test]
     fn run_instruction() {
         let instructions = vec![];
         let mut grid = Grid::new(&instructions);
 
         grid.run(&"esenee".parse().unwrap());
-        assert!(grid.black_tiles.contains(&Tile { x: 3, y: -3, z: 0 }));
+        assert!(is_black(&grid, Tile { x: 3, y: -3, z: 0 }));
 
         grid.run(&"sesenwnenenewseeswwswswwnenewsewsw".parse().unwrap());
-        assert!(grid.black_tiles.contains(&Tile { x: -3, y: 1, z: 2 }));
+        assert!(is_black(&grid, Tile { x: -3, y: 1, z: 2 }));
     }
 
     #[test]
     fn run_sample() {
-        let instructions = load_instructions("sample.txt");
+        let instructions = load_instructions("sample.txt").unwrap();
         assert_eq!(10, Grid::new(&instructions).num_black());
     }
 
     #[test]
     fn tick_sample() {
-        let instructions = load_instructions("sample.txt");
+        let instructions = load_instructions("sample.txt").unwrap();
 
         let mut first_grid = Grid::new(&instructions);
         assert_eq!(2208, first_grid.tick_times(100).num_black());
@@ -302,4 +355,12 @@ mod tests {
         assert_eq!(1844, grid.tick_times(10).num_black());
         assert_eq!(2208, grid.tick_times(10).num_black());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solution_sample() {
+        let day = Day24::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(10, day.part1());
+        assert_eq!(2208, day.part2());
+    }
+}
@@ -1,3 +1,5 @@
+use common::{LoadError, Solution};
+
 /// Performs a handshake between the card and the door, and returns the resulting encryption key.
 pub fn handshake(card_pk: i64, door_pk: i64) -> i64 {
     let card_loop_size = find_loop(7, card_pk);
@@ -38,6 +40,35 @@ fn find_loop(subject_num: i64, target: i64) -> i64 {
     loop_size
 }
 
+pub struct Day25 {
+    card_pk: i64,
+    door_pk: i64,
+}
+
+impl Solution for Day25 {
+    type Part1 = i64;
+    type Part2 = &'static str;
+
+    /// Parses the card's public key, then the door's public key, one per line.
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        let keys: Vec<i64> = common::parse_lines(input.as_bytes())?;
+
+        match keys.as_slice() {
+            &[card_pk, door_pk] => Ok(Day25 { card_pk, door_pk }),
+            _ => Err(LoadError::invalid(&format!("expected 2 public keys, found {}", keys.len()))),
+        }
+    }
+
+    fn part1(&self) -> i64 {
+        handshake(self.card_pk, self.door_pk)
+    }
+
+    /// Day 25 only has one puzzle - the second star is free once every other day is solved.
+    fn part2(&self) -> &'static str {
+        "Merry Christmas!"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +92,9 @@ mod tests {
     fn handshake_sample() {
         assert_eq!(14897079, handshake(5764801, 17807724));
     }
+
+    #[test]
+    fn solution_sample() {
+        assert_eq!(14897079, Day25::parse("5764801\n17807724\n").unwrap().part1());
+    }
 }
@@ -0,0 +1,173 @@
+use std::str::FromStr;
+use crate::Square::{Empty, Tree};
+use std::io::BufRead;
+use common::{LoadError, ParseError, Solution};
+use grid::Edges;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Square {
+    Empty, Tree
+}
+
+impl FromStr for Square {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "." => Ok(Empty),
+            "#" => Ok(Tree),
+            _ => Err(ParseError::new(s, 1, "'.' or '#'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod square_tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(".".parse(), Ok(Empty));
+        assert_eq!("#".parse(), Ok(Tree));
+        assert_eq!("...".parse::<Square>(), Err(ParseError::new("...", 1, "'.' or '#'")));
+    }
+}
+
+pub struct Grid {
+    squares: grid::Grid<Square>,
+}
+
+impl Grid {
+
+    /// Loads a grid from the given file, returning an error if the grid is invalid or the file doesn't exist.
+    pub fn load(filename: &str) -> Result<Grid, LoadError> {
+        Grid::read(common::open(filename)?)
+    }
+
+    /// Reads a grid from the given reader, returning an error if the grid is invalid.
+    pub fn read<R: BufRead>(reader: R) -> Result<Grid, LoadError> {
+        let squares = grid::Grid::read(reader, "'.' or '#'", |c| c.to_string().parse::<Square>().ok())?;
+
+        if squares.height() == 0 {
+            return Err(LoadError::invalid("grid has no rows"));
+        }
+
+        // The grid is infinitely wide - the pattern repeats to the right.
+        Ok(Grid { squares: squares.with_edges(Edges::WrapHorizontal) })
+    }
+
+    /// Returns the value of the square at the given position.  The grid is infinitely wide, so
+    /// x may be greater than the grid's width.
+    fn get(&self, x: usize, y: usize) -> Square {
+        *self.squares.get(y as isize, x as isize).unwrap()
+    }
+
+    /// Returns the number of trees encountered starting at the top left corner of this grid
+    /// and sliding right and down.
+    pub fn count_trees(&self, right: usize, down: usize) -> usize {
+        let mut x = 0;
+        let mut y = 0;
+        let mut count = 0;
+
+        while y < self.squares.height() {
+            if self.get(x, y) == Tree {
+                count += 1;
+            }
+
+            x += right;
+            y += down;
+        }
+
+        count
+    }
+
+    /// Returns the product of the number of trees encountered across several slopes.
+    pub fn count_trees_product(&self, slopes: &[(usize, usize)]) -> usize {
+        slopes.iter()
+            .map(|(right, down)| self.count_trees(*right, *down))
+            .product()
+    }
+}
+
+pub struct Day3 {
+    grid: Grid,
+}
+
+impl Solution for Day3 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day3 { grid: Grid::read(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        self.grid.count_trees(3, 1)
+    }
+
+    /// In part 2, find the product of the number of trees encountered in several slopes.
+    fn part2(&self) -> usize {
+        self.grid.count_trees_product(&[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)])
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::*;
+
+    #[test]
+    fn load() {
+        let grid = Grid::load("sample.txt").unwrap();
+
+        assert_eq!(11, grid.squares.width());
+        assert_eq!(11, grid.squares.height());
+
+        assert_eq!(vec![Empty, Empty, Tree, Tree, Empty, Empty, Empty, Empty, Empty, Empty, Empty], grid.squares.rows().next().unwrap());
+    }
+
+    #[test]
+    fn read_invalid_square() {
+        match Grid::read("..#\n.x.\n".as_bytes()) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new(".x.", 2, "'.' or '#'").at_line(2), e),
+            _ => panic!("Expected a parse error."),
+        }
+    }
+
+    #[test]
+    fn read_short_row() {
+        match Grid::read("..#\n.#\n".as_bytes()) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new(".#", 3, "3 squares").at_line(2), e),
+            _ => panic!("Expected a parse error."),
+        }
+    }
+
+    #[test]
+    fn get() {
+        let grid = Grid::load("sample.txt").unwrap();
+
+        assert_eq!(Empty, grid.get(0, 0));
+        assert_eq!(Tree, grid.get(11, 1));
+    }
+
+    #[test]
+    fn count_trees() {
+        let grid = Grid::load("sample.txt").unwrap();
+
+        assert_eq!(7, grid.count_trees(3, 1));
+    }
+
+    #[test]
+    fn count_trees_product() {
+        let grid = Grid::load("sample.txt").unwrap();
+
+        assert_eq!(336, grid.count_trees_product(&[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]));
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day3::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(7, day.part1());
+        assert_eq!(336, day.part2());
+    }
+}
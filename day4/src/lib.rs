@@ -0,0 +1,327 @@
+#[macro_use]
+extern crate lazy_static;
+extern crate regex;
+
+use std::str::FromStr;
+use crate::Field::{BirthYear, IssueYear, ExpirationYear, Height, HairColor, EyeColor, PassportID, CountryID};
+use std::io::BufRead;
+use std::collections::HashSet;
+use regex::Regex;
+use common::{LoadError, ParseError, Solution};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Field {
+    BirthYear(String),
+    IssueYear(String),
+    ExpirationYear(String),
+    Height(String),
+    HairColor(String),
+    EyeColor(String),
+    PassportID(String),
+    CountryID(String),
+}
+
+impl Field {
+    fn code(&self) -> &str {
+        match self {
+            BirthYear(_) => "byr",
+            IssueYear(_) => "iyr",
+            ExpirationYear(_) => "eyr",
+            Height(_) => "hgt",
+            HairColor(_) => "hcl",
+            EyeColor(_) => "ecl",
+            PassportID(_) => "pid",
+            CountryID(_) => "cid",
+        }
+    }
+
+    fn validate(&self) -> bool {
+        lazy_static! {
+            static ref YEAR_RE: Regex = Regex::new(r"^(\d{4})$").unwrap();
+            static ref CM_RE: Regex = Regex::new(r"^(\d+)cm$").unwrap();
+            static ref IN_RE: Regex = Regex::new(r"^(\d+)in$").unwrap();
+            static ref HAIR_RE: Regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
+            static ref EYE_COLORS: HashSet<String> = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]
+            .iter().map(|s| s.to_string()).collect();
+            static ref PASSPORT_ID_RE: Regex = Regex::new(r"^\d{9}$").unwrap();
+        }
+
+        match self {
+            // four digits; at least 1920 and at most 2002.
+            BirthYear(s) => {
+                YEAR_RE.captures(s).map(|captures| {
+                    let year: i32 = captures[1].parse().unwrap();
+                    (1920..=2002).contains(&year)
+                }).unwrap_or(false)
+            }
+
+            // four digits; at least 2010 and at most 2020.
+            IssueYear(s) => {
+                YEAR_RE.captures(s).map(|captures| {
+                    let year: i32 = captures[1].parse().unwrap();
+                    (2010..=2020).contains(&year)
+                }).unwrap_or(false)
+            }
+
+            // four digits; at least 2020 and at most 2030.
+            ExpirationYear(s) => {
+                YEAR_RE.captures(s).map(|captures| {
+                    let year: i32 = captures[1].parse().unwrap();
+                    (2020..=2030).contains(&year)
+                }).unwrap_or(false)
+            }
+
+            // a number followed by either cm or in:
+            // If cm, the number must be at least 150 and at most 193.
+            // If in, the number must be at least 59 and at most 76.
+            Height(s) => {
+                let cm = CM_RE.captures(s).map(|captures| {
+                    let cm: i32 = captures[1].parse().unwrap();
+                    (150..=193).contains(&cm)
+                });
+
+                if let Some(cm) = cm {
+                    return cm;
+                }
+
+                IN_RE.captures(s).map(|captures| {
+                    let num: i32 = captures[1].parse().unwrap();
+                    (59..=76).contains(&num)
+                }).unwrap_or(false)
+            }
+
+            // a # followed by exactly six characters 0-9 or a-f
+            HairColor(s) => HAIR_RE.is_match(s),
+
+            // exactly one of: amb blu brn gry grn hzl oth
+            EyeColor(s) => EYE_COLORS.contains(s.as_str()),
+
+            // a nine-digit number, including leading zeroes
+            PassportID(s) => PASSPORT_ID_RE.is_match(s),
+
+            // ignored, missing or not.
+            CountryID(_) => true,
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = ParseError;
+
+    fn from_str<'a>(str: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = str.split(":").collect();
+        if parts.len() != 2 {
+            let column = parts[0].len() + if parts.len() == 1 { 1 } else { parts[1].len() + 2 };
+            let expected = if parts.len() == 1 { "':' followed by a value" } else { "end of field" };
+
+            return Err(ParseError::new(str, column, expected));
+        }
+
+        match parts[0] {
+            "byr" => Ok(BirthYear(parts[1].to_owned())),
+            "iyr" => Ok(IssueYear(parts[1].to_owned())),
+            "eyr" => Ok(ExpirationYear(parts[1].to_owned())),
+            "hgt" => Ok(Height(parts[1].to_owned())),
+            "hcl" => Ok(HairColor(parts[1].to_owned())),
+            "ecl" => Ok(EyeColor(parts[1].to_owned())),
+            "pid" => Ok(PassportID(parts[1].to_owned())),
+            "cid" => Ok(CountryID(parts[1].to_owned())),
+            _ => Err(ParseError::new(str, 1, "a field code (byr, iyr, eyr, hgt, hcl, ecl, pid, or cid)")),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Passport {
+    fields: Vec<Field>
+}
+
+impl Passport {
+    /// Returns whether this passport is valid (has all of the required fields).
+    fn validate_has_fields(&self) -> bool {
+        // cid is optional, all other fields are required.
+        let expected: HashSet<&str> = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
+            .iter().cloned().collect();
+
+        let actual: HashSet<&str> = self.fields.iter()
+            .map(|f| f.code())
+            .collect();
+
+        let missing: HashSet<_> = expected.difference(&actual).collect();
+
+        missing.is_empty()
+    }
+
+    /// Returns whether this passport is valid (has the required fields, and valid values for fields).
+    fn validate_field_values(&self) -> bool {
+        self.fields.iter().all(|field| field.validate())
+    }
+}
+
+/// Loads passports from the given file.
+pub fn load_passports(filename: &str) -> Result<Vec<Passport>, LoadError> {
+    read_passports(common::open(filename)?)
+}
+
+/// Reads passports from the given reader.
+pub fn read_passports<R: BufRead>(reader: R) -> Result<Vec<Passport>, LoadError> {
+    let mut passports = Vec::new();
+
+    // Passports are a series of fields separated by an empty line.
+    for section in common::sections(reader) {
+        let mut fields = Vec::new();
+
+        for (number, line) in section?.lines() {
+            let mut column = 0;
+            for f in line.split(" ") {
+                fields.push(f.parse::<Field>().map_err(|e| e.offset(column, line).at_line(number))?);
+
+                column += f.len() + 1;
+            }
+        }
+
+        passports.push(Passport { fields });
+    }
+
+    Ok(passports)
+}
+
+/// Counts the number of valid passports in the list.
+pub fn count_valid(passports: &[Passport]) -> usize {
+    passports.iter()
+        .filter(|p| p.validate_has_fields())
+        .count()
+}
+
+/// Counts the number of valid passports in the list.
+pub fn count_valid_field_values(passports: &[Passport]) -> usize {
+    passports.iter()
+        .filter(|p| p.validate_has_fields())
+        .filter(|p| p.validate_field_values())
+        .count()
+}
+
+pub struct Day4 {
+    passports: Vec<Passport>,
+}
+
+impl Solution for Day4 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day4 { passports: read_passports(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        count_valid(&self.passports)
+    }
+
+    fn part2(&self) -> usize {
+        count_valid_field_values(&self.passports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Field::{EyeColor, BirthYear, PassportID, ExpirationYear, HairColor, IssueYear, CountryID, Height};
+
+    #[test]
+    fn parse_field() {
+        assert_eq!(Ok(EyeColor("gry".to_owned())), "ecl:gry".parse());
+        assert_eq!(Ok(PassportID("860033327".to_owned())), "pid:860033327".parse());
+        assert_eq!(Ok(ExpirationYear("2020".to_owned())), "eyr:2020".parse());
+        assert_eq!(Ok(HairColor("#fffffd".to_owned())), "hcl:#fffffd".parse());
+        assert_eq!(Ok(BirthYear("1937".to_owned())), "byr:1937".parse());
+        assert_eq!(Ok(IssueYear("2017".to_owned())), "iyr:2017".parse());
+        assert_eq!(Ok(CountryID("147".to_owned())), "cid:147".parse());
+        assert_eq!(Ok(Height("183cm".to_owned())), "hgt:183cm".parse());
+
+        assert_eq!(Err(ParseError::new("invalid", 8, "':' followed by a value")), "invalid".parse::<Field>());
+        assert_eq!(Err(ParseError::new("ecl:gry:", 8, "end of field")), "ecl:gry:".parse::<Field>());
+        assert_eq!(Err(ParseError::new("abc:123", 1, "a field code (byr, iyr, eyr, hgt, hcl, ecl, pid, or cid)")),
+                   "abc:123".parse::<Field>());
+    }
+
+    #[test]
+    fn read_passports_invalid_field() {
+        match read_passports("ecl:gry pid:860033327\neyr:2020 hcl\n".as_bytes()) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("eyr:2020 hcl", 13, "':' followed by a value").at_line(2), e),
+            _ => panic!("Expected a parse error."),
+        }
+    }
+
+    #[test]
+    fn load_passports_sample() {
+        let passports = load_passports("sample.txt").unwrap();
+
+        assert_eq!(4, passports.len());
+
+        let expected = Passport {
+            fields: vec![
+                EyeColor("gry".to_owned()),
+                PassportID("860033327".to_owned()),
+                ExpirationYear("2020".to_owned()),
+                HairColor("#fffffd".to_owned()),
+                BirthYear("1937".to_owned()),
+                IssueYear("2017".to_owned()),
+                CountryID("147".to_owned()),
+                Height("183cm".to_owned()),
+            ]
+        };
+
+        assert_eq!(expected, passports[0]);
+    }
+
+    #[test]
+    fn count_valid_sample() {
+        let passports = load_passports("sample.txt").unwrap();
+        assert_eq!(2, count_valid(&passports));
+    }
+
+    #[test]
+    fn validate_field() {
+        assert!("byr:2002".parse::<Field>().unwrap().validate());
+        assert!(!"byr:2003".parse::<Field>().unwrap().validate());
+
+        assert!("hgt:60in".parse::<Field>().unwrap().validate());
+        assert!("hgt:190cm".parse::<Field>().unwrap().validate());
+        assert!(!"hgt:190in".parse::<Field>().unwrap().validate());
+        assert!(!"hgt:190".parse::<Field>().unwrap().validate());
+
+        assert!("hcl:#123abc".parse::<Field>().unwrap().validate());
+        assert!(!"hcl:#123abz".parse::<Field>().unwrap().validate());
+        assert!(!"hcl:123abc".parse::<Field>().unwrap().validate());
+
+        assert!("ecl:brn".parse::<Field>().unwrap().validate());
+        assert!(!"ecl:wat".parse::<Field>().unwrap().validate());
+
+        assert!("pid:000000001".parse::<Field>().unwrap().validate());
+        assert!(!"pid:0123456789".parse::<Field>().unwrap().validate());
+    }
+
+    #[test]
+    fn count_valid_fields() {
+        let passports = load_passports("sample_valid.txt").unwrap();
+
+        assert_eq!(4, passports.len());
+        assert_eq!(4, count_valid_field_values(&passports));
+    }
+
+    #[test]
+    fn count_invalid_fields() {
+        let passports = load_passports("sample_invalid.txt").unwrap();
+
+        assert_eq!(4, passports.len());
+        assert_eq!(0, count_valid_field_values(&passports));
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day4::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(2, day.part1());
+        assert_eq!(2, day.part2());
+    }
+}
@@ -0,0 +1,184 @@
+use std::io::BufRead;
+use common::{LoadError, ParseError, Solution};
+
+/// Returns the seat id described by the string.  Seats are arranged using binary space
+/// partitioning - the first 7 characters are F (front) or B (back) to find the row, and the
+/// remaining 3 characters are R (right) or L (left) for the column.
+pub fn seat_id(s: &str) -> usize {
+    // Row: first 6 characters are front or back and narrow down the range.
+    let mut row = 0..127;
+
+    for r in 0..6 {
+        row = match &s[r..r+1] {
+            "F" => row.start .. ((row.start + row.end) as f32 / 2.0).floor() as usize,
+            "B" => ((row.start + row.end) as f32 / 2.0).ceil() as usize .. row.end,
+            _ => panic!("Unknown row code"),
+        }
+    }
+
+    // Final F or R takes the upper or lower bound to pick out the final row.
+    let final_row = match &s[6..7] {
+        "F" => row.start,
+        "B" => row.end,
+        _ => panic!("Unknown row code"),
+    };
+
+    // Col
+    let mut col = 0..7;
+
+    for c in 0..2 {
+        col = match &s[7 + c..8 + c] {
+            "R" => ((col.start + col.end) as f32 / 2.0).ceil() as usize .. col.end,
+            "L" => col.start .. ((col.start + col.end) as f32 / 2.0).floor() as usize,
+            _ => panic!("Unknown column code"),
+        }
+    }
+
+    // Final R or L picks out the column from the range.
+    let final_col = match &s[9..10] {
+        "R" => col.end,
+        "L" => col.start,
+        _ => panic!("Unknown column code"),
+    };
+
+    final_row * 8 + final_col
+}
+
+/// Loads boarding passes from the given file.
+pub fn load_passes(filename: &str) -> Result<Vec<String>, LoadError> {
+    read_passes(common::open(filename)?)
+}
+
+/// Reads boarding passes from the given reader, one per line.
+pub fn read_passes<R: BufRead>(reader: R) -> Result<Vec<String>, LoadError> {
+    let passes = common::read_lines(reader)?;
+
+    for (i, pass) in passes.iter().enumerate() {
+        check_pass(pass).map_err(|e| e.at_line(i + 1))?;
+    }
+
+    Ok(passes)
+}
+
+/// Checks that the given string is a boarding pass - 7 row codes followed by 3 column codes.
+fn check_pass(s: &str) -> Result<(), ParseError> {
+    // Expected code at each position of the pass, with one extra position for the end.
+    let expected = |i: usize| match i {
+        0..=6 => "'F' or 'B'",
+        7..=9 => "'L' or 'R'",
+        _ => "end of boarding pass",
+    };
+
+    let chars: Vec<char> = s.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let valid = match i {
+            0..=6 => c == 'F' || c == 'B',
+            7..=9 => c == 'L' || c == 'R',
+            _ => false,
+        };
+
+        if !valid {
+            return Err(ParseError::new(s, i + 1, expected(i)));
+        }
+    }
+
+    if chars.len() < 10 {
+        return Err(ParseError::new(s, chars.len() + 1, expected(chars.len())));
+    }
+
+    Ok(())
+}
+
+/// Returns the highest seat id on any of the given boarding passes, or an error if there
+/// aren't any passes.
+pub fn highest_seat_id(passes: &[String]) -> Result<usize, LoadError> {
+    passes.iter()
+        .map(|pass| seat_id(pass.as_str()))
+        .max()
+        .ok_or_else(|| LoadError::invalid("There aren't any boarding passes."))
+}
+
+/// Returns the id of the missing seat - the one seat between the lowest and highest ids that
+/// isn't on any of the given boarding passes.  Returns an error if no seat is missing.
+pub fn missing_seat_id(passes: &[String]) -> Result<usize, LoadError> {
+    let seat_ids: Vec<usize> = passes.iter().map(|pass| seat_id(pass.as_str())).collect();
+
+    let highest_id = highest_seat_id(passes)?;
+    let lowest_id = *seat_ids.iter().min().unwrap_or(&highest_id);
+
+    (lowest_id .. highest_id)
+        .find(|seat_id| !seat_ids.contains(seat_id))
+        .ok_or_else(|| LoadError::invalid("No seat is missing between the lowest and highest seat ids."))
+}
+
+pub struct Day5 {
+    passes: Vec<String>,
+}
+
+impl Solution for Day5 {
+    type Part1 = Result<usize, LoadError>;
+    type Part2 = Result<usize, LoadError>;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day5 { passes: read_passes(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> Result<usize, LoadError> {
+        highest_seat_id(&self.passes)
+    }
+
+    fn part2(&self) -> Result<usize, LoadError> {
+        missing_seat_id(&self.passes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seat_id() {
+        assert_eq!(357, seat_id("FBFBBFFRLR"));
+        assert_eq!(567, seat_id("BFFFBBFRRR"));
+        assert_eq!(119, seat_id("FFFBBBFRRR"));
+        assert_eq!(820, seat_id("BBFFBBFRLL"));
+    }
+
+    #[test]
+    fn test_missing_seat_id() {
+        let passes = vec!["FBFBBFFRLL".to_owned(), "FBFBBFFRRL".to_owned(), "FBFBBFFRRR".to_owned()];
+
+        assert_eq!(359, highest_seat_id(&passes).unwrap());
+        assert_eq!(357, missing_seat_id(&passes).unwrap());
+    }
+
+    #[test]
+    fn test_read_passes_invalid() {
+        match read_passes("FBFBBFFRLL\nFBFBBFXRRL\n".as_bytes()) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("FBFBBFXRRL", 7, "'F' or 'B'").at_line(2), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        assert_eq!(Err(ParseError::new("FBFBBFFRL", 10, "'L' or 'R'")), check_pass("FBFBBFFRL"));
+        assert_eq!(Err(ParseError::new("FBFBBFFRLLR", 11, "end of boarding pass")), check_pass("FBFBBFFRLLR"));
+    }
+
+    #[test]
+    fn solution_passes() {
+        let day = Day5::parse("FBFBBFFRLL\nFBFBBFFRRL\nFBFBBFFRRR\n").unwrap();
+
+        assert_eq!(359, day.part1().unwrap());
+        assert_eq!(357, day.part2().unwrap());
+    }
+
+    #[test]
+    fn solution_without_passes() {
+        let day = Day5::parse("").unwrap();
+
+        assert_eq!("Invalid input: There aren't any boarding passes.", day.part1().unwrap_err().to_string());
+        assert_eq!("Invalid input: There aren't any boarding passes.", day.part2().unwrap_err().to_string());
+
+        let day = Day5::parse("FBFBBFFRLL\n").unwrap();
+        assert_eq!("Invalid input: No seat is missing between the lowest and highest seat ids.", day.part2().unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::BufRead;
+use itertools::Itertools;
+use std::collections::HashSet;
+use common::{LoadError, Solution};
+
+/// Parses the given file into groups of yes answers.
+pub fn parse(filename: &str) -> Result<Vec<Vec<String>>, LoadError> {
+    read(common::open(filename)?)
+}
+
+/// Reads groups of yes answers from the given reader.  Groups are separated by an empty line.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<Vec<String>>, LoadError> {
+    common::sections(reader)
+        .map(|section| Ok(section?.lines))
+        .collect()
+}
+
+/// Counts the total number of yes answers across all of the groups.  Within a group,
+/// a question only counts once if multiple people answer it yes.
+pub fn count_answers_any_yes(groups: &[Vec<String>]) -> usize {
+    groups.iter()
+        .map(|group| group.iter()
+            .flat_map(|line| line.chars())
+            .unique()
+            .count())
+        .sum()
+}
+
+/// Counts the total number of questions that everyone in a group answered yes to.
+pub fn count_answers_all_yes(groups: &[Vec<String>]) -> usize {
+    let all_answers: HashSet<char> = vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i',
+                                          'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+                                          's', 't', 'u', 'v', 'w', 'x', 'y', 'z'].into_iter().collect();
+
+    groups.iter().map(|group| {
+        group.iter()
+            // Intersection of answers from all of the folks in the group is the number of all-yes
+            // answers for that group.
+            .map(|person| person.chars().collect::<HashSet<char>>())
+            .fold(all_answers.clone(), |yes, person| {
+                yes.intersection(&person).cloned().collect()
+            })
+            .len()
+    }).sum()
+}
+
+pub struct Day6 {
+    groups: Vec<Vec<String>>,
+}
+
+impl Solution for Day6 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day6 { groups: read(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        count_answers_any_yes(&self.groups)
+    }
+
+    fn part2(&self) -> usize {
+        count_answers_all_yes(&self.groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sample() {
+        let answers = parse("sample.txt").unwrap();
+
+        assert_eq!(5, answers.len());
+        assert_eq!(vec!["abc"], answers[0]);
+    }
+
+    #[test]
+    fn count_answers_any_yes_sample() {
+        let answers = parse("sample.txt").unwrap();
+
+        assert_eq!(11, count_answers_any_yes(&answers));
+    }
+
+    #[test]
+    fn count_answers_all_yes_sample() {
+        let answers = parse("sample.txt").unwrap();
+
+        assert_eq!(6, count_answers_all_yes(&answers));
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day6::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(11, day.part1());
+        assert_eq!(6, day.part2());
+    }
+}
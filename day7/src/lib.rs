@@ -0,0 +1,186 @@
+#[macro_use]
+extern crate lazy_static;
+extern crate regex;
+
+use std::collections::{HashMap, HashSet, BinaryHeap};
+use std::io::BufRead;
+
+use regex::Regex;
+use common::{LoadError, ParseError, Solution};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Bag {
+    num: usize,
+    color: String,
+}
+
+/// Loads rules (map of bag color to the bags inside) from the given file, returning an error if
+/// the file is invalid or can't be loaded.
+pub fn load_rules(filename: &str) -> Result<HashMap<String, Vec<Bag>>, LoadError> {
+    read_rules(common::open(filename)?)
+}
+
+/// Reads rules (map of bag color to the bags inside) from the given reader, one per line.
+pub fn read_rules<R: BufRead>(reader: R) -> Result<HashMap<String, Vec<Bag>>, LoadError> {
+    // bright white bags contain 1 shiny gold bag.
+    // dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+    lazy_static! {
+        static ref BAG_COUNT_RE: Regex = Regex::new(r"(\d+) ([a-z ]+) bags?").unwrap();
+    }
+
+    let mut rules = HashMap::new();
+
+    for (i, line_result) in reader.lines().enumerate() {
+        let line = line_result?;
+
+        let bag_color_idx = line.find(" bags contain")
+            .ok_or_else(|| ParseError::new(&line, line.len() + 1, "' bags contain'").at_line(i + 1))?;
+
+        let color = line[0..bag_color_idx].to_owned();
+
+        let inside = if line.ends_with("contain no other bags.") {
+            vec![]
+        } else {
+            BAG_COUNT_RE.captures_iter(line.as_str())
+                .map(|capture| {
+                    let num = capture.get(1).unwrap();
+
+                    Ok(Bag {
+                        num: num.as_str().parse()
+                            .map_err(|_| ParseError::new(&line, num.start() + 1, "a bag count").at_line(i + 1))?,
+                        color: capture[2].to_owned(),
+                    })
+                })
+                .collect::<Result<_, LoadError>>()?
+        };
+
+        rules.insert(color, inside);
+    }
+
+    Ok(rules)
+}
+
+/// Counts the number of bag colors that contain at least one shiny gold bag.
+pub fn count_shiny_gold(rules: &HashMap<String, Vec<Bag>>) -> usize {
+    let mut bag_containers = HashMap::new();
+
+    for container in rules.keys() {
+        for inside in &rules[container] {
+            bag_containers.entry(inside.color.clone()).or_insert(Vec::new()).push(container.clone());
+        }
+    }
+
+    let mut to_visit = BinaryHeap::new();
+    to_visit.push("shiny gold".to_owned());
+
+    let mut containers = HashSet::new();
+
+    while let Some(color) = to_visit.pop() {
+        if !bag_containers.contains_key(&color) {
+            continue;
+        }
+
+        for container in bag_containers.get(&color).unwrap() {
+            if !containers.contains(container) {
+                containers.insert(container);
+                to_visit.push(container.to_owned());
+            }
+        }
+    }
+
+    containers.len()
+}
+
+/// Counts the number of bags required inside of the shiny gold bag, not counting the gold bag.
+pub fn count_bags_in_shiny_gold(rules: &HashMap<String, Vec<Bag>>) -> usize {
+    count_bags(rules, "shiny gold") - 1
+}
+
+/// Counts the number of bags inside the given bag, including the bag.
+fn count_bags(rules: &HashMap<String, Vec<Bag>>, color: &str) -> usize {
+    let count = match rules.get(color) {
+        None => 1,
+        Some(bags) => {
+            let mut count = 1;
+
+            for bag in bags {
+                count += bag.num * count_bags(rules, &bag.color);
+            }
+
+            count
+        }
+    };
+
+    count
+}
+
+pub struct Day7 {
+    rules: HashMap<String, Vec<Bag>>,
+}
+
+impl Solution for Day7 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day7 { rules: read_rules(input.as_bytes())? })
+    }
+
+    fn part1(&self) -> usize {
+        count_shiny_gold(&self.rules)
+    }
+
+    fn part2(&self) -> usize {
+        count_bags_in_shiny_gold(&self.rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_sample() {
+        let rules = load_rules("sample.txt").unwrap();
+
+        assert_eq!(9, rules.len());
+
+        assert_eq!(Some(&vec![
+            Bag { num: 1, color: "bright white".to_owned() },
+            Bag { num: 2, color: "muted yellow".to_owned() },
+        ]), rules.get("light red"));
+
+        assert_eq!(
+            Some(&vec![Bag { num: 1, color: "shiny gold".to_owned() }]),
+            rules.get("bright white"));
+    }
+
+    #[test]
+    fn count_sample() {
+        let rules = load_rules("sample.txt").unwrap();
+
+        assert_eq!(4, count_shiny_gold(&rules));
+    }
+
+    #[test]
+    fn count_bags_inside_sample() {
+        let rules = load_rules("sample.txt").unwrap();
+
+        assert_eq!(32, count_bags_in_shiny_gold(&rules));
+    }
+
+    #[test]
+    fn count_bags_inside_sample2() {
+        let rules = load_rules("sample2.txt").unwrap();
+
+        assert_eq!(126, count_bags_in_shiny_gold(&rules));
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day7::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(4, day.part1());
+        assert_eq!(32, day.part2());
+    }
+}
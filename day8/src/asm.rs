@@ -0,0 +1,309 @@
+use std::collections::{HashMap, HashSet};
+
+use common::ParseError;
+
+use crate::{Instruction, OpcodeTable, Operand, OperandKind, Register};
+
+/// Listing controls how a program is disassembled.  The default listing is the canonical form:
+/// one instruction per line, exactly the way Computer::load reads it.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct Listing {
+    /// Labels every jump target with its pc, like 'pc12:', and jumps to the label instead of
+    /// by an offset.
+    pub labels: bool,
+
+    /// Follows each jump with a comment giving the pc it jumps to.
+    pub targets: bool,
+}
+
+/// Writes out the given program.  Every listing assembles back into the same program.
+pub fn disassemble(instructions: &[Instruction], listing: &Listing) -> String {
+    let len = instructions.len();
+
+    let target = |pc: usize, instruction: &Instruction| jump(instruction).map(|(_, offset)| pc as i64 + offset as i64);
+
+    // Jumps can land on an instruction or immediately past the last one.  Other jumps stay as
+    // offsets, since there's nothing to label.
+    let labeled: HashSet<usize> = instructions.iter().enumerate()
+        .filter_map(|(pc, instruction)| target(pc, instruction))
+        .filter(|&target| listing.labels && target >= 0 && target <= len as i64)
+        .map(|target| target as usize)
+        .collect();
+
+    let indent = if listing.labels { "    " } else { "" };
+
+    let mut out = String::new();
+    for (pc, instruction) in instructions.iter().enumerate() {
+        if labeled.contains(&pc) {
+            out.push_str(&format!("{}:\n", label(pc)));
+        }
+
+        let text = match (jump(instruction), target(pc, instruction)) {
+            (Some((arg, _)), Some(target)) if target >= 0 && labeled.contains(&(target as usize)) => {
+                let mut args = arguments(instruction);
+                args[arg] = label(target as usize);
+
+                format!("{} {}", instruction.name(), args.join(" "))
+            }
+            _ => instruction.to_string(),
+        };
+
+        match target(pc, instruction) {
+            Some(target) if listing.targets => out.push_str(&format!("{}{:<12}; -> {}\n", indent, text, target)),
+            _ => out.push_str(&format!("{}{}\n", indent, text)),
+        }
+    }
+
+    if labeled.contains(&len) {
+        out.push_str(&format!("{}:\n", label(len)));
+    }
+
+    out
+}
+
+/// Returns which of the instruction's arguments is a jump offset, along with the offset, or
+/// None if the instruction doesn't jump.  'jmp' jumps, as do opcodes with an Offset operand.
+fn jump(instruction: &Instruction) -> Option<(usize, i32)> {
+    match instruction {
+        Instruction::Jmp(offset) => Some((0, *offset)),
+        Instruction::Op(opcode, operands) => opcode.operands.iter().zip(operands).enumerate()
+            .find_map(|(arg, operand)| match operand {
+                (OperandKind::Offset, Operand::Value(offset)) => Some((arg, *offset)),
+                _ => None,
+            }),
+        _ => None,
+    }
+}
+
+/// Returns the instruction's arguments the way they're written, like ['a', '-2'].
+fn arguments(instruction: &Instruction) -> Vec<String> {
+    match instruction {
+        Instruction::Nop(value) | Instruction::Acc(value) | Instruction::Jmp(value) => vec![format!("{:+}", value)],
+        Instruction::Op(opcode, operands) => operands[..opcode.operands.len()].iter().map(|operand| operand.to_string()).collect(),
+    }
+}
+
+/// Returns the label that disassembly gives the instruction at pc.
+fn label(pc: usize) -> String {
+    format!("pc{}", pc)
+}
+
+/// One instruction in assembly source, along with where it came from.
+struct SourceLine<'a> {
+    /// Line number in the source, numbered from 1.
+    number: usize,
+
+    /// Full text of the line.
+    text: &'a str,
+
+    /// Opcode and arguments, along with the column (from 0) each starts at.
+    tokens: Vec<(usize, &'a str)>,
+}
+
+/// Assembles a program written with labels and comments into instructions.  A label is a name
+/// followed by ':', either on its own line or before an instruction, and any argument can be a
+/// label instead of an offset: 'jmp loop' jumps to the instruction labeled 'loop:'.  Comments
+/// start with ';' or '#' and run to the end of the line.  Labels can't be register names.
+pub fn assemble(source: &str, table: &OpcodeTable) -> Result<Vec<Instruction>, ParseError> {
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (i, text) in source.lines().enumerate() {
+        let code = &text[..text.find([';', '#']).unwrap_or(text.len())];
+        let mut tokens = tokens(code);
+
+        while let Some(&(column, token)) = tokens.first() {
+            let name = match token.strip_suffix(':') {
+                Some(name) => name,
+                None => break,
+            };
+
+            let error = |expected: &str| ParseError::new(text, column + 1, expected).at_line(i + 1);
+            if !is_name(name) {
+                return Err(error("a label name made of letters, digits, and '_'"));
+            }
+            if name.parse::<Register>().is_ok() {
+                return Err(error("a label name that isn't a register"));
+            }
+            if labels.insert(name, lines.len()).is_some() {
+                return Err(error("a label that isn't already defined"));
+            }
+
+            tokens.remove(0);
+        }
+
+        if !tokens.is_empty() {
+            lines.push(SourceLine { number: i + 1, text, tokens });
+        }
+    }
+
+    lines.iter().enumerate()
+        .map(|(pc, line)| {
+            let mut resolved = vec![line.tokens[0].1.to_owned()];
+            for &(column, arg) in &line.tokens[1..] {
+                if !is_name(arg) || arg.parse::<Register>().is_ok() {
+                    resolved.push(arg.to_owned());
+                    continue;
+                }
+
+                let target = labels.get(arg)
+                    .ok_or_else(|| ParseError::new(line.text, column + 1, "a signed argument or a label").at_line(line.number))?;
+                resolved.push(format!("{:+}", *target as i64 - pc as i64));
+            }
+
+            let instruction = resolved.join(" ");
+            table.parse(&instruction).map_err(|e| {
+                let column = source_column(&line.tokens, &resolved, e.column);
+                ParseError::new(line.text, column, &e.expected).at_line(line.number)
+            })
+        })
+        .collect()
+}
+
+/// Splits code into tokens separated by whitespace, along with the column (from 0) each
+/// token starts at.
+fn tokens(code: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in code.char_indices().chain(Some((code.len(), ' '))) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push((s, &code[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Returns whether the given text can be a label: letters, digits, and '_', not starting
+/// with a digit.
+fn is_name(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Converts a column (from 1) in the resolved instruction, which is the tokens joined by single
+/// spaces, into the matching column (from 1) in the source line.
+fn source_column(tokens: &[(usize, &str)], resolved: &[String], column: usize) -> usize {
+    let mut start = 0;
+    for (&(source_start, source), token) in tokens.iter().zip(resolved) {
+        let end = start + token.len();
+        if column - 1 <= end {
+            return source_start + (column - 1 - start).min(source.len()) + 1;
+        }
+
+        start = end + 1;
+    }
+
+    let &(last_start, last) = tokens.last().unwrap();
+    last_start + last.len() + 2
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Computer;
+    use crate::Instruction::{Acc, Jmp, Nop};
+
+    use super::*;
+
+    const LABELED: &str = "    nop +0
+pc1:
+    acc +1
+    jmp pc6     ; -> 6
+pc3:
+    acc +3
+    jmp pc1     ; -> 1
+    acc -99
+pc6:
+    acc +1
+    jmp pc3     ; -> 3
+    acc +6
+";
+
+    #[test]
+    fn disassemble_canonical() {
+        let comp = Computer::load("sample.txt").unwrap();
+
+        assert_eq!(std::fs::read_to_string("sample.txt").unwrap(), disassemble(comp.instructions(), &Listing::default()));
+    }
+
+    #[test]
+    fn disassemble_labels() {
+        let comp = Computer::load("sample.txt").unwrap();
+        let listing = disassemble(comp.instructions(), &Listing { labels: true, targets: true });
+
+        assert_eq!(LABELED, listing);
+        assert_eq!(comp.instructions(), &assemble(&listing, &OpcodeTable::standard()).unwrap()[..]);
+    }
+
+    #[test]
+    fn disassemble_targets() {
+        let listing = disassemble(&[Nop(0), Jmp(-2), Jmp(1)], &Listing { labels: true, targets: true });
+
+        assert_eq!("    nop +0\n    jmp -2      ; -> -1\n    jmp pc3     ; -> 3\npc3:\n", listing);
+    }
+
+    #[test]
+    fn assemble_labels_and_comments() {
+        let source = "# Counts down from 3.\n\
+                      \x20   set a +3\n\
+                      loop: sub a +1     ; a -= 1\n\
+                      \x20   acc +1\n\
+                      \x20   jnz a loop\n\
+                      \n\
+                      done: jmp end\n\
+                      \x20   acc +100\n\
+                      end:\n";
+
+        let instructions = assemble(source, &OpcodeTable::standard()).unwrap();
+        let canonical = disassemble(&instructions, &Listing::default());
+        assert_eq!("set a +3\nsub a +1\nacc +1\njnz a -2\njmp +2\nacc +100\n", canonical);
+
+        let mut comp = Computer::read(canonical.as_bytes()).unwrap();
+        assert!(comp.run(100));
+        assert_eq!(3, comp.acc);
+    }
+
+    #[test]
+    fn disassemble_conditional_jumps() {
+        let source = "    set a +3\n\
+                      pc1:\n\
+                      \x20   sub a +1\n\
+                      \x20   jz a pc4    ; -> 4\n\
+                      \x20   jnz a pc1   ; -> 1\n\
+                      pc4:\n\
+                      \x20   jnz a -9    ; -> -5\n";
+
+        let table = OpcodeTable::standard();
+        let instructions = assemble(source, &table).unwrap();
+        assert_eq!("set a +3\nsub a +1\njz a +2\njnz a -2\njnz a -9\n", disassemble(&instructions, &Listing::default()));
+
+        let listing = disassemble(&instructions, &Listing { labels: true, targets: true });
+        assert_eq!(source, listing);
+        assert_eq!(instructions, assemble(&listing, &table).unwrap());
+    }
+
+    #[test]
+    fn assemble_forward_and_backward() {
+        let instructions = assemble("start: nop end\nacc +1\njmp start\nend: acc -1", &OpcodeTable::original()).unwrap();
+
+        assert_eq!(vec![Nop(3), Acc(1), Jmp(-2), Acc(-1)], instructions);
+    }
+
+    #[test]
+    fn assemble_errors() {
+        let table = OpcodeTable::standard();
+
+        assert_eq!(Err(ParseError::new("  jmp nowhere", 7, "a signed argument or a label").at_line(2)), assemble("nop +0\n  jmp nowhere", &table));
+        assert_eq!(Err(ParseError::new("loop: acc +1", 1, "a label that isn't already defined").at_line(2)), assemble("loop:\nloop: acc +1", &table));
+        assert_eq!(Err(ParseError::new("b: nop +0", 1, "a label name that isn't a register").at_line(1)), assemble("b: nop +0", &table));
+        assert_eq!(Err(ParseError::new("1x: nop +0", 1, "a label name made of letters, digits, and '_'").at_line(1)), assemble("1x: nop +0", &table));
+        assert_eq!(Err(ParseError::new("top: hlt  +2 ; extra", 11, "end of instruction").at_line(1)), assemble("top: hlt  +2 ; extra", &table));
+        assert_eq!(Err(ParseError::new("   mul a", 9, "a space followed by an argument").at_line(1)), assemble("   mul a", &table));
+    }
+}
@@ -0,0 +1,191 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::{Computer, Instruction};
+use crate::Instruction::{Acc, Jmp, Nop, Op};
+
+/// Fix is the single toggled instruction that makes a program terminate.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Fix {
+    /// Index of the toggled instruction.
+    pub pc: usize,
+
+    /// The instruction that replaces it.
+    pub instruction: Instruction,
+
+    /// Value of the accumulator after the fixed program terminates.
+    pub acc: i32,
+}
+
+/// FixError is why there isn't exactly one way to fix a program.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum FixError {
+    /// The program already terminates without toggling anything.
+    AlreadyTerminates,
+
+    /// No single toggled instruction makes the program terminate.
+    NoFix,
+
+    /// Toggling any of the instructions at these pcs makes the program terminate.
+    MultipleFixes(Vec<usize>),
+
+    /// The instruction at this pc isn't 'nop', 'acc', or 'jmp', so where it goes next depends
+    /// on the computer's registers.
+    Unsupported(usize),
+}
+
+impl Display for FixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FixError::AlreadyTerminates => write!(f, "The program already terminates."),
+            FixError::NoFix => write!(f, "No toggled instructions allow the computer to terminate."),
+            FixError::MultipleFixes(pcs) => {
+                let pcs: Vec<String> = pcs.iter().map(|pc| pc.to_string()).collect();
+                write!(f, "Toggling any of the instructions at {} allows the computer to terminate.", pcs.join(", "))
+            }
+            FixError::Unsupported(pc) => write!(f, "The instruction at {} can't be analyzed statically.", pc),
+        }
+    }
+}
+
+impl Error for FixError {}
+
+/// Returns the pc that runs after the given instruction, or None if it leaves the program
+/// without landing immediately past the last instruction.  A program with n instructions
+/// terminates at pc n.
+fn next_pc(pc: usize, instruction: &Instruction, len: usize) -> Option<usize> {
+    let next = match instruction {
+        Nop(_) | Acc(_) => pc as i64 + 1,
+        Jmp(offset) => pc as i64 + *offset as i64,
+        Op(..) => return None,
+    };
+
+    if next >= 0 && next <= len as i64 { Some(next as usize) } else { None }
+}
+
+/// Returns whether running the program from each pc leads to termination, for every pc from 0
+/// up to and including the length of the program.  Builds the control flow graph
+/// in reverse and walks it backwards from the terminating pc, so it takes linear time.
+pub fn reaches_end(instructions: &[Instruction]) -> Result<Vec<bool>, FixError> {
+    let len = instructions.len();
+
+    // sources[pc] is the instructions that go to pc next.
+    let mut sources = vec![Vec::new(); len + 1];
+    for (pc, instruction) in instructions.iter().enumerate() {
+        if let Op(..) = instruction {
+            return Err(FixError::Unsupported(pc));
+        }
+
+        if let Some(next) = next_pc(pc, instruction, len) {
+            sources[next].push(pc);
+        }
+    }
+
+    let mut reaches = vec![false; len + 1];
+    reaches[len] = true;
+
+    let mut to_visit = vec![len];
+    while let Some(pc) = to_visit.pop() {
+        for &source in &sources[pc] {
+            if !reaches[source] {
+                reaches[source] = true;
+                to_visit.push(source);
+            }
+        }
+    }
+
+    Ok(reaches)
+}
+
+/// Finds the single 'jmp' or 'nop' that makes the program terminate when toggled, in linear time.
+///
+/// Only instructions the program runs before it loops are worth toggling.  Toggling one of them
+/// fixes the program if its toggled target reaches the end.  The path from that target to the
+/// end can't go back through the toggled instruction, since the original program would have
+/// followed it to the end.
+pub fn find_fix(comp: &Computer) -> Result<Fix, FixError> {
+    let instructions = &comp.instructions;
+    let len = instructions.len();
+    let reaches = reaches_end(instructions)?;
+
+    if reaches[0] {
+        return Err(FixError::AlreadyTerminates);
+    }
+
+    let mut visited = vec![false; len];
+    let mut fixes = Vec::new();
+
+    let mut pc = Some(0);
+    while let Some(current) = pc.filter(|&current| current < len && !visited[current]) {
+        visited[current] = true;
+
+        let toggled = instructions[current].toggle();
+        if toggled != instructions[current] && next_pc(current, &toggled, len).is_some_and(|next| reaches[next]) {
+            fixes.push(current);
+        }
+
+        pc = next_pc(current, &instructions[current], len);
+    }
+
+    match fixes[..] {
+        [] => Err(FixError::NoFix),
+        [fix_pc] => {
+            let mut fixed = comp.clone();
+            fixed.instructions[fix_pc] = instructions[fix_pc].toggle();
+            fixed.run_until_loop();
+
+            Ok(Fix { pc: fix_pc, instruction: fixed.instructions[fix_pc], acc: fixed.acc })
+        }
+        _ => Err(FixError::MultipleFixes(fixes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reaches_end_sample() {
+        let comp = Computer::load("sample.txt").unwrap();
+
+        assert_eq!(
+            vec![false, false, false, false, false, false, false, false, true, true],
+            reaches_end(&comp.instructions).unwrap(),
+        );
+    }
+
+    #[test]
+    fn find_fix_sample() {
+        let comp = Computer::load("sample.txt").unwrap();
+
+        assert_eq!(Ok(Fix { pc: 7, instruction: Nop(-4), acc: 8 }), find_fix(&comp));
+    }
+
+    #[test]
+    fn find_fix_matches_brute_force() {
+        let comp = Computer::load("input.txt").unwrap();
+
+        let brute_force: Vec<usize> = (0..comp.instructions.len())
+            .filter(|&pc| {
+                let mut toggled = comp.clone();
+                toggled.instructions[pc] = toggled.instructions[pc].toggle();
+                toggled.run_until_loop();
+
+                toggled.pc == comp.instructions.len()
+            })
+            .collect();
+
+        let fix = find_fix(&comp).unwrap();
+        assert_eq!(vec![fix.pc], brute_force);
+    }
+
+    #[test]
+    fn find_fix_errors() {
+        let find = |program: &str| find_fix(&Computer::read(program.as_bytes()).unwrap());
+
+        assert_eq!(Err(FixError::AlreadyTerminates), find("nop +0\nacc +1\n"));
+        assert_eq!(Err(FixError::NoFix), find("acc +1\njmp -1\njmp -2\n"));
+        assert_eq!(Err(FixError::MultipleFixes(vec![0, 1])), find("nop +2\njmp +0\nacc +1\n"));
+        assert_eq!(Err(FixError::Unsupported(1)), find("nop +0\nhlt\n"));
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use crate::{Computer, Register};
+
+/// Breakpoint stops the debugger before it runs an instruction.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum Breakpoint {
+    /// Stops before the instruction at the given pc.
+    Pc(usize),
+
+    /// Stops before any instruction with the given name, like 'jmp'.
+    Kind(String),
+}
+
+/// Stop is why the debugger stopped running the program.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Stop {
+    /// A single step finished.
+    Step,
+
+    /// The next instruction has a breakpoint.
+    Breakpoint(Breakpoint),
+
+    /// The last instruction changed a watched register.
+    Watch { register: Register, old: i32, new: i32 },
+
+    /// The program halted, ran past its last instruction, or jumped before its first.
+    Finished,
+
+    /// The program ran the maximum number of steps without stopping.
+    Limit,
+}
+
+/// Debugger runs a computer one instruction at a time, stopping at breakpoints and when
+/// watched registers change.
+pub struct Debugger {
+    computer: Computer,
+    breakpoints: HashSet<Breakpoint>,
+    watches: Vec<Register>,
+    finished: bool,
+    steps: usize,
+}
+
+impl Debugger {
+    /// Returns a debugger for the given computer, with no breakpoints or watches.
+    pub fn new(computer: Computer) -> Debugger {
+        Debugger { computer, breakpoints: HashSet::new(), watches: Vec::new(), finished: false, steps: 0 }
+    }
+
+    /// Returns the computer being debugged.
+    pub fn computer(&self) -> &Computer {
+        &self.computer
+    }
+
+    /// Returns the number of instructions run so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Adds a breakpoint, returning whether it's new.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.breakpoints.insert(breakpoint)
+    }
+
+    /// Removes a breakpoint, returning whether it existed.
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        self.breakpoints.remove(breakpoint)
+    }
+
+    /// Stops the debugger whenever an instruction changes the given register.
+    pub fn watch(&mut self, register: Register) {
+        if !self.watches.contains(&register) {
+            self.watches.push(register);
+        }
+    }
+
+    /// Stops watching the given register, returning whether it was watched.
+    pub fn unwatch(&mut self, register: Register) -> bool {
+        let len = self.watches.len();
+        self.watches.retain(|&watched| watched != register);
+
+        self.watches.len() != len
+    }
+
+    /// Returns the breakpoint on the next instruction, if there is one.
+    fn breakpoint(&self) -> Option<Breakpoint> {
+        let pc = Breakpoint::Pc(self.computer.pc);
+        if self.breakpoints.contains(&pc) {
+            return Some(pc);
+        }
+
+        let kind = Breakpoint::Kind(self.computer.instructions.get(self.computer.pc)?.name().to_owned());
+        Some(kind).filter(|kind| self.breakpoints.contains(kind))
+    }
+
+    /// Runs the next instruction.  Stops early if the program has finished or the instruction
+    /// changed a watched register.
+    pub fn step(&mut self) -> Stop {
+        if self.finished {
+            return Stop::Finished;
+        }
+
+        let before: Vec<i32> = self.watches.iter().map(|&register| self.computer.register(register)).collect();
+
+        self.finished = !self.computer.step();
+        self.steps += 1;
+
+        let changed = self.watches.iter().zip(before)
+            .map(|(&register, old)| (register, old, self.computer.register(register)))
+            .find(|(_, old, new)| old != new);
+
+        match changed {
+            Some((register, old, new)) => Stop::Watch { register, old, new },
+            None if self.finished => Stop::Finished,
+            None => Stop::Step,
+        }
+    }
+
+    /// Runs instructions until the next one has a breakpoint, a watched register changes, the
+    /// program finishes, or the given number of instructions have run.  The current instruction
+    /// always runs, so continuing from a breakpoint doesn't stop at it again.
+    pub fn resume(&mut self, max_steps: usize) -> Stop {
+        for _ in 0..max_steps {
+            match self.step() {
+                Stop::Step => {}
+                stop => return stop,
+            }
+
+            if let Some(breakpoint) = self.breakpoint() {
+                return Stop::Breakpoint(breakpoint);
+            }
+        }
+
+        Stop::Limit
+    }
+
+    /// Describes the next instruction, like 'pc 4: jmp -3'.
+    pub fn current(&self) -> String {
+        match self.computer.instructions.get(self.computer.pc) {
+            Some(instruction) if !self.finished => format!("pc {}: {}", self.computer.pc, instruction),
+            _ => format!("pc {}: finished", self.computer.pc),
+        }
+    }
+
+    /// Describes the registers that aren't zero, always including acc.
+    pub fn registers(&self) -> String {
+        let general = (0..Register::GENERAL as u8).map(Register::General)
+            .filter(|&register| self.computer.register(register) != 0);
+
+        std::iter::once(Register::Acc).chain(general)
+            .map(|register| format!("{} = {}", register, self.computer.register(register)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Most instructions 'continue' runs before giving up, since boot programs usually loop forever.
+const CONTINUE_LIMIT: usize = 1_000_000;
+
+const HELP: &str = "\
+commands:
+  step [n]          run the next n instructions (default 1)
+  continue          run until a breakpoint, a watched register changes, or the program finishes
+  break <pc|op>     stop before the instruction at pc, or before any instruction named op
+  delete <pc|op>    remove a breakpoint
+  watch <register>  stop when an instruction changes the register, like 'acc' or 'a'
+  unwatch <register>
+  print             show the next instruction and the registers
+  list [n]          show n instructions around the pc (default 5)
+  quit";
+
+/// Runs an interactive debugging session, reading commands from the input and writing
+/// results to the output until the input ends or the user quits.
+pub fn repl<R: BufRead, W: Write>(debugger: &mut Debugger, input: R, output: &mut W) -> io::Result<()> {
+    writeln!(output, "{}", debugger.current())?;
+
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+
+        let command = match words.next() {
+            Some(command) => command,
+            None => continue,
+        };
+        let arg = words.next();
+
+        match command {
+            "s" | "step" => {
+                let count = match arg.map(str::parse::<usize>) {
+                    None => 1,
+                    Some(Ok(count)) => count,
+                    Some(Err(_)) => {
+                        writeln!(output, "expected a number of steps")?;
+                        continue;
+                    }
+                };
+
+                let mut stop = Stop::Step;
+                for _ in 0..count {
+                    stop = debugger.step();
+                    if stop != Stop::Step {
+                        break;
+                    }
+                }
+
+                report(debugger, &stop, output)?;
+            }
+            "c" | "continue" => {
+                let stop = debugger.resume(CONTINUE_LIMIT);
+                report(debugger, &stop, output)?;
+            }
+            "b" | "break" | "d" | "delete" => {
+                let breakpoint = match arg {
+                    Some(arg) => arg.parse().map(Breakpoint::Pc).unwrap_or_else(|_| Breakpoint::Kind(arg.to_owned())),
+                    None => {
+                        writeln!(output, "expected a pc or an instruction name")?;
+                        continue;
+                    }
+                };
+
+                if command.starts_with('b') {
+                    debugger.add_breakpoint(breakpoint);
+                } else if !debugger.remove_breakpoint(&breakpoint) {
+                    writeln!(output, "no breakpoint on {}", arg.unwrap())?;
+                }
+            }
+            "w" | "watch" | "unwatch" => {
+                let register = match arg.map(str::parse::<Register>) {
+                    Some(Ok(register)) => register,
+                    _ => {
+                        writeln!(output, "expected a register from 'a' to 'h', or 'acc'")?;
+                        continue;
+                    }
+                };
+
+                if command == "unwatch" {
+                    debugger.unwatch(register);
+                } else {
+                    debugger.watch(register);
+                }
+            }
+            "p" | "print" => {
+                writeln!(output, "{}", debugger.current())?;
+                writeln!(output, "{}", debugger.registers())?;
+            }
+            "l" | "list" => {
+                let around = arg.and_then(|arg| arg.parse().ok()).unwrap_or(5);
+                let pc = debugger.computer.pc;
+                let instructions = &debugger.computer.instructions;
+
+                for (i, instruction) in instructions.iter().enumerate().take(pc + around + 1).skip(pc.saturating_sub(around)) {
+                    let marker = if i == pc { "=>" } else { "  " };
+                    writeln!(output, "{} {:>4}: {}", marker, i, instruction)?;
+                }
+            }
+            "h" | "help" => writeln!(output, "{}", HELP)?,
+            "q" | "quit" => break,
+            _ => writeln!(output, "unknown command '{}', try 'help'", command)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes why the debugger stopped, followed by the next instruction.
+fn report<W: Write>(debugger: &Debugger, stop: &Stop, output: &mut W) -> io::Result<()> {
+    match stop {
+        Stop::Step => {}
+        Stop::Breakpoint(Breakpoint::Pc(pc)) => writeln!(output, "breakpoint at pc {}", pc)?,
+        Stop::Breakpoint(Breakpoint::Kind(kind)) => writeln!(output, "breakpoint on {}", kind)?,
+        Stop::Watch { register, old, new } => writeln!(output, "{} changed from {} to {}", register, old, new)?,
+        Stop::Finished => writeln!(output, "program finished after {} steps with {}", debugger.steps, debugger.registers())?,
+        Stop::Limit => writeln!(output, "stopped after {} steps", CONTINUE_LIMIT)?,
+    }
+
+    writeln!(output, "{}", debugger.current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_debugger() -> Debugger {
+        Debugger::new(Computer::load("sample.txt").unwrap())
+    }
+
+    #[test]
+    fn step() {
+        let mut debugger = sample_debugger();
+
+        assert_eq!(Stop::Step, debugger.step());
+        assert_eq!(Stop::Step, debugger.step());
+        assert_eq!("pc 2: jmp +4", debugger.current());
+        assert_eq!("acc = 1", debugger.registers());
+    }
+
+    #[test]
+    fn break_on_pc() {
+        let mut debugger = sample_debugger();
+        debugger.add_breakpoint(Breakpoint::Pc(4));
+
+        assert_eq!(Stop::Breakpoint(Breakpoint::Pc(4)), debugger.resume(100));
+        assert_eq!(6, debugger.steps());
+
+        // The sample loops back to pc 1, then runs into pc 4 again.
+        assert_eq!(Stop::Breakpoint(Breakpoint::Pc(4)), debugger.resume(100));
+        assert_eq!(12, debugger.steps());
+    }
+
+    #[test]
+    fn break_on_kind() {
+        let mut debugger = sample_debugger();
+        debugger.add_breakpoint(Breakpoint::Kind("jmp".to_owned()));
+
+        assert_eq!(Stop::Breakpoint(Breakpoint::Kind("jmp".to_owned())), debugger.resume(100));
+        assert_eq!("pc 2: jmp +4", debugger.current());
+
+        assert_eq!(Stop::Breakpoint(Breakpoint::Kind("jmp".to_owned())), debugger.resume(100));
+        assert_eq!("pc 7: jmp -4", debugger.current());
+    }
+
+    #[test]
+    fn watch_acc() {
+        let mut debugger = sample_debugger();
+        debugger.watch(Register::Acc);
+
+        assert_eq!(Stop::Watch { register: Register::Acc, old: 0, new: 1 }, debugger.resume(100));
+        assert_eq!(Stop::Watch { register: Register::Acc, old: 1, new: 2 }, debugger.resume(100));
+        assert_eq!(Stop::Watch { register: Register::Acc, old: 2, new: 5 }, debugger.resume(100));
+    }
+
+    #[test]
+    fn finish() {
+        let mut debugger = Debugger::new(Computer::read("acc +2\nhlt\nacc +1\n".as_bytes()).unwrap());
+
+        assert_eq!(Stop::Finished, debugger.resume(100));
+        assert_eq!(Stop::Finished, debugger.step());
+        assert_eq!("pc 1: finished", debugger.current());
+        assert_eq!(2, debugger.steps());
+    }
+
+    #[test]
+    fn repl_session() {
+        let mut debugger = sample_debugger();
+        let commands = "break 4\ncontinue\nwatch acc\nstep 2\nprint\nlist 1\nfoo\nquit\nstep\n";
+        let mut output = Vec::new();
+
+        repl(&mut debugger, commands.as_bytes(), &mut output).unwrap();
+
+        let expected = "\
+pc 0: nop +0
+breakpoint at pc 4
+pc 4: jmp -3
+acc changed from 5 to 6
+pc 2: jmp +4
+pc 2: jmp +4
+acc = 6
+      1: acc +1
+=>    2: jmp +4
+      3: acc +3
+unknown command 'foo', try 'help'
+";
+
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+}
@@ -0,0 +1,109 @@
+use rand::Rng;
+
+use crate::{control_flow, Computer, Instruction};
+use crate::Instruction::{Acc, Jmp, Nop};
+
+/// Params size a generated boot program.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// Number of instructions in the program.
+    pub instructions: usize,
+
+    /// Largest accumulator change or jump offset.
+    pub max_arg: i32,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params { instructions: 600, max_arg: 50 }
+    }
+}
+
+/// Generates a boot program that loops forever, but terminates if the corrupted instruction
+/// is repaired.  The program only jumps forward, so it always terminates, until one of the
+/// instructions that runs is corrupted into a jump backwards.  Programs where toggling some
+/// other instruction also terminates are thrown away, so there's exactly one repair.
+pub fn generate<R: Rng>(rng: &mut R, params: &Params) -> String {
+    let len = params.instructions.max(2);
+    let max_arg = params.max_arg.max(1);
+
+    let computer = loop {
+        let computer = Computer::new(corrupted_program(rng, len, max_arg));
+        if control_flow::find_fix(&computer).is_ok() {
+            break computer;
+        }
+    };
+
+    computer.instructions().iter()
+        .map(|instruction| format!("{}\n", instruction))
+        .collect()
+}
+
+/// Returns a random program with only forward jumps, where one of the instructions that runs
+/// has been corrupted into a jump back to an earlier instruction that runs.
+fn corrupted_program<R: Rng>(rng: &mut R, len: usize, max_arg: i32) -> Vec<Instruction> {
+    let mut instructions = Vec::with_capacity(len);
+    for pc in 0..len {
+        // Jumps land on an instruction or immediately past the last one.
+        let max_jump = ((len - pc) as i32).min(max_arg);
+
+        instructions.push(match rng.gen_range(0..3) {
+            0 => Nop(rng.gen_range(-max_arg..=max_arg)),
+            1 => Acc(rng.gen_range(-max_arg..=max_arg)),
+            _ => Jmp(rng.gen_range(1..=max_jump)),
+        });
+    }
+
+    let mut path = Vec::new();
+    let mut pc = 0;
+    while pc < len {
+        path.push(pc);
+        pc = match instructions[pc] {
+            Jmp(offset) => (pc as i32 + offset) as usize,
+            // A nop that runs could be toggled into a jump past the loop, so it becomes an acc.
+            Nop(value) => {
+                instructions[pc] = Acc(value);
+                pc + 1
+            }
+            _ => pc + 1,
+        };
+    }
+
+    let corrupt = rng.gen_range(0..path.len());
+    let target = rng.gen_range(0..=corrupt);
+    instructions[path[corrupt]] = Jmp(path[target] as i32 - path[corrupt] as i32);
+
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use common::Solution;
+
+    use crate::Day8;
+
+    use super::*;
+
+    #[test]
+    fn generate_solvable() {
+        for seed in 0..20 {
+            let input = generate(&mut StdRng::seed_from_u64(seed), &Params { instructions: 50, max_arg: 10 });
+            let computer = Computer::read(input.as_bytes()).unwrap();
+
+            assert_eq!(50, computer.instructions.len());
+
+            // Part 2 is an error unless exactly one repaired program terminates.
+            assert!(Day8::parse(&input).unwrap().part2().is_ok(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn generate_seeded() {
+        let params = Params::default();
+
+        assert_eq!(generate(&mut StdRng::seed_from_u64(7), &params), generate(&mut StdRng::seed_from_u64(7), &params));
+    }
+}
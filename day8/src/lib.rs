@@ -1,34 +1,89 @@
+use std::fmt::{self, Formatter};
 use std::str::FromStr;
 
-use crate::Instruction::{Acc, Jmp, Nop};
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-use std::collections::HashSet;
+use crate::Instruction::{Acc, Jmp, Nop, Op};
+use std::io::BufRead;
+use std::collections::HashMap;
+use common::{LoadError, ParseError, Solution};
 
-#[derive(Debug, Eq, PartialEq)]
-struct ParseErr {}
+pub use control_flow::{Fix, FixError};
+pub use opcode::{Flow, Opcode, OpcodeTable, Operand, OperandKind, Register};
+pub use trace::{Cycle, LoopInstruction, Trace, TraceStep};
 
+pub mod asm;
+pub mod control_flow;
+pub mod debugger;
+pub mod generate;
+pub mod opcode;
+pub mod trace;
+
+/// Instruction is one line of a boot program.  'nop', 'acc', and 'jmp' are built in, and other
+/// instructions come from an OpcodeTable.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
-enum Instruction {
+pub enum Instruction {
     Nop(i32),
     Acc(i32),
     Jmp(i32),
+
+    /// An opcode from an OpcodeTable and its operands.  Operands past the ones the opcode
+    /// takes are unused.
+    Op(&'static Opcode, [Operand; Opcode::MAX_OPERANDS]),
 }
 
 impl FromStr for Instruction {
-    type Err = ParseErr;
+    type Err = ParseError;
 
+    /// Parses an instruction using the standard opcode table, like 'jmp -3' or 'mul a b'.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match (&s[0..3], s[4..].parse::<i32>()) {
-            ("nop", Ok(value)) => Ok(Nop(value)),
-            ("acc", Ok(value)) => Ok(Acc(value)),
-            ("jmp", Ok(value)) => Ok(Jmp(value)),
-            _ => Err(ParseErr {})
+        OpcodeTable::standard().parse(s)
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Writes the instruction the way it's parsed, like 'jmp -3' or 'mul a +2'.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Nop(value) | Acc(value) | Jmp(value) => write!(f, "{} {:+}", self.name(), value),
+            Op(opcode, operands) => {
+                write!(f, "{}", opcode.name)?;
+                for operand in &operands[..opcode.operands.len()] {
+                    write!(f, " {}", operand)?;
+                }
+
+                Ok(())
+            }
         }
     }
 }
 
 impl Instruction {
+    /// Returns the name of this instruction's operation, like 'jmp' or 'mul'.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Nop(_) => "nop",
+            Acc(_) => "acc",
+            Jmp(_) => "jmp",
+            Op(opcode, _) => opcode.name,
+        }
+    }
+
+    /// Parses one of the built in instructions, which is an operation and a signed argument
+    /// like 'jmp -3'.
+    fn parse_built_in(s: &str, op: &str, arg: Option<&str>) -> Result<Instruction, ParseError> {
+        let instruction: fn(i32) -> Instruction = match op {
+            "nop" => Nop,
+            "acc" => Acc,
+            "jmp" => Jmp,
+            _ => return Err(ParseError::new(s, 1, "'nop', 'acc', or 'jmp'")),
+        };
+
+        let arg = arg.ok_or_else(|| ParseError::new(s, s.len() + 1, "a space followed by an argument"))?;
+        let value = arg.parse()
+            .map_err(|_| ParseError::new(s, op.len() + 2, "a signed argument like +3 or -4"))?;
+
+        Ok(instruction(value))
+    }
+
     /// Runs the given instruction, returning whether the computer has more instructions to execute.
     fn run(&self, comp: &mut Computer) -> bool {
         let new_pc = match self {
@@ -44,6 +99,17 @@ impl Instruction {
             Jmp(value) => {
                 comp.pc as i32 + *value
             }
+
+            Op(opcode, operands) => {
+                match (opcode.run)(comp, &operands[..opcode.operands.len()]) {
+                    Flow::Next => comp.pc as i32 + 1,
+                    Flow::Jump(offset) => comp.pc as i32 + offset,
+                    Flow::Halt => {
+                        comp.halted = true;
+                        return false;
+                    }
+                }
+            }
         };
 
         if new_pc < 0 {
@@ -60,7 +126,7 @@ impl Instruction {
         match self {
             Jmp(value) => Nop(*value),
             Nop(value) => Jmp(*value),
-            Acc(value) => Acc(*value),
+            other => *other,
         }
     }
 }
@@ -76,7 +142,11 @@ mod instruction_tests {
         assert_eq!("jmp +4".parse(), Ok(Jmp(4)));
         assert_eq!("jmp -3".parse(), Ok(Jmp(-3)));
 
-        assert_eq!("invalid".parse::<Instruction>(), Err(ParseErr {}));
+        let opcodes = "'nop', 'acc', 'jmp', 'set', 'add', 'sub', 'mul', 'jz', 'jnz', or 'hlt'";
+        assert_eq!("invalid".parse::<Instruction>(), Err(ParseError::new("invalid", 1, opcodes)));
+        assert_eq!("ac".parse::<Instruction>(), Err(ParseError::new("ac", 1, opcodes)));
+        assert_eq!("acc".parse::<Instruction>(), Err(ParseError::new("acc", 4, "a space followed by an argument")));
+        assert_eq!("jmp x3".parse::<Instruction>(), Err(ParseError::new("jmp x3", 5, "a signed argument like +3 or -4")));
     }
 
     #[test]
@@ -109,13 +179,39 @@ mod instruction_tests {
         assert_eq!(comp.acc, 0);
     }
 
+    #[test]
+    fn run_conditional_jump() {
+        let mut comp = Computer::read("set a +2\njnz a +2\nacc +100\nsub a +1\njz a +2\njmp -2\nacc +1\n".as_bytes()).unwrap();
+
+        assert!(comp.run(100));
+
+        assert_eq!(comp.pc, 7);
+        assert_eq!(comp.acc, 1);
+        assert_eq!(comp.register(Register::General(0)), 0);
+    }
+
+    #[test]
+    fn run_mul_halt() {
+        let mut comp = Computer::read("acc +3\nset b acc\nmul acc b\nhlt\nacc +1\n".as_bytes()).unwrap();
+
+        assert!(comp.run(100));
+        assert!(comp.halted());
+        assert_eq!(comp.pc, 3);
+        assert_eq!(comp.acc, 9);
+    }
+
+    #[test]
+    fn display() {
+        for line in ["nop +0", "acc -99", "jmp +4", "set a -3", "mul acc b", "jz h +2", "hlt"].iter() {
+            assert_eq!(*line, line.parse::<Instruction>().unwrap().to_string());
+        }
+    }
+
     #[test]
     fn toggle() {
-        let instructions = vec![
-            Nop(0),
+        let instructions = [Nop(0),
             Acc(1),
-            Jmp(3),
-        ];
+            Jmp(3)];
 
         let toggled: Vec<Instruction> = instructions.iter().map(|i| i.toggle()).collect();
 
@@ -123,10 +219,13 @@ mod instruction_tests {
     }
 }
 
+#[derive(Clone)]
 pub struct Computer {
     instructions: Vec<Instruction>,
     pc: usize,
     pub acc: i32,
+    registers: [i32; Register::GENERAL],
+    halted: bool,
 }
 
 impl Computer {
@@ -136,52 +235,164 @@ impl Computer {
             instructions,
             pc: 0,
             acc: 0,
+            registers: [0; Register::GENERAL],
+            halted: false,
         }
     }
 
-    /// Loads the instructions in the given file into a new computer, panicking if the
+    /// Loads the instructions in the given file into a new computer, returning an error if the
     /// file can't be loaded or contains invalid instructions.
-    pub fn load(filename: &str) -> Computer {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
+    pub fn load(filename: &str) -> Result<Computer, LoadError> {
+        Computer::read(common::open(filename)?)
+    }
 
-        let instructions = f.lines()
-            .map(|line| line.unwrap().parse::<Instruction>().unwrap())
-            .collect();
+    /// Reads instructions from the given reader into a new computer, returning an error if the
+    /// reader contains invalid instructions.
+    pub fn read<R: BufRead>(reader: R) -> Result<Computer, LoadError> {
+        Ok(Computer::new(common::parse_lines(reader)?))
+    }
+
+    /// Reads instructions from the given reader into a new computer, using the opcodes in the
+    /// given table.
+    pub fn read_with<R: BufRead>(reader: R, table: &OpcodeTable) -> Result<Computer, LoadError> {
+        let instructions = reader.lines().enumerate()
+            .map(|(i, line)| {
+                let line = line?;
+                table.parse(&line).map_err(|e| LoadError::Parse(e.at_line(i + 1)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Computer::new(instructions))
+    }
 
-        Computer::new(instructions)
+    /// Assembles the given source, which can use labels and comments, into a new computer.
+    /// See asm::assemble for the syntax.
+    pub fn assemble(source: &str, table: &OpcodeTable) -> Result<Computer, LoadError> {
+        Ok(Computer::new(asm::assemble(source, table)?))
+    }
+
+    /// Returns the index of the next instruction to run.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Returns the computer's program.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Returns the value in the given register.
+    pub fn register(&self, register: Register) -> i32 {
+        match register {
+            Register::Acc => self.acc,
+            Register::General(i) => self.registers[i as usize],
+        }
+    }
+
+    /// Sets the given register to a value.
+    pub fn set_register(&mut self, register: Register, value: i32) {
+        match register {
+            Register::Acc => self.acc = value,
+            Register::General(i) => self.registers[i as usize] = value,
+        }
+    }
+
+    /// Returns whether the computer stopped by running a 'hlt' instruction.
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Runs the next instruction, returning whether the computer has more instructions to run.
+    pub fn step(&mut self) -> bool {
+        if self.halted || self.pc >= self.instructions.len() {
+            return false;
+        }
+
+        let instruction = self.instructions[self.pc];
+        instruction.run(self)
+    }
+
+    /// Runs the computer until it halts or runs past its last instruction, or until it has run
+    /// the given number of instructions.  Returns whether the program finished.  Programs with
+    /// registers and conditional jumps can run an instruction many times without looping, so
+    /// this bounds them by steps instead of stopping at the first repeated instruction.
+    pub fn run(&mut self, max_steps: usize) -> bool {
+        for _ in 0..max_steps {
+            if !self.step() {
+                return self.halted || self.pc >= self.instructions.len();
+            }
+        }
+
+        false
     }
 
     /// Runs the computer until immediately before an instruction would be run for a second time.
     pub fn run_until_loop(&mut self) {
-        let mut run_pcs = HashSet::new();
+        self.run_until_repeat(|_| {});
+    }
+
+    /// Runs the computer like run_until_loop, returning every instruction it ran along with the
+    /// loop that stopped it.
+    pub fn trace_until_loop(&mut self) -> Trace {
+        let mut steps = Vec::new();
+        let loop_start = self.run_until_repeat(|step| steps.push(step));
+
+        Trace::new(steps, loop_start)
+    }
+
+    /// Runs the computer until immediately before an instruction would be run for a second time,
+    /// passing each instruction it runs to record.  Returns the step where the repeated
+    /// instruction first ran, or None if the program finished.
+    fn run_until_repeat<F: FnMut(TraceStep)>(&mut self, mut record: F) -> Option<usize> {
+        let mut run_pcs = HashMap::new();
         let mut keep_going = true;
 
-        while keep_going && !run_pcs.contains(&self.pc) {
-            run_pcs.insert(self.pc);
+        while keep_going && self.pc < self.instructions.len() {
+            if let Some(&step) = run_pcs.get(&self.pc) {
+                return Some(step);
+            }
+            run_pcs.insert(self.pc, run_pcs.len());
 
+            let (pc, acc_before) = (self.pc, self.acc);
             let instruction = self.instructions[self.pc];
             keep_going = instruction.run(self);
+
+            record(TraceStep { pc, instruction, acc_before, acc_after: self.acc });
         }
+
+        None
     }
 }
 
 /// Finds a computer that terminates successfully with the pc immediately past the last instruction
 /// by toggling a single instruction in the computer and returns the value of the accumulator.
-pub fn find_terminating_computer(comp: &Computer) -> i32 {
-    for i in 0..comp.instructions.len() {
-        let mut toggled_instructions = comp.instructions.clone();
-        toggled_instructions[i] = toggled_instructions[i].toggle();
+/// Returns an error if there isn't exactly one instruction to toggle.
+pub fn find_terminating_computer(comp: &Computer) -> Result<i32, FixError> {
+    control_flow::find_fix(comp).map(|fix| fix.acc)
+}
 
-        let mut toggled_comp = Computer::new(toggled_instructions);
-        toggled_comp.run_until_loop();
+pub struct Day8 {
+    computer: Computer,
+}
 
-        if toggled_comp.pc == comp.instructions.len() {
-            return toggled_comp.acc;
-        }
+impl Solution for Day8 {
+    type Part1 = i32;
+    type Part2 = Result<i32, FixError>;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day8 { computer: Computer::read(input.as_bytes())? })
     }
 
-    panic!("No toggled instructions allow the computer to terminate.")
+    fn part1(&self) -> i32 {
+        let mut computer = self.computer.clone();
+        computer.run_until_loop();
+
+        computer.acc
+    }
+
+    fn part2(&self) -> Result<i32, FixError> {
+        find_terminating_computer(&self.computer)
+    }
 }
 
 #[cfg(test)]
@@ -190,16 +401,27 @@ mod computer_tests {
 
     #[test]
     fn load_sample() {
-        let computer = Computer::load("sample.txt");
+        let computer = Computer::load("sample.txt").unwrap();
 
         assert_eq!(computer.pc, 0);
         assert_eq!(computer.acc, 0);
         assert_eq!(computer.instructions.len(), 9);
     }
 
+    #[test]
+    fn read_invalid() {
+        match Computer::read("nop +0\nacc +1\nfoo +2\n".as_bytes()) {
+            Err(LoadError::Parse(e)) => {
+                assert_eq!(3, e.line);
+                assert_eq!("foo +2", e.text);
+            }
+            _ => panic!("Expected a parse error on line 3."),
+        }
+    }
+
     #[test]
     fn run_sample_until_loop() {
-        let mut computer = Computer::load("sample.txt");
+        let mut computer = Computer::load("sample.txt").unwrap();
         computer.run_until_loop();
 
         assert_eq!(computer.acc, 5);
@@ -207,8 +429,30 @@ mod computer_tests {
 
     #[test]
     fn find_terminating_computer_sample() {
-        let comp = Computer::load("sample.txt");
+        let comp = Computer::load("sample.txt").unwrap();
+
+        assert_eq!(find_terminating_computer(&comp), Ok(8));
+    }
+    #[test]
+    fn solution_sample() {
+        let day = Day8::parse(&std::fs::read_to_string("sample.txt").unwrap()).unwrap();
+
+        assert_eq!(5, day.part1());
+        assert_eq!(Ok(8), day.part2());
+    }
+
+    #[test]
+    fn solution_without_fix() {
+        let day = Day8::parse("jmp +0\njmp +0\n").unwrap();
 
-        assert_eq!(find_terminating_computer(&comp), 8);
+        assert_eq!(Err(FixError::NoFix), day.part2());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solution_empty_program() {
+        let day = Day8::parse("").unwrap();
+
+        assert_eq!(0, day.part1());
+        assert!(day.part2().is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use std::fmt::{self, Debug, Formatter};
+use std::str::FromStr;
+
+use common::ParseError;
+
+use crate::{Computer, Instruction};
+
+/// Register is a place the computer stores a value.  'acc' is the accumulator that the original
+/// instructions use, and 'a' through 'h' are general purpose registers.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum Register {
+    Acc,
+    General(u8),
+}
+
+impl Register {
+    /// Number of general purpose registers.
+    pub const GENERAL: usize = 8;
+}
+
+impl FromStr for Register {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            b"acc" => Ok(Register::Acc),
+            &[c] if (b'a'..b'a' + Register::GENERAL as u8).contains(&c) => Ok(Register::General(c - b'a')),
+            _ => Err(ParseError::new(s, 1, "a register from 'a' to 'h', or 'acc'")),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Register::Acc => write!(f, "acc"),
+            Register::General(i) => write!(f, "{}", (b'a' + i) as char),
+        }
+    }
+}
+
+/// Operand is an argument to an instruction: either a register or a signed value.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Operand {
+    Register(Register),
+    Value(i32),
+}
+
+impl Operand {
+    /// Returns the value of this operand on the given computer.
+    pub fn value(&self, comp: &Computer) -> i32 {
+        match self {
+            Operand::Register(register) => comp.register(*register),
+            Operand::Value(value) => *value,
+        }
+    }
+
+    /// Returns the register this operand names.  Panics if the operand is a value, which the
+    /// opcode table rules out for operands declared as registers.
+    pub fn register(&self) -> Register {
+        match self {
+            Operand::Register(register) => *register,
+            Operand::Value(_) => panic!("Operand {:?} isn't a register.", self),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Value(value) => write!(f, "{:+}", value),
+        }
+    }
+}
+
+/// OperandKind is the kind of operand an opcode accepts in one position.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum OperandKind {
+    /// A register like 'a' or 'acc'.
+    Register,
+
+    /// A signed value like +3 or -4.
+    Value,
+
+    /// Either a register or a signed value.
+    Any,
+
+    /// A signed offset from the instruction to the one to jump to, like +3 or -4.  Parses like
+    /// a value, but disassembly can show it as a label.
+    Offset,
+}
+
+impl OperandKind {
+    /// Parses an operand of this kind.
+    fn parse(&self, s: &str) -> Result<Operand, ParseError> {
+        let starts_with_letter = s.starts_with(|c: char| c.is_ascii_lowercase());
+
+        match self {
+            OperandKind::Register => s.parse().map(Operand::Register),
+            OperandKind::Any if starts_with_letter => s.parse().map(Operand::Register),
+            OperandKind::Value | OperandKind::Any | OperandKind::Offset => s.parse().map(Operand::Value)
+                .map_err(|_| ParseError::new(s, 1, self.expected())),
+        }
+    }
+
+    /// Describes this kind of operand for parse errors.
+    fn expected(&self) -> &'static str {
+        match self {
+            OperandKind::Register => "a register from 'a' to 'h', or 'acc'",
+            OperandKind::Value | OperandKind::Offset => "a signed argument like +3 or -4",
+            OperandKind::Any => "a register or a signed argument",
+        }
+    }
+}
+
+/// Flow is where the computer goes after running an instruction.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Flow {
+    /// Continue with the next instruction.
+    Next,
+
+    /// Jump by the given offset from the current instruction.
+    Jump(i32),
+
+    /// Stop the computer.
+    Halt,
+}
+
+/// Opcode defines an instruction beyond the original 'nop', 'acc', and 'jmp': its mnemonic,
+/// the operands it takes, and what it does to a computer.  Opcodes are statics so instructions
+/// can refer to them while staying Copy.
+pub struct Opcode {
+    pub name: &'static str,
+    pub operands: &'static [OperandKind],
+    pub run: fn(&mut Computer, &[Operand]) -> Flow,
+}
+
+impl Opcode {
+    /// The most operands an opcode can take, so instructions can hold them without allocating.
+    pub const MAX_OPERANDS: usize = 2;
+}
+
+impl Debug for Opcode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl PartialEq for Opcode {
+    fn eq(&self, other: &Opcode) -> bool {
+        self.name == other.name && self.operands == other.operands
+    }
+}
+
+impl Eq for Opcode {}
+
+/// 'set r x' sets register r to x.
+pub static SET: Opcode = Opcode {
+    name: "set",
+    operands: &[OperandKind::Register, OperandKind::Any],
+    run: |comp, operands| {
+        comp.set_register(operands[0].register(), operands[1].value(comp));
+        Flow::Next
+    },
+};
+
+/// 'add r x' adds x to register r.
+pub static ADD: Opcode = Opcode {
+    name: "add",
+    operands: &[OperandKind::Register, OperandKind::Any],
+    run: |comp, operands| arithmetic(comp, operands, i32::wrapping_add),
+};
+
+/// 'sub r x' subtracts x from register r.
+pub static SUB: Opcode = Opcode {
+    name: "sub",
+    operands: &[OperandKind::Register, OperandKind::Any],
+    run: |comp, operands| arithmetic(comp, operands, i32::wrapping_sub),
+};
+
+/// 'mul r x' multiplies register r by x.
+pub static MUL: Opcode = Opcode {
+    name: "mul",
+    operands: &[OperandKind::Register, OperandKind::Any],
+    run: |comp, operands| arithmetic(comp, operands, i32::wrapping_mul),
+};
+
+/// 'jz r +n' jumps by n if register r is zero.
+pub static JZ: Opcode = Opcode {
+    name: "jz",
+    operands: &[OperandKind::Register, OperandKind::Offset],
+    run: |comp, operands| conditional_jump(comp, operands, |value| value == 0),
+};
+
+/// 'jnz r +n' jumps by n if register r isn't zero.
+pub static JNZ: Opcode = Opcode {
+    name: "jnz",
+    operands: &[OperandKind::Register, OperandKind::Offset],
+    run: |comp, operands| conditional_jump(comp, operands, |value| value != 0),
+};
+
+/// 'hlt' stops the computer.
+pub static HLT: Opcode = Opcode {
+    name: "hlt",
+    operands: &[],
+    run: |_, _| Flow::Halt,
+};
+
+/// Replaces the register in the first operand with the result of combining it with the second.
+fn arithmetic(comp: &mut Computer, operands: &[Operand], op: fn(i32, i32) -> i32) -> Flow {
+    let register = operands[0].register();
+    let value = op(comp.register(register), operands[1].value(comp));
+    comp.set_register(register, value);
+
+    Flow::Next
+}
+
+/// Jumps by the second operand if the first operand's register passes the test.
+fn conditional_jump(comp: &mut Computer, operands: &[Operand], test: fn(i32) -> bool) -> Flow {
+    if test(operands[0].value(comp)) {
+        Flow::Jump(operands[1].value(comp))
+    } else {
+        Flow::Next
+    }
+}
+
+/// OpcodeTable is the set of opcodes a program can use, on top of 'nop', 'acc', and 'jmp'.
+#[derive(Debug, Clone)]
+pub struct OpcodeTable {
+    opcodes: Vec<&'static Opcode>,
+}
+
+impl Default for OpcodeTable {
+    fn default() -> Self {
+        OpcodeTable::standard()
+    }
+}
+
+impl OpcodeTable {
+    /// Opcodes that can't be replaced, since part 2 toggles between them.
+    const BUILT_IN: [&'static str; 3] = ["nop", "acc", "jmp"];
+
+    /// Returns a table with the original instructions and no others.
+    pub fn original() -> OpcodeTable {
+        OpcodeTable { opcodes: Vec::new() }
+    }
+
+    /// Returns a table with the standard opcodes: set, add, sub, mul, jz, jnz, and hlt.
+    pub fn standard() -> OpcodeTable {
+        OpcodeTable { opcodes: vec![&SET, &ADD, &SUB, &MUL, &JZ, &JNZ, &HLT] }
+    }
+
+    /// Returns this table with the given opcode added, replacing any opcode with the same name.
+    /// Panics if the opcode would replace 'nop', 'acc', or 'jmp', or takes more than
+    /// Opcode::MAX_OPERANDS operands.
+    pub fn with(mut self, opcode: &'static Opcode) -> OpcodeTable {
+        assert!(!OpcodeTable::BUILT_IN.contains(&opcode.name), "Opcode '{}' is built in.", opcode.name);
+        assert!(
+            opcode.operands.len() <= Opcode::MAX_OPERANDS,
+            "Opcode '{}' takes {} operands, but opcodes can take at most {}.", opcode.name, opcode.operands.len(), Opcode::MAX_OPERANDS,
+        );
+
+        self.opcodes.retain(|existing| existing.name != opcode.name);
+        self.opcodes.push(opcode);
+
+        self
+    }
+
+    /// Returns the opcode with the given name, if it's in this table.
+    pub fn get(&self, name: &str) -> Option<&'static Opcode> {
+        self.opcodes.iter().find(|opcode| opcode.name == name).copied()
+    }
+
+    /// Parses an instruction, which is an opcode followed by its operands separated by spaces,
+    /// like 'jmp -3' or 'mul a b'.
+    pub fn parse(&self, s: &str) -> Result<Instruction, ParseError> {
+        let (op, rest) = match s.split_once(' ') {
+            Some((op, rest)) => (op, Some(rest)),
+            None => (s, None),
+        };
+
+        if OpcodeTable::BUILT_IN.contains(&op) {
+            return Instruction::parse_built_in(s, op, rest);
+        }
+
+        let opcode = self.get(op).ok_or_else(|| ParseError::new(s, 1, &self.expected()))?;
+
+        let mut operands = [Operand::Value(0); Opcode::MAX_OPERANDS];
+        let mut column = op.len() + 1;
+        let mut args = rest.map(|rest| rest.split(' ')).into_iter().flatten();
+
+        for (i, kind) in opcode.operands.iter().enumerate() {
+            let arg = args.next().ok_or_else(|| ParseError::new(s, s.len() + 1, "a space followed by an argument"))?;
+            operands[i] = kind.parse(arg).map_err(|e| e.offset(column, s))?;
+            column += arg.len() + 1;
+        }
+
+        if args.next().is_some() || (opcode.operands.is_empty() && rest.is_some()) {
+            return Err(ParseError::new(s, column + 1, "end of instruction"));
+        }
+
+        Ok(Instruction::Op(opcode, operands))
+    }
+
+    /// Describes the opcodes in this table for parse errors, like "'nop', 'acc', or 'jmp'".
+    fn expected(&self) -> String {
+        let names: Vec<String> = OpcodeTable::BUILT_IN.iter().copied()
+            .chain(self.opcodes.iter().map(|opcode| opcode.name))
+            .map(|name| format!("'{}'", name))
+            .collect();
+
+        match names.split_last() {
+            Some((last, rest)) if rest.len() > 1 => format!("{}, or {}", rest.join(", "), last),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_register() {
+        assert_eq!(Ok(Register::Acc), "acc".parse());
+        assert_eq!(Ok(Register::General(0)), "a".parse());
+        assert_eq!(Ok(Register::General(7)), "h".parse());
+        assert!("i".parse::<Register>().is_err());
+        assert!("ab".parse::<Register>().is_err());
+    }
+
+    #[test]
+    fn parse_op() {
+        let table = OpcodeTable::standard();
+
+        assert_eq!(Ok(Instruction::Op(&SET, [Operand::Register(Register::General(1)), Operand::Value(-3)])), table.parse("set b -3"));
+        assert_eq!(Ok(Instruction::Op(&MUL, [Operand::Register(Register::Acc), Operand::Register(Register::General(2))])), table.parse("mul acc c"));
+        assert_eq!(Ok(Instruction::Op(&HLT, [Operand::Value(0); Opcode::MAX_OPERANDS])), table.parse("hlt"));
+        assert_eq!(Ok(Instruction::Jmp(-3)), table.parse("jmp -3"));
+    }
+
+    #[test]
+    fn parse_invalid_op() {
+        let table = OpcodeTable::standard();
+
+        assert_eq!(Err(ParseError::new("mul +3 a", 5, "a register from 'a' to 'h', or 'acc'")), table.parse("mul +3 a"));
+        assert_eq!(Err(ParseError::new("jz a b", 6, "a signed argument like +3 or -4")), table.parse("jz a b"));
+        assert_eq!(Err(ParseError::new("add a", 6, "a space followed by an argument")), table.parse("add a"));
+        assert_eq!(Err(ParseError::new("hlt +1", 5, "end of instruction")), table.parse("hlt +1"));
+        assert_eq!(Err(ParseError::new("set a +1 +2", 10, "end of instruction")), table.parse("set a +1 +2"));
+        assert_eq!(Err(ParseError::new("set a +1", 1, "'nop', 'acc', or 'jmp'")), OpcodeTable::original().parse("set a +1"));
+    }
+
+    #[test]
+    fn custom_opcode() {
+        // 'dbl r' doubles a register.
+        static DBL: Opcode = Opcode {
+            name: "dbl",
+            operands: &[OperandKind::Register],
+            run: |comp, operands| {
+                let register = operands[0].register();
+                comp.set_register(register, comp.register(register) * 2);
+                Flow::Next
+            },
+        };
+
+        let table = OpcodeTable::standard().with(&DBL);
+        let mut comp = Computer::read_with("set a +3\ndbl a\nacc +1\ndbl acc\n".as_bytes(), &table).unwrap();
+        comp.run_until_loop();
+
+        assert_eq!(6, comp.register(Register::General(0)));
+        assert_eq!(2, comp.acc);
+    }
+
+    #[test]
+    #[should_panic(expected = "Opcode 'mad' takes 3 operands, but opcodes can take at most 2.")]
+    fn custom_opcode_too_many_operands() {
+        // 'mad r x y' adds x times y to a register.
+        static MAD: Opcode = Opcode {
+            name: "mad",
+            operands: &[OperandKind::Register, OperandKind::Any, OperandKind::Any],
+            run: |comp, operands| {
+                let register = operands[0].register();
+                comp.set_register(register, comp.register(register) + operands[1].value(comp) * operands[2].value(comp));
+                Flow::Next
+            },
+        };
+
+        OpcodeTable::standard().with(&MAD);
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+use serde::{Serialize, Serializer};
+
+use crate::Instruction;
+
+/// TraceStep is one instruction run by a computer, along with the accumulator before and after.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct TraceStep {
+    pub pc: usize,
+    #[serde(serialize_with = "display")]
+    pub instruction: Instruction,
+    pub acc_before: i32,
+    pub acc_after: i32,
+}
+
+/// LoopInstruction is an instruction in a loop, along with where it is in the program.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct LoopInstruction {
+    pub pc: usize,
+    #[serde(serialize_with = "display")]
+    pub instruction: Instruction,
+}
+
+/// Cycle is the loop that stopped a program: running the instructions in order from the entry
+/// pc leads back to the entry pc.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct Cycle {
+    pub entry: usize,
+    pub instructions: Vec<LoopInstruction>,
+}
+
+/// Trace is every instruction a computer ran until it finished or was about to repeat an
+/// instruction, along with the loop if it found one.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+    pub cycle: Option<Cycle>,
+}
+
+impl Trace {
+    /// Returns a trace made up of the given steps, where the loop (if any) starts at the
+    /// given step.
+    pub(crate) fn new(steps: Vec<TraceStep>, loop_start: Option<usize>) -> Trace {
+        let cycle = loop_start.map(|start| Cycle {
+            entry: steps[start].pc,
+            instructions: steps[start..].iter()
+                .map(|step| LoopInstruction { pc: step.pc, instruction: step.instruction })
+                .collect(),
+        });
+
+        Trace { steps, cycle }
+    }
+
+    /// Returns whether the program ran into a loop, rather than finishing.
+    pub fn looped(&self) -> bool {
+        self.cycle.is_some()
+    }
+
+    /// Returns the trace as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Returns the trace as CSV with one row per step.  The 'in_loop' column marks the steps
+    /// that make up the cycle.
+    pub fn to_csv(&self) -> String {
+        let loop_start = self.steps.len() - self.cycle.as_ref().map_or(0, |cycle| cycle.instructions.len());
+
+        let mut csv = String::from("step,pc,instruction,acc_before,acc_after,in_loop\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                i, step.pc, step.instruction, step.acc_before, step.acc_after, i >= loop_start,
+            ));
+        }
+
+        csv
+    }
+}
+
+/// Serializes a value as its Display string, so instructions look the way they're written.
+fn display<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Computer;
+
+    use super::*;
+
+    #[test]
+    fn trace_sample() {
+        let trace = Computer::load("sample.txt").unwrap().trace_until_loop();
+
+        let pcs: Vec<usize> = trace.steps.iter().map(|step| step.pc).collect();
+        assert_eq!(vec![0, 1, 2, 6, 7, 3, 4], pcs);
+        assert_eq!(TraceStep { pc: 6, instruction: Instruction::Acc(1), acc_before: 1, acc_after: 2 }, trace.steps[3]);
+
+        let cycle = trace.cycle.unwrap();
+        assert_eq!(1, cycle.entry);
+        assert_eq!(vec![1, 2, 6, 7, 3, 4], cycle.instructions.iter().map(|i| i.pc).collect::<Vec<_>>());
+        assert_eq!(Instruction::Jmp(-3), cycle.instructions[5].instruction);
+    }
+
+    #[test]
+    fn trace_finished() {
+        let mut computer = Computer::read("nop +0\nacc +1\njmp +2\nacc +5\nacc +2\n".as_bytes()).unwrap();
+        let trace = computer.trace_until_loop();
+
+        assert!(!trace.looped());
+        assert_eq!(4, trace.steps.len());
+        assert_eq!(3, computer.acc);
+    }
+
+    #[test]
+    fn trace_json() {
+        let trace = Computer::read("acc +2\njmp -1\n".as_bytes()).unwrap().trace_until_loop();
+
+        let json: serde_json::Value = serde_json::from_str(&trace.to_json()).unwrap();
+        assert_eq!(serde_json::json!({
+            "steps": [
+                { "pc": 0, "instruction": "acc +2", "acc_before": 0, "acc_after": 2 },
+                { "pc": 1, "instruction": "jmp -1", "acc_before": 2, "acc_after": 2 },
+            ],
+            "cycle": {
+                "entry": 0,
+                "instructions": [
+                    { "pc": 0, "instruction": "acc +2" },
+                    { "pc": 1, "instruction": "jmp -1" },
+                ],
+            },
+        }), json);
+    }
+
+    #[test]
+    fn trace_csv() {
+        let trace = Computer::read("nop +0\nacc -3\njmp -1\n".as_bytes()).unwrap().trace_until_loop();
+
+        assert_eq!(
+            "step,pc,instruction,acc_before,acc_after,in_loop\n\
+             0,0,nop +0,0,0,false\n\
+             1,1,acc -3,0,-3,true\n\
+             2,2,jmp -1,-3,-3,true\n",
+            trace.to_csv(),
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::io::BufRead;
+use itertools::Itertools;
+use common::{LoadError, Solution};
+
+/// Loads numbers from the given file.
+pub fn load_nums(filename: &str) -> Result<Vec<i64>, LoadError> {
+    read_nums(common::open(filename)?)
+}
+
+/// Reads numbers from the given reader, one per line.
+pub fn read_nums<R: BufRead>(reader: R) -> Result<Vec<i64>, LoadError> {
+    common::parse_lines(reader)
+}
+
+/// Finds the first number in the list that isn't a sum of two previous numbers, or returns an
+/// error if they all are.
+pub fn first_non_sum(nums: &[i64], preamble: usize) -> Result<i64, LoadError> {
+    for i in preamble..nums.len() {
+        let num = nums[i];
+        let is_sum = nums[(i-preamble)..].iter()
+            .combinations(2)
+            .any(|combo| combo[0] + combo[1] == nums[i]);
+
+        if !is_sum {
+            return Ok(num);
+        }
+    }
+
+    Err(LoadError::invalid("All numbers are sums."))
+}
+
+/// Finds the sum of the smallest and largest numbers in a contiguous sequence that sums to
+/// the given number, or returns an error if no sequence sums to it.
+pub fn contiguous_sum(nums: &[i64], num: i64) -> Result<i64, LoadError> {
+    for i in 0..nums.len() {
+        for j in i + 1 .. nums.len() {
+            let sum: i64 = nums[i..j].iter().sum();
+
+            if sum == num {
+                return Ok(nums[i..j].iter().min().unwrap() + nums[i..j].iter().max().unwrap());
+            } else if sum > num {
+                continue;
+            }
+        }
+    }
+
+    Err(LoadError::Invalid(format!("No sequence sums to {}.", num)))
+}
+
+pub struct Day9 {
+    nums: Vec<i64>,
+    preamble: usize,
+}
+
+impl Solution for Day9 {
+    type Part1 = Result<i64, LoadError>;
+    type Part2 = Result<i64, LoadError>;
+
+    fn parse(input: &str) -> Result<Self, LoadError> {
+        Ok(Day9 { nums: read_nums(input.as_bytes())?, preamble: 25 })
+    }
+
+    fn part1(&self) -> Result<i64, LoadError> {
+        first_non_sum(&self.nums, self.preamble)
+    }
+
+    fn part2(&self) -> Result<i64, LoadError> {
+        contiguous_sum(&self.nums, self.part1()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_non_sum_sample() {
+        let nums = load_nums("sample.txt").unwrap();
+
+        assert_eq!(127, first_non_sum(&nums, 5).unwrap())
+    }
+
+    #[test]
+    fn contiguous_sum_sample() {
+        let nums = load_nums("sample.txt").unwrap();
+
+        assert_eq!(62, contiguous_sum(&nums, 127).unwrap());
+    }
+
+    #[test]
+    fn solution_sample() {
+        let day = Day9 { nums: load_nums("sample.txt").unwrap(), preamble: 5 };
+
+        assert_eq!(127, day.part1().unwrap());
+        assert_eq!(62, day.part2().unwrap());
+    }
+
+    #[test]
+    fn solution_all_sums() {
+        let day = Day9::parse("").unwrap();
+
+        assert_eq!("Invalid input: All numbers are sums.", day.part1().unwrap_err().to_string());
+        assert_eq!("Invalid input: All numbers are sums.", day.part2().unwrap_err().to_string());
+        assert_eq!("Invalid input: No sequence sums to 3.", contiguous_sum(&[1, 5, 7], 3).unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,415 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::BufRead;
+use std::ops::{Index, IndexMut};
+
+use common::{LoadError, ParseError};
+
+/// Edges determine what happens to positions that fall off the side of a grid.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Edges {
+    /// Positions outside of the grid don't exist.
+    Bounded,
+
+    /// The grid repeats infinitely to the left and right, but rows outside of it don't exist.
+    WrapHorizontal,
+
+    /// The grid repeats infinitely in every direction.
+    Wrap,
+}
+
+/// Neighborhood is the set of squares that count as neighbors of a square.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Neighborhood {
+    /// Squares directly above, below, left, and right.
+    Four,
+
+    /// Squares that touch, including diagonals.
+    Eight,
+
+    /// The six hexagons around a hexagon stored in axial coordinates, where each row is shifted
+    /// half a hexagon right of the row above it.  A hexagon touches the squares to its left and
+    /// right, above and above to the right, and below and below to the left.
+    Hex,
+}
+
+impl Neighborhood {
+    /// Returns the (row, col) offsets from a square to its neighbors.
+    pub fn directions(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Neighborhood::Eight => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+            Neighborhood::Hex => &[(-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0)],
+        }
+    }
+}
+
+/// Transform is one of the 8 ways to rotate and flip a grid (the dihedral group of a square).
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Transform {
+    Identity,
+    /// Rotates 90 degrees clockwise.
+    Rotate90,
+    Rotate180,
+    /// Rotates 270 degrees clockwise, or 90 degrees counterclockwise.
+    Rotate270,
+    /// Mirrors left to right.
+    FlipHorizontal,
+    /// Mirrors top to bottom.
+    FlipVertical,
+    /// Mirrors across the diagonal from the top left to the bottom right.
+    Transpose,
+    /// Mirrors across the diagonal from the top right to the bottom left.
+    AntiTranspose,
+}
+
+impl Transform {
+    /// Every transform, starting with the identity.
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity, Transform::Rotate90, Transform::Rotate180, Transform::Rotate270,
+        Transform::FlipHorizontal, Transform::FlipVertical, Transform::Transpose, Transform::AntiTranspose,
+    ];
+
+    /// Returns whether this transform swaps a grid's width and height.
+    fn swaps_sides(&self) -> bool {
+        matches!(self, Transform::Rotate90 | Transform::Rotate270 | Transform::Transpose | Transform::AntiTranspose)
+    }
+
+    /// Returns the position in a grid with the given width and height that ends up at
+    /// (row, col) after this transform.
+    fn source(&self, row: usize, col: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::Identity => (row, col),
+            Transform::Rotate90 => (height - 1 - col, row),
+            Transform::Rotate180 => (height - 1 - row, width - 1 - col),
+            Transform::Rotate270 => (col, width - 1 - row),
+            Transform::FlipHorizontal => (row, width - 1 - col),
+            Transform::FlipVertical => (height - 1 - row, col),
+            Transform::Transpose => (col, row),
+            Transform::AntiTranspose => (height - 1 - col, width - 1 - row),
+        }
+    }
+}
+
+/// Grid is a rectangle of squares, addressed by row and column from the top left corner.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    edges: Edges,
+    squares: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Returns a bounded grid with every square set to the given value.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid { width, height, edges: Edges::Bounded, squares: vec![value; width * height] }
+    }
+
+    /// Returns a copy of this grid, rotated or flipped by the given transform.
+    pub fn transform(&self, transform: Transform) -> Grid<T> {
+        let (width, height) = if transform.swaps_sides() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+
+        let mut squares = Vec::with_capacity(self.squares.len());
+        for row in 0..height {
+            for col in 0..width {
+                let (source_row, source_col) = transform.source(row, col, self.width, self.height);
+                squares.push(self[(source_row, source_col)].clone());
+            }
+        }
+
+        Grid { width, height, edges: self.edges, squares }
+    }
+
+    /// Returns the part of this grid with the given size, starting at the given row and column.
+    pub fn sub_grid(&self, row: usize, col: usize, width: usize, height: usize) -> Grid<T> {
+        let squares = (row..row + height)
+            .flat_map(|r| (col..col + width).map(move |c| (r, c)))
+            .map(|position| self[position].clone())
+            .collect();
+
+        Grid { width, height, edges: self.edges, squares }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Returns a bounded grid made up of the given rows.  Panics if the rows aren't all
+    /// the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "Grid rows must all be {} squares", width);
+
+        Grid { width, height, edges: Edges::Bounded, squares: rows.into_iter().flatten().collect() }
+    }
+
+    /// Reads a bounded grid from the given reader, one row per line.  Each character is parsed
+    /// into a square, and characters that don't parse are reported as something other than
+    /// the expected form.  Every row must be as wide as the first.
+    pub fn read<R, F>(reader: R, expected: &str, parse: F) -> Result<Grid<T>, LoadError>
+        where R: BufRead, F: Fn(char) -> Option<T> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+
+        for (row, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            let squares = line.chars().enumerate()
+                .map(|(col, c)| parse(c).ok_or_else(|| ParseError::new(&line, col + 1, expected).at_line(row + 1)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+
+            if let Some(first) = rows.first() {
+                if squares.len() != first.len() {
+                    let column = squares.len().min(first.len()) + 1;
+                    let expected = format!("{} squares", first.len());
+
+                    return Err(ParseError::new(&line, column, &expected).at_line(row + 1).into());
+                }
+            }
+
+            rows.push(squares);
+        }
+
+        Ok(Grid::from_rows(rows))
+    }
+
+    /// Returns this grid with the given edge behavior.
+    pub fn with_edges(self, edges: Edges) -> Grid<T> {
+        Grid { edges, ..self }
+    }
+
+    /// Returns the number of columns in this grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows in this grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the square at the given row and column, or None if it's off the edge of the grid.
+    /// Row and column can be negative or past the end of the grid if the grid wraps.
+    pub fn get(&self, row: isize, col: isize) -> Option<&T> {
+        self.position(row, col).map(|position| &self[position])
+    }
+
+    /// Resolves a row and column that may be off the edge of the grid to a position in the grid,
+    /// or None if it's off the edge of a grid that doesn't wrap that way.
+    pub fn position(&self, row: isize, col: isize) -> Option<(usize, usize)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        let wrap = |value: isize, size: usize| value.rem_euclid(size as isize) as usize;
+        let bound = |value: isize, size: usize| if value >= 0 && (value as usize) < size { Some(value as usize) } else { None };
+
+        match self.edges {
+            Edges::Bounded => Some((bound(row, self.height)?, bound(col, self.width)?)),
+            Edges::WrapHorizontal => Some((bound(row, self.height)?, wrap(col, self.width))),
+            Edges::Wrap => Some((wrap(row, self.height), wrap(col, self.width))),
+        }
+    }
+
+    /// Returns the positions of the neighbors of the given square.
+    pub fn neighbors(&self, row: usize, col: usize, neighborhood: Neighborhood) -> Vec<(usize, usize)> {
+        neighborhood.directions().iter()
+            .filter_map(|(row_dir, col_dir)| self.position(row as isize + row_dir, col as isize + col_dir))
+            .collect()
+    }
+
+    /// Looks from the given square in a direction, skipping over squares that can be seen through,
+    /// and returns the first square that can't be.  Returns None if the ray leaves the grid, or
+    /// if a wrapping grid has nothing but see-through squares in that direction.
+    pub fn cast<F>(&self, row: usize, col: usize, direction: (isize, isize), see_through: F) -> Option<&T>
+        where F: Fn(&T) -> bool {
+        let (row_dir, col_dir) = direction;
+        if row_dir == 0 && col_dir == 0 {
+            return None;
+        }
+
+        // A ray through a wrapping grid has to repeat after visiting every square.
+        for dist in 1..=(self.width * self.height) as isize {
+            let square = self.get(row as isize + row_dir * dist, col as isize + col_dir * dist)?;
+
+            if !see_through(square) {
+                return Some(square);
+            }
+        }
+
+        None
+    }
+
+    /// Returns every position in this grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Returns every square in this grid, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.squares.iter()
+    }
+
+    /// Returns the rows of this grid, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a width of 0, but a grid without columns has no squares anyway.
+        self.squares.chunks(self.width.max(1))
+    }
+
+    /// Returns a grid the same shape as this one, with each square converted by the given function.
+    pub fn map<U, F>(&self, f: F) -> Grid<U> where F: Fn(&T) -> U {
+        Grid { width: self.width, height: self.height, edges: self.edges, squares: self.squares.iter().map(f).collect() }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Returns the square at the given (row, col), which must be inside the grid.
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.height && col < self.width, "({}, {}) is outside of the grid", row, col);
+
+        &self.squares[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.height && col < self.width, "({}, {}) is outside of the grid", row, col);
+
+        &mut self.squares[row * self.width + col]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    /// Renders the grid one row per line.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for square in row {
+                write!(f, "{}", square)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::read("..#\n#..\n.##\n##.\n".as_bytes(), "'#' or '.'", |c| Some(c).filter(|c| *c == '#' || *c == '.')).unwrap()
+    }
+
+    #[test]
+    fn read() {
+        let grid = sample();
+
+        assert_eq!(3, grid.width());
+        assert_eq!(4, grid.height());
+        assert_eq!('#', grid[(0, 2)]);
+        assert_eq!('.', grid[(3, 2)]);
+    }
+
+    #[test]
+    fn read_invalid() {
+        let parse = |c| Some(c).filter(|c| *c == '#' || *c == '.');
+
+        match Grid::read("..#\n#x.\n".as_bytes(), "'#' or '.'", parse) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("#x.", 2, "'#' or '.'").at_line(2), e),
+            _ => panic!("Expected a parse error."),
+        }
+
+        match Grid::read("..#\n#.\n".as_bytes(), "'#' or '.'", parse) {
+            Err(LoadError::Parse(e)) => assert_eq!(ParseError::new("#.", 3, "3 squares").at_line(2), e),
+            _ => panic!("Expected a parse error."),
+        }
+    }
+
+    #[test]
+    fn get_edges() {
+        let grid = sample();
+        assert_eq!(Some(&'#'), grid.get(1, 0));
+        assert_eq!(None, grid.get(1, 3));
+        assert_eq!(None, grid.get(-1, 0));
+
+        let grid = sample().with_edges(Edges::WrapHorizontal);
+        assert_eq!(Some(&'#'), grid.get(1, 3));
+        assert_eq!(Some(&'#'), grid.get(0, -1));
+        assert_eq!(None, grid.get(4, 0));
+
+        let grid = sample().with_edges(Edges::Wrap);
+        assert_eq!(Some(&'#'), grid.get(4, 2));
+        assert_eq!(Some(&'#'), grid.get(-1, -3));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = sample();
+
+        assert_eq!(vec![(0, 1), (1, 0)], grid.neighbors(0, 0, Neighborhood::Four));
+        assert_eq!(vec![(0, 1), (1, 0), (1, 1)], grid.neighbors(0, 0, Neighborhood::Eight));
+        assert_eq!(8, grid.neighbors(1, 1, Neighborhood::Eight).len());
+        assert_eq!(vec![(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1)], grid.neighbors(1, 1, Neighborhood::Hex));
+
+        let grid = sample().with_edges(Edges::Wrap);
+        assert_eq!(vec![(3, 0), (0, 2), (0, 1), (1, 0)], grid.neighbors(0, 0, Neighborhood::Four));
+    }
+
+    #[test]
+    fn cast() {
+        let grid = sample();
+        let floor = |c: &char| *c == '.';
+
+        assert_eq!(Some(&'#'), grid.cast(0, 0, (0, 1), floor));
+        assert_eq!(Some(&'#'), grid.cast(0, 0, (1, 1), floor));
+        assert_eq!(None, grid.cast(0, 1, (-1, 0), floor));
+        assert_eq!(None, grid.cast(0, 0, (0, 0), floor));
+
+        let grid = Grid::from_rows(vec![vec!['.', '.']]).with_edges(Edges::Wrap);
+        assert_eq!(None, grid.cast(0, 0, (0, 1), floor));
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = sample();
+
+        assert_eq!(grid, grid.transform(Transform::Identity));
+        assert_eq!("#.#.\n##..\n.#.#\n", grid.transform(Transform::Rotate90).to_string());
+        assert_eq!(".##\n##.\n..#\n#..\n", grid.transform(Transform::Rotate180).to_string());
+        assert_eq!("#.#.\n..##\n.#.#\n", grid.transform(Transform::Rotate270).to_string());
+        assert_eq!("#..\n..#\n##.\n.##\n", grid.transform(Transform::FlipHorizontal).to_string());
+        assert_eq!("##.\n.##\n#..\n..#\n", grid.transform(Transform::FlipVertical).to_string());
+        assert_eq!(".#.#\n..##\n#.#.\n", grid.transform(Transform::Transpose).to_string());
+        assert_eq!(".#.#\n##..\n#.#.\n", grid.transform(Transform::AntiTranspose).to_string());
+    }
+
+    #[test]
+    fn transforms_are_distinct() {
+        let grid = sample();
+        let transformed: Vec<Grid<char>> = Transform::ALL.iter().map(|&t| grid.transform(t)).collect();
+
+        for i in 0..transformed.len() {
+            for j in i + 1..transformed.len() {
+                assert_ne!(transformed[i], transformed[j], "{:?} and {:?}", Transform::ALL[i], Transform::ALL[j]);
+            }
+        }
+    }
+
+    #[test]
+    fn sub_grid() {
+        assert_eq!("..\n##\n", sample().sub_grid(1, 1, 2, 2).to_string());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("..#\n#..\n.##\n##.\n", sample().to_string());
+    }
+}